@@ -1,12 +1,62 @@
 //! Version 1 of the plow registry service API.
 
+use actix_web::web;
+
 /// Endpoints concerning retrieving or creating artifacts.
 pub mod artifact;
 /// Endpoints concerning getting info about fields and their details.
 pub mod field;
+/// Endpoints concerning syncing indexes of fields.
+pub mod index;
+/// Endpoints concerning organizations and their members.
+pub mod organization;
 /// Module where the response types are defined and utility functions are managed for the api.
 pub mod response;
 /// Endpoints concerning getting info about users and their details.
 pub mod user;
 
-pub mod token;
\ No newline at end of file
+pub mod token;
+
+/// Registers the endpoints of the API, they are served under [`super::Version::V1`].
+///
+/// The endpoints expect a `web::Data<DatabaseConnection>` and a `web::Data<Mutex<AppState>>` as app data
+/// and the [`crate::middlewares::authenticate`] middleware for the ones which need authentication.
+pub fn services(config: &mut web::ServiceConfig) {
+    config
+        .service(
+            web::scope("/artifact")
+                .service(artifact::get_signed_url)
+                .service(artifact::get_signed_url_by_field_hash)
+                .service(artifact::download),
+        )
+        .service(web::scope("/index").service(index::sync_private_index))
+        .service(
+            web::scope("/field")
+                .service(field::submit)
+                .service(field::yank)
+                .service(field::unyank)
+                .service(field::search)
+                .service(field::get_all_categories)
+                .service(field::list_new_and_recent_time_scoped)
+                .service(field::list_new_and_recent_n_fields)
+                .service(field::get_field_details_with_field_id)
+                .service(field::get_fields_which_belong_to_a_user),
+        )
+        .service(
+            web::scope("/user")
+                .service(user::register)
+                .service(user::get_user_details),
+        )
+        .service(
+            web::scope("/organization")
+                .service(organization::create_organization)
+                .service(organization::get_organizations_of_user)
+                .service(organization::add_member),
+        )
+        .service(
+            web::scope("/token")
+                .service(token::generate_api_token)
+                .service(token::get_token_summaries)
+                .service(token::delete_api_token_by_its_id),
+        );
+}
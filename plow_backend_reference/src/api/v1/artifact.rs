@@ -1,21 +1,39 @@
-#![allow(clippy::unused_async)]
+mod types;
 
 use super::response;
-use crate::AppState;
+use crate::{
+    api::{v1::artifact::types::SignedUrlQuery, Version},
+    database,
+    entity::field,
+    env::ARTIFACT_SIGNING_KEY,
+    middlewares::AuthenticatedUser,
+    AppState,
+};
 use actix_http::StatusCode;
-use actix_web::{get, web, HttpResponseBuilder, Responder};
+use actix_web::{dev::ConnectionInfo, get, web, HttpResponse, HttpResponseBuilder, Responder};
 use futures::lock::Mutex;
+use hmac::{Hmac, Mac};
+use plow_package_management::{package::PackageVersion, registry::Registry};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a signed url of an artifact is valid for, in seconds.
+const SIGNED_URL_LIFETIME_SECONDS: i64 = 15 * 60;
 
 /// Gets a signed url for intended artifact for public download functionality.
 ///
+/// The artifact of a field is named after the SHA256 checksum of its contents.
+/// Artifacts of private fields are only signed for their owner.
+///
 /// ## Properties:
 /// - Request type: `GET`
-/// - Needs authentication: **Yes**
-/// - Respects `API_SECURITY` environment variable: **Yes**
+/// - Needs authentication: **Only for private fields**
 /// - Routes:
 ///   - `/v1/artifact/signed-url/{artifact_name}`
 /// - Request Headers:
-///   - `Authorization: Bearer <access_token>` or `Authorization: Basic <base64_encoded_api_token>`
+///   - `Authorization: Basic <base64_encoded_api_token>`
 /// ## Example Successful Response:
 ///  ```json
 ///  {
@@ -27,11 +45,183 @@ use futures::lock::Mutex;
 ///  ```
 #[get("/signed-url/{artifact_name}")]
 pub async fn get_signed_url(
-    _path: web::Path<(String,)>,
-    _data: web::Data<Mutex<AppState>>,
+    connection_info: ConnectionInfo,
+    path: web::Path<(String,)>,
+    user: Option<AuthenticatedUser>,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let artifact_name = path.into_inner().0;
+    let field = field::Entity::find()
+        .filter(field::Column::Artifact.eq(artifact_name.as_str()))
+        .one(database.get_ref())
+        .await;
+    signed_url_of_visible_field(
+        &connection_info,
+        field,
+        &format!("There is no artifact `{artifact_name}`."),
+        user,
+        &database,
+    )
+    .await
+}
+
+/// Gets a signed url for the artifact of a field version, this is how clients download fields.
+///
+/// Artifacts of private fields are only signed for their owner.
+///
+/// ## Properties:
+/// - Request type: `GET`
+/// - Needs authentication: **Only for private fields**
+/// - Routes:
+///   - `/v1/artifact/signed-url-by-field-hash/{field_hash}`
+/// - Request Headers:
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": {
+///      "url": "<a-signed-download-url-for-the-artifact>",
+///    },
+///  }
+///  ```
+#[get("/signed-url-by-field-hash/{field_hash}")]
+pub async fn get_signed_url_by_field_hash(
+    connection_info: ConnectionInfo,
+    path: web::Path<(String,)>,
+    user: Option<AuthenticatedUser>,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let field_hash = path.into_inner().0;
+    let field = field::Entity::find_by_id(field_hash.clone())
+        .one(database.get_ref())
+        .await;
+    signed_url_of_visible_field(
+        &connection_info,
+        field,
+        &format!("There is no field with the hash `{field_hash}`."),
+        user,
+        &database,
+    )
+    .await
+}
+
+/// Downloads an artifact through a url which is signed by one of the signed url endpoints.
+///
+/// ## Properties:
+/// - Request type: `GET`
+/// - Needs authentication: **No**, the url is signed instead
+/// - Routes:
+///   - `/v1/artifact/{artifact_name}?expires=<unix_timestamp>&signature=<signature>`
+/// ## Example Successful Response:
+///   The contents of the field with `Content-Type: text/turtle`.
+#[get("/{artifact_name}")]
+pub async fn download(
+    path: web::Path<(String,)>,
+    query: web::Query<SignedUrlQuery>,
+    database: web::Data<DatabaseConnection>,
+    data: web::Data<Mutex<AppState>>,
 ) -> impl Responder {
-    let response = response::Success::new(Some(response::Data::ResourceUrl {
-        url: "http://example.com",
-    }));
-    HttpResponseBuilder::new(StatusCode::OK).json(response)
+    let artifact_name = path.into_inner().0;
+    if query.expires < chrono::Utc::now().timestamp()
+        || !is_signed(&artifact_name, query.expires, &query.signature)
+    {
+        return response::with_failure_message(
+            "The url of the artifact is not signed by the registry or it has expired.",
+            StatusCode::FORBIDDEN,
+        );
+    }
+    let field = match field::Entity::find()
+        .filter(field::Column::Artifact.eq(artifact_name.as_str()))
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return response::with_failure_message(
+                format!("There is no artifact `{artifact_name}`."),
+                StatusCode::NOT_FOUND,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+
+    let contents = data
+        .lock()
+        .await
+        .registry
+        .retrieve_package(&PackageVersion {
+            package_name: field.name,
+            version: field.version,
+        });
+    match contents {
+        Ok(contents) => HttpResponse::Ok()
+            .content_type("text/turtle")
+            .body(contents),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
+}
+
+/// Responds with a signed url to the artifact of the field if the user may see the field.
+///
+/// Private fields which the user does not own are not found, so that their existence is not revealed.
+async fn signed_url_of_visible_field(
+    connection_info: &ConnectionInfo,
+    field: Result<Option<field::Model>, DbErr>,
+    not_found_message: &str,
+    user: Option<AuthenticatedUser>,
+    database: &DatabaseConnection,
+) -> HttpResponse {
+    let field = match field {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return response::with_failure_message(not_found_message, StatusCode::NOT_FOUND)
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    if !field.public {
+        let visible = match user {
+            Some(AuthenticatedUser(user)) => {
+                database::is_owner(database, field.submitter_id, field.organization_id, user.id)
+                    .await
+            }
+            None => Ok(false),
+        };
+        match visible {
+            Ok(true) => {}
+            Ok(false) => {
+                return response::with_failure_message(not_found_message, StatusCode::NOT_FOUND)
+            }
+            Err(err) => return response::with_error_message(err.to_string()),
+        }
+    }
+
+    let expires = chrono::Utc::now().timestamp() + SIGNED_URL_LIFETIME_SECONDS;
+    let url = format!(
+        "{scheme}://{host}{version}/artifact/{artifact_name}?expires={expires}&signature={signature}",
+        scheme = connection_info.scheme(),
+        host = connection_info.host(),
+        version = Version::V1.as_path(),
+        artifact_name = field.artifact,
+        signature = hex::encode(signature(&field.artifact, expires).finalize().into_bytes()),
+    );
+    HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
+        response::Data::ResourceUrl { url: &url },
+    )))
+}
+
+/// The HMAC which a url of the artifact is signed with until `expires`.
+fn signature(artifact_name: &str, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(ARTIFACT_SIGNING_KEY.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(format!("{artifact_name}:{expires}").as_bytes());
+    mac
+}
+
+fn is_signed(artifact_name: &str, expires: i64, given_signature: &str) -> bool {
+    hex::decode(given_signature).map_or(false, |given_signature| {
+        signature(artifact_name, expires)
+            .verify_slice(&given_signature)
+            .is_ok()
+    })
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// The query of a signed url of an artifact.
+#[derive(Deserialize, Serialize)]
+pub struct SignedUrlQuery {
+    /// Unix timestamp after which the url is not valid anymore.
+    pub expires: i64,
+    /// Hex encoded HMAC-SHA256 of `<artifact_name>:<expires>` with the signing key of the registry.
+    pub signature: String,
+}
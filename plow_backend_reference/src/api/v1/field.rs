@@ -2,12 +2,28 @@
 mod types;
 
 use super::response;
-use crate::{api::v1::field::types::FieldSearchForm, AppState};
+use crate::{
+    api::v1::field::types::{FieldSearchForm, FieldSubmissionForm, SubmissionQuery},
+    database,
+    entity::{field, field_owner, organization, user},
+    middlewares::AuthenticatedUser,
+    AppState,
+};
 use actix_http::StatusCode;
-use actix_web::{get, post, web, HttpResponseBuilder, Responder};
+use actix_multipart::form::MultipartForm;
+use actix_web::{delete, get, post, put, web, HttpResponse, HttpResponseBuilder, Responder};
 
 use crate::api::v1::response::types::{Category, FieldSummary};
 use futures::lock::Mutex;
+use harriet::TurtleDocument;
+use plow_linter::{lint::LintResult, lints::field_manifest_lints, Linter};
+use plow_package_management::{
+    checksum,
+    metadata::OntologyMetadata,
+    package::PackageVersion,
+    registry::{http::field_hash, Registry},
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 
 fn categories() -> Vec<Category> {
     vec![
@@ -253,3 +269,423 @@ pub async fn search(
     let response = response::Success::new(Some(response::Data::FieldSummaries(fields())));
     return HttpResponseBuilder::new(StatusCode::OK).json(response);
 }
+
+/// Submits a field, it is linted with the field manifest lints and stored unless it is a dry run.
+///
+/// A version of a field can only be submitted once.
+/// New versions of a field can only be submitted by its owner, they keep the owner of the first version.
+/// Public fields are committed to the public index and pushed, if the `PUBLIC_INDEX_REMOTE` environment variable is set.
+///
+/// ## Properties:
+/// - Request type: `POST`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/field/submit`
+///   - `/v1/field/submit?dry-run=true`
+/// - Request Headers:
+///   - `Content-Type: multipart/form-data`
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// - Request Parameters:
+///   - `field`: The turtle file of the field.
+///   - `public`: `true` or `false`, private fields are only visible to their owner.
+///   - `owner_plo_id`: The organization which owns the field, optional. The submitter owns it otherwise.
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": null,
+///  }
+///  ```
+/// ## Example Failed Response:
+///  ```json
+///  {
+///    "status": "fail",
+///    "data": {
+///      "failures": [
+///        "<message of a failed lint>",
+///        //...
+///      ],
+///    },
+///  }
+///  ```
+#[post("/submit")]
+pub async fn submit(
+    // Extracted first so that submissions without a valid API token are rejected before their form is read.
+    AuthenticatedUser(user): AuthenticatedUser,
+    query: web::Query<SubmissionQuery>,
+    MultipartForm(submission): MultipartForm<FieldSubmissionForm>,
+    database: web::Data<DatabaseConnection>,
+    data: web::Data<Mutex<AppState>>,
+) -> impl Responder {
+    let field_contents = match std::str::from_utf8(&submission.field.data) {
+        Ok(field_contents) => field_contents,
+        Err(_) => {
+            return response::with_failure_message(
+                "The submitted field is not UTF-8 encoded.",
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    };
+    let document = match TurtleDocument::parse_full(field_contents) {
+        Ok(document) => document,
+        Err(_) => {
+            return response::with_failure_message(
+                "The submitted field is not valid turtle.",
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    };
+
+    let failures = match field_manifest_lint_failures(field_contents) {
+        Ok(failures) => failures,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    if !failures.is_empty() {
+        return HttpResponseBuilder::new(StatusCode::BAD_REQUEST).json(response::Failure::new(
+            response::Data::SubmissionLintingResults { failures },
+        ));
+    }
+
+    let metadata = match OntologyMetadata::try_from(&document) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            return response::with_failure_message(err.to_string(), StatusCode::BAD_REQUEST)
+        }
+    };
+    let package_version: PackageVersion = metadata.into();
+
+    let owner = match owner_of_submission(
+        &database,
+        &user,
+        &package_version.package_name,
+        submission
+            .owner_plo_id
+            .as_ref()
+            .map(|plo_id| plo_id.as_str()),
+    )
+    .await
+    {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+    // Dry runs do not make anyone the owner of a field.
+    let organization_id = if query.dry_run {
+        owner.organization_id
+    } else {
+        match claim_ownership(&database, &user, owner).await {
+            Ok(organization_id) => organization_id,
+            Err(response) => return response,
+        }
+    };
+
+    let state = data.lock().await;
+    if state
+        .registry
+        .get_package_version_metadata(&package_version)
+        .is_ok()
+    {
+        return response::with_failure_message(
+            format!(
+                "Version `{}` of package `{}` is already present in registry.",
+                package_version.version, package_version.package_name
+            ),
+            StatusCode::CONFLICT,
+        );
+    }
+    if query.dry_run {
+        return response::success_with_null_data();
+    }
+
+    // The artifact and the owner of the field are stored before public fields are pushed to the public index,
+    // what was stored is taken back if a later step fails so that the submission can be tried again.
+    if let Err(err) = state.registry.submit_package(field_contents) {
+        return response::with_error_message(err.to_string());
+    }
+    drop(state);
+    let hash = field_hash(&package_version.package_name, &package_version.version);
+    let inserted = (field::ActiveModel {
+        hash: Set(hash.clone()),
+        name: Set(package_version.package_name.clone()),
+        version: Set(package_version.version.clone()),
+        artifact: Set(checksum::compute(field_contents.as_bytes())),
+        public: Set(submission.public.0),
+        submitter_id: Set(user.id),
+        organization_id: Set(organization_id),
+        submitted_at: Set(chrono::Utc::now().to_rfc3339()),
+    })
+    .insert(database.get_ref())
+    .await;
+    if let Err(err) = inserted {
+        let removed = data
+            .lock()
+            .await
+            .registry
+            .remove_package_version(&package_version);
+        return response::with_error_message(match removed {
+            Ok(()) => err.to_string(),
+            Err(remove_err) => format!("{err} The field could not be removed from the registry either. Error: {remove_err}"),
+        });
+    }
+
+    if submission.public.0 {
+        let state = data.lock().await;
+        if let Some(ref public_index) = state.public_index {
+            if let Err(err) = public_index.submit_package(field_contents) {
+                let removed = state.registry.remove_package_version(&package_version);
+                drop(state);
+                let deleted = field::Entity::delete_by_id(hash)
+                    .exec(database.get_ref())
+                    .await;
+                let mut message =
+                    format!("Unable to publish the field to the public index. Error: {err}");
+                if let Err(remove_err) = removed {
+                    message.push_str(&format!(" The field could not be removed from the registry either. Error: {remove_err}"));
+                }
+                if let Err(delete_err) = deleted {
+                    message.push_str(&format!(
+                        " The owner of the field could not be removed either. Error: {delete_err}"
+                    ));
+                }
+                return response::with_error_message(message);
+            }
+        }
+    }
+    response::success_with_null_data()
+}
+
+/// Yanks a version of a field, fields which depend on it keep it only if it is in their lock file.
+///
+/// Only the owner of a field can yank its versions.
+/// Public fields are also yanked in the public index, if there is one.
+///
+/// ## Properties:
+/// - Request type: `DELETE`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/field/{field_hash}/yank`
+/// - Request Headers:
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": null,
+///  }
+///  ```
+#[delete("/{field_hash}/yank")]
+pub async fn yank(
+    path: web::Path<(String,)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+    data: web::Data<Mutex<AppState>>,
+) -> impl Responder {
+    set_yanked(&path.into_inner().0, &user, &database, &data, true).await
+}
+
+/// Takes back the yank of a version of a field, it can be resolved again.
+///
+/// ## Properties:
+/// - Request type: `PUT`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/field/{field_hash}/unyank`
+/// - Request Headers:
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": null,
+///  }
+///  ```
+#[put("/{field_hash}/unyank")]
+pub async fn unyank(
+    path: web::Path<(String,)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+    data: web::Data<Mutex<AppState>>,
+) -> impl Responder {
+    set_yanked(&path.into_inner().0, &user, &database, &data, false).await
+}
+
+/// Sets the `yanked` flag of a field version in the registry and, for public fields, in the public index.
+///
+/// The registry is set back if the public index can not be updated.
+async fn set_yanked(
+    field_hash: &str,
+    user: &user::Model,
+    database: &DatabaseConnection,
+    data: &Mutex<AppState>,
+    yanked: bool,
+) -> HttpResponse {
+    let field = match field::Entity::find_by_id(field_hash.to_owned())
+        .one(database)
+        .await
+    {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return response::with_failure_message(
+                format!("There is no field with the hash `{field_hash}`."),
+                StatusCode::NOT_FOUND,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    match database::is_owner(database, field.submitter_id, field.organization_id, user.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return response::with_failure_message(
+                format!("Only the owner of `{}` can yank its versions.", field.name),
+                StatusCode::FORBIDDEN,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    }
+
+    let package_version = PackageVersion {
+        package_name: field.name,
+        version: field.version,
+    };
+    let state = data.lock().await;
+    if let Err(err) = state.registry.yank(&package_version, yanked) {
+        return response::with_error_message(err.to_string());
+    }
+    if field.public {
+        if let Some(ref public_index) = state.public_index {
+            if let Err(err) = public_index.yank(&package_version, yanked) {
+                // Keep the registry the same as the public index.
+                return response::with_error_message(
+                    match state.registry.yank(&package_version, !yanked) {
+                        Ok(()) => format!("Unable to update the public index. Error: {err}"),
+                        Err(reset_err) => format!("Unable to update the public index. Error: {err} The registry could not be set back either. Error: {reset_err}"),
+                    },
+                );
+            }
+        }
+    }
+    response::success_with_null_data()
+}
+
+/// The owner of a submitted field, the submitter and the organization if it is given for a new field.
+///
+/// Fails with a response if the user may not submit the field for the owner.
+async fn owner_of_submission(
+    database: &DatabaseConnection,
+    user: &user::Model,
+    field_name: &str,
+    owner_plo_id: Option<&str>,
+) -> Result<field_owner::Model, HttpResponse> {
+    let recorded_owner = field_owner::Entity::find_by_id(field_name.to_owned())
+        .one(database)
+        .await
+        .map_err(|err| response::with_error_message(err.to_string()))?;
+    let existing_owner = match recorded_owner {
+        Some(owner) => Some(owner),
+        // Fields which were submitted before their owners were recorded apart keep the owner of their versions.
+        None => field::Entity::find()
+            .filter(field::Column::Name.eq(field_name))
+            .one(database)
+            .await
+            .map_err(|err| response::with_error_message(err.to_string()))?
+            .map(|existing_version| field_owner::Model {
+                name: field_name.to_owned(),
+                submitter_id: existing_version.submitter_id,
+                organization_id: existing_version.organization_id,
+            }),
+    };
+    if let Some(existing_owner) = existing_owner {
+        return verify_owner(database, user, existing_owner).await;
+    }
+
+    let plo_id = match owner_plo_id {
+        Some(plo_id) => plo_id,
+        None => {
+            return Ok(field_owner::Model {
+                name: field_name.to_owned(),
+                submitter_id: user.id,
+                organization_id: None,
+            })
+        }
+    };
+    let organization = organization::Entity::find()
+        .filter(organization::Column::PloId.eq(plo_id))
+        .one(database)
+        .await
+        .map_err(|err| response::with_error_message(err.to_string()))?
+        .ok_or_else(|| {
+            response::with_failure_message(
+                format!("There is no organization `{plo_id}`."),
+                StatusCode::NOT_FOUND,
+            )
+        })?;
+    if !database::is_member(database, organization.id, user.id)
+        .await
+        .map_err(|err| response::with_error_message(err.to_string()))?
+    {
+        return Err(response::with_failure_message(
+            format!("Only members of `{plo_id}` can submit fields for it."),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(field_owner::Model {
+        name: field_name.to_owned(),
+        submitter_id: user.id,
+        organization_id: Some(organization.id),
+    })
+}
+
+/// Records the owner of a submitted field if it is the first submission of it and returns the organization which owns it.
+///
+/// Fails with a response if a concurrent submission recorded another owner which the user is not.
+async fn claim_ownership(
+    database: &DatabaseConnection,
+    user: &user::Model,
+    owner: field_owner::Model,
+) -> Result<Option<i32>, HttpResponse> {
+    let owner = database::claim_field(database, owner)
+        .await
+        .map_err(|err| response::with_error_message(err.to_string()))?;
+    Ok(verify_owner(database, user, owner).await?.organization_id)
+}
+
+/// Fails with a response unless the user owns the field.
+async fn verify_owner(
+    database: &DatabaseConnection,
+    user: &user::Model,
+    owner: field_owner::Model,
+) -> Result<field_owner::Model, HttpResponse> {
+    if !database::is_owner(database, owner.submitter_id, owner.organization_id, user.id)
+        .await
+        .map_err(|err| response::with_error_message(err.to_string()))?
+    {
+        return Err(response::with_failure_message(
+            format!(
+                "Only the owner of `{}` can submit new versions of it.",
+                owner.name
+            ),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(owner)
+}
+
+/// Messages of the field manifest lints which fail for a field.
+fn field_manifest_lint_failures(field_contents: &str) -> anyhow::Result<Vec<String>> {
+    let mut linter = Linter::try_from(field_contents)?;
+    // Fields can not allow away what the registry requires.
+    linter.enforce_all_lints();
+    let lint_set = field_manifest_lints();
+    let set_id = lint_set.id;
+    linter.add_lint_set(lint_set);
+
+    Ok(linter
+        .run_lint_set(set_id)
+        .into_iter()
+        .filter_map(|result| match result {
+            LintResult::Failure(messages) => Some(messages),
+            _ => None,
+        })
+        .flatten()
+        .map(|message| message.text)
+        .collect())
+}
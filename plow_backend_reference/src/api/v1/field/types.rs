@@ -1,3 +1,4 @@
+use actix_multipart::form::{bytes::Bytes, text::Text, MultipartForm};
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 
@@ -37,3 +38,21 @@ pub struct NamespaceAndName {
 pub struct FieldSearchForm {
     pub name: String,
 }
+
+/// The multipart form which a field is submitted with.
+#[derive(MultipartForm)]
+pub struct FieldSubmissionForm {
+    /// The contents of the field, a turtle file.
+    pub field: Bytes,
+    /// Whether the field is public or only visible to its owner.
+    pub public: Text<bool>,
+    /// The organization which owns the field, the submitter owns it otherwise.
+    pub owner_plo_id: Option<Text<String>>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct SubmissionQuery {
+    /// Only checks the field without storing it.
+    #[serde(rename = "dry-run", default)]
+    pub dry_run: bool,
+}
@@ -0,0 +1,125 @@
+use super::response;
+use crate::{database, entity::field, middlewares::AuthenticatedUser, AppState};
+use actix_http::StatusCode;
+use actix_web::{post, web, HttpResponseBuilder, Responder};
+
+use futures::lock::Mutex;
+use plow_package_management::{
+    package::PackageVersion,
+    registry::{
+        http::{DifferenceQuery, PrivateIndex, PrivateIndexes},
+        Registry,
+    },
+};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Syncs the private index of the querying user.
+///
+/// The private index consists of the private fields which the user or one of their organizations owns,
+/// public fields are served through the public index.
+/// Only the entries which are not in the submitted field hashes are sent,
+/// together with the submitted field hashes which are not in the private index anymore.
+///
+/// ## Properties:
+/// - Request type: `POST`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/index/private/sync`
+/// - Request Headers:
+///   - `Content-Type: application/json`
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// - Request Body:
+///   - `existing_local_field_hashes`: Field hashes of the entries which the client has already.
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": {
+///      "indexes": [
+///        {
+///          "name": "<field namespace and name>",
+///          "version": "<field version>",
+///          "cksum": "<SHA256 checksum of the field contents>",
+///          "ontology_iri": "<ontology iri>", // Nullable
+///          "deps": [
+///            {
+///              "name": "<dependency namespace and name>",
+///              "req": "<version requirement>",
+///            },
+///            // ...
+///          ],
+///          "yanked": false,
+///        },
+///        // ...
+///      ],
+///      "removed_field_hashes": [
+///        "<field hash>",
+///        // ...
+///      ],
+///    },
+///  }
+///  ```
+#[post("/private/sync")]
+pub async fn sync_private_index(
+    query: web::Json<DifferenceQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+    data: web::Data<Mutex<AppState>>,
+) -> impl Responder {
+    let organization_ids = match database::organization_ids_of_user(&database, user.id).await {
+        Ok(organization_ids) => organization_ids,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    let visible_fields = match field::Entity::find()
+        .filter(field::Column::Public.eq(false))
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(field::Column::OrganizationId.is_null())
+                        .add(field::Column::SubmitterId.eq(user.id)),
+                )
+                .add(field::Column::OrganizationId.is_in(organization_ids)),
+        )
+        .all(database.get_ref())
+        .await
+    {
+        Ok(visible_fields) => visible_fields,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+
+    let DifferenceQuery {
+        existing_local_field_hashes,
+    } = query.into_inner();
+    let state = data.lock().await;
+    let indexes = match visible_fields
+        .iter()
+        .filter(|field| !existing_local_field_hashes.contains(&field.hash))
+        .map(|field| {
+            state
+                .registry
+                .get_package_version_metadata(&PackageVersion {
+                    package_name: field.name.clone(),
+                    version: field.version.clone(),
+                })
+                .and_then(PrivateIndex::try_from)
+        })
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(indexes) => indexes,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    drop(state);
+    // Fields which were removed or which the user can not see anymore.
+    let removed_field_hashes = existing_local_field_hashes
+        .into_iter()
+        .filter(|hash| !visible_fields.iter().any(|field| &field.hash == hash))
+        .collect();
+
+    HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
+        response::Data::PrivateIndexes(PrivateIndexes {
+            indexes,
+            removed_field_hashes,
+        }),
+    )))
+}
@@ -0,0 +1,241 @@
+pub mod types;
+
+use super::response;
+use crate::{
+    api::v1::{
+        organization::types::{MemberAdditionForm, OrganizationCreationForm},
+        response::types::OrganizationSummary,
+    },
+    database,
+    entity::{organization, organization_member, user},
+    middlewares::AuthenticatedUser,
+};
+use actix_http::StatusCode;
+use actix_web::{get, post, web, HttpResponseBuilder, Responder};
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+};
+
+impl From<organization::Model> for OrganizationSummary {
+    fn from(organization: organization::Model) -> Self {
+        Self {
+            plo_id: organization.plo_id,
+            name: organization.name,
+            created_at: organization.created_at,
+        }
+    }
+}
+
+/// Summaries of the organizations which a user is a member of.
+async fn organizations_of_user(
+    database: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<OrganizationSummary>, DbErr> {
+    let organization_ids = database::organization_ids_of_user(database, user_id).await?;
+    Ok(organization::Entity::find()
+        .filter(organization::Column::Id.is_in(organization_ids))
+        .all(database)
+        .await?
+        .into_iter()
+        .map(OrganizationSummary::from)
+        .collect())
+}
+
+/// Creates an organization, the querying user becomes its first member.
+///
+/// ## Properties:
+/// - Request type: `POST`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/organization/create`
+/// - Request Headers:
+///   - `Content-Type: application/x-www-form-urlencoded`
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// - Request Parameters:
+///   - `plo_id`: The id of the organization, it has to be unique.
+///   - `name`: The readable name of the organization.
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": [
+///      // Organizations which the querying user is a member of.
+///      {
+///        "plo_id": "<organization id>",
+///        "name": "<organization name>",
+///        "created_at": "2020-01-01T00:00:00.000Z",
+///      },
+///      // ...
+///    ],
+///  }
+///  ```
+#[post("/create")]
+pub async fn create_organization(
+    creation: web::Form<OrganizationCreationForm>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let database = database.get_ref();
+    match organization::Entity::find()
+        .filter(organization::Column::PloId.eq(creation.plo_id.as_str()))
+        .one(database)
+        .await
+    {
+        Ok(Some(_)) => {
+            return response::with_failure_message(
+                format!(
+                    "The organization id `{}` is already taken.",
+                    creation.plo_id
+                ),
+                StatusCode::CONFLICT,
+            )
+        }
+        Ok(None) => {}
+        Err(err) => return response::with_error_message(err.to_string()),
+    }
+
+    let created = async {
+        let organization = organization::ActiveModel {
+            plo_id: Set(creation.plo_id.clone()),
+            name: Set(creation.name.clone()),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(database)
+        .await?;
+        organization_member::ActiveModel {
+            organization_id: Set(organization.id),
+            user_id: Set(user.id),
+        }
+        .insert(database)
+        .await?;
+        organizations_of_user(database, user.id).await
+    };
+    match created.await {
+        Ok(organizations) => HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(
+            Some(response::Data::Organizations(organizations)),
+        )),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
+}
+
+/// Gets a list of organizations which the querying user is a member of.
+///
+/// ## Properties:
+/// - Request type: `GET`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/organization/list`
+/// - Request Headers:
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": [
+///      {
+///        "plo_id": "<organization id>",
+///        "name": "<organization name>",
+///        "created_at": "2020-01-01T00:00:00.000Z",
+///      },
+///      // ...
+///    ],
+///  }
+///  ```
+#[get("/list")]
+pub async fn get_organizations_of_user(
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    match organizations_of_user(database.get_ref(), user.id).await {
+        Ok(organizations) => HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(
+            Some(response::Data::Organizations(organizations)),
+        )),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
+}
+
+/// Adds a user to an organization, only members of the organization can add members.
+///
+/// ## Properties:
+/// - Request type: `POST`
+/// - Needs authentication: **Yes**
+/// - Routes:
+///   - `/v1/organization/{plo_id}/member`
+/// - Request Headers:
+///   - `Content-Type: application/x-www-form-urlencoded`
+///   - `Authorization: Basic <base64_encoded_api_token>`
+/// - Request Parameters:
+///   - `user_name`: The name of the user to add.
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": null
+///  }
+///  ```
+#[post("/{plo_id}/member")]
+pub async fn add_member(
+    path: web::Path<(String,)>,
+    addition: web::Form<MemberAdditionForm>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (plo_id,) = path.into_inner();
+    let database = database.get_ref();
+    let organization = match organization::Entity::find()
+        .filter(organization::Column::PloId.eq(plo_id.as_str()))
+        .one(database)
+        .await
+    {
+        Ok(Some(organization)) => organization,
+        Ok(None) => {
+            return response::with_failure_message(
+                format!("There is no organization `{plo_id}`."),
+                StatusCode::NOT_FOUND,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    match database::is_member(database, organization.id, user.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return response::with_failure_message(
+                format!("Only members of `{plo_id}` can add members."),
+                StatusCode::FORBIDDEN,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    }
+
+    let member = match user::Entity::find()
+        .filter(user::Column::Name.eq(addition.user_name.as_str()))
+        .one(database)
+        .await
+    {
+        Ok(Some(member)) => member,
+        Ok(None) => {
+            return response::with_failure_message(
+                format!("There is no user `{}`.", addition.user_name),
+                StatusCode::NOT_FOUND,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    match database::is_member(database, organization.id, member.id).await {
+        Ok(true) => return response::success_with_null_data(),
+        Ok(false) => {}
+        Err(err) => return response::with_error_message(err.to_string()),
+    }
+    match (organization_member::ActiveModel {
+        organization_id: Set(organization.id),
+        user_id: Set(member.id),
+    })
+    .insert(database)
+    .await
+    {
+        Ok(_) => response::success_with_null_data(),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A structure to model the form for creating a new organization.
+#[derive(Deserialize, Serialize)]
+pub struct OrganizationCreationForm {
+    pub plo_id: String,
+    pub name: String,
+}
+
+/// A structure to model the form for adding a member to an organization.
+#[derive(Deserialize, Serialize)]
+pub struct MemberAdditionForm {
+    /// The name of the user to add.
+    pub user_name: String,
+}
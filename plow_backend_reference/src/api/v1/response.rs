@@ -7,6 +7,7 @@ pub mod types;
 
 use actix_web::HttpResponse;
 use actix_web::HttpResponseBuilder;
+use plow_package_management::registry::http::PrivateIndexes;
 use reqwest::StatusCode;
 use serde::Deserialize;
 use serde::Serialize;
@@ -14,7 +15,7 @@ use serde::Serialize;
 use types::Category;
 use types::FieldSummary;
 
-use self::types::ApiTokenSummary;
+use self::types::{ApiTokenSummary, OrganizationSummary};
 
 /// A response with success status.
 ///
@@ -100,6 +101,11 @@ pub enum StatusInfo {
 #[serde(untagged)]
 pub enum Data<'data> {
     FailureMessage(&'data str),
+    // Serialized as {"failures": ["...", ], }
+    SubmissionLintingResults {
+        /// Messages of the lints which failed for a submitted field.
+        failures: Vec<String>,
+    },
     Categories(Vec<Category>),
     FieldSummaries(Vec<FieldSummary>),
     // Serialized as {"field": "...", }
@@ -118,6 +124,8 @@ pub enum Data<'data> {
         /// Base 64 encoded un-encrypted publish token.
         generated_token: String,
     },
+    Organizations(Vec<OrganizationSummary>),
+    PrivateIndexes(PrivateIndexes),
     FieldDetails {
         title: &'data str,
         short_description: &'data str,
@@ -24,6 +24,15 @@ pub struct ApiTokenSummary {
     pub last_used_at: Option<String>,
 }
 
+/// A type which represents an organization to be returned in a response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationSummary {
+    /// The id which the organization is referred to with.
+    pub plo_id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
 /// A type which represents a field but in a summarized way to be returned in a response.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialOrd, Ord)]
 pub struct FieldSummary {
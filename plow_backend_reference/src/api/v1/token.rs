@@ -1,25 +1,42 @@
 pub mod types;
 
 use super::response;
-use crate::{api::v1::token::types::TokenGenerationForm, AppState};
+use crate::{
+    api::v1::token::types::TokenGenerationForm, database, entity::api_token,
+    middlewares::AuthenticatedUser,
+};
 use actix_web::{delete, get, post, web, HttpResponseBuilder, Responder};
 
 use crate::api::v1::response::types::ApiTokenSummary;
-use futures::lock::Mutex;
 use reqwest::StatusCode;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter};
 
-const FIXED_TOKEN: &str = "token123";
-
-fn fixed_api_token_summary() -> ApiTokenSummary {
-    ApiTokenSummary {
-        id: 0,
-        name: "Fixed token".to_string(),
-        expires: -1,
-        created_at: "2020-01-01T00:00:00.000Z".to_string(),
-        last_used_at: None,
+impl From<api_token::Model> for ApiTokenSummary {
+    fn from(api_token: api_token::Model) -> Self {
+        Self {
+            id: api_token.id,
+            name: api_token.name,
+            expires: api_token.expires,
+            created_at: api_token.created_at,
+            last_used_at: api_token.last_used_at,
+        }
     }
 }
 
+/// Summaries of the tokens which belong to a user.
+async fn token_summaries(
+    database: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<ApiTokenSummary>, DbErr> {
+    Ok(api_token::Entity::find()
+        .filter(api_token::Column::UserId.eq(user_id))
+        .all(database)
+        .await?
+        .into_iter()
+        .map(ApiTokenSummary::from)
+        .collect())
+}
+
 /// Generates an api token for an app client.
 ///
 /// ## Properties:
@@ -30,7 +47,7 @@ fn fixed_api_token_summary() -> ApiTokenSummary {
 ///   - `/v1/token/generate`
 /// - Request Headers:
 ///   - `Content-Type: application/x-www-form-urlencoded`
-///   - `Authorization: Bearer <access_token>` or `Authorization: Basic <base64_encoded_api_token>`
+///   - `Authorization: Basic <base64_encoded_api_token>`
 /// - Request Parameters:
 ///   - `name`: The name of the token.
 /// ## Example Successful Response:
@@ -55,15 +72,25 @@ fn fixed_api_token_summary() -> ApiTokenSummary {
 ///  ```
 #[post("/generate")]
 pub async fn generate_api_token(
-    _requested_token: web::Form<TokenGenerationForm>,
-    _data: web::Data<Mutex<AppState>>,
+    requested_token: web::Form<TokenGenerationForm>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
 ) -> impl Responder {
-    HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
-        response::Data::GeneratedApiToken {
-            tokens: vec![fixed_api_token_summary()],
-            generated_token: base64::encode(FIXED_TOKEN),
-        },
-    )))
+    let database = database.get_ref();
+    let generated_token =
+        match database::create_token(database, user.id, &requested_token.name).await {
+            Ok(generated_token) => generated_token,
+            Err(err) => return response::with_error_message(err.to_string()),
+        };
+    match token_summaries(database, user.id).await {
+        Ok(tokens) => HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
+            response::Data::GeneratedApiToken {
+                tokens,
+                generated_token,
+            },
+        ))),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
 }
 
 /// Gets a list of tokens which belong to a user.
@@ -75,7 +102,7 @@ pub async fn generate_api_token(
 /// - Routes:
 ///   - `/v1/token/list`
 /// - Request Headers:
-///   - `Authorization: Bearer <access_token>` or `Authorization: Basic <base64_encoded_api_token>`
+///   - `Authorization: Basic <base64_encoded_api_token>`
 /// ## Example Successful Response:
 ///  ```json
 ///  {
@@ -93,14 +120,19 @@ pub async fn generate_api_token(
 ///  }
 ///  ```
 #[get("/list")]
-pub async fn get_token_summaries(_data: web::Data<Mutex<AppState>>) -> impl Responder {
-    let response = response::Success::new(Some(response::Data::ApiTokens(vec![
-        fixed_api_token_summary(),
-    ])));
-    HttpResponseBuilder::new(StatusCode::OK).json(response)
+pub async fn get_token_summaries(
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    match token_summaries(database.get_ref(), user.id).await {
+        Ok(tokens) => HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
+            response::Data::ApiTokens(tokens),
+        ))),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
 }
 
-/// Deletes a token by it's id.
+/// Deletes a token by it's id, only tokens of the querying user can be deleted.
 ///
 /// ## Properties:
 /// - Request type: `DELETE`
@@ -109,7 +141,7 @@ pub async fn get_token_summaries(_data: web::Data<Mutex<AppState>>) -> impl Resp
 /// - Routes:
 ///   - `/v1/token/{token_id}`
 /// - Request Headers:
-///   - `Authorization: Bearer <access_token>` or `Authorization: Basic <base64_encoded_api_token>`
+///   - `Authorization: Basic <base64_encoded_api_token>`
 /// ## Example Successful Response:
 ///  ```json
 ///  {
@@ -119,10 +151,28 @@ pub async fn get_token_summaries(_data: web::Data<Mutex<AppState>>) -> impl Resp
 ///  ```
 #[delete("/{token_id}")]
 pub async fn delete_api_token_by_its_id(
-    _path: web::Path<(String,)>,
-    _data: web::Data<Mutex<AppState>>,
+    path: web::Path<(i32,)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    database: web::Data<DatabaseConnection>,
 ) -> impl Responder {
-    HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
-        response::Data::ApiTokens(vec![]),
-    )))
+    let (token_id,) = path.into_inner();
+    let database = database.get_ref();
+    let api_token = match api_token::Entity::find_by_id(token_id)
+        .filter(api_token::Column::UserId.eq(user.id))
+        .one(database)
+        .await
+    {
+        Ok(Some(api_token)) => api_token,
+        Ok(None) => {
+            return response::with_failure_message(
+                format!("There is no token with the id {token_id}."),
+                StatusCode::NOT_FOUND,
+            )
+        }
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    match api_token.delete(database).await {
+        Ok(_) => response::success_with_null_data(),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
 }
@@ -1,11 +1,109 @@
+pub mod types;
+
 use super::response;
-use crate::AppState;
+use crate::{
+    api::v1::{response::types::ApiTokenSummary, user::types::UserRegistrationForm},
+    database,
+    entity::{api_token, user},
+    env::ALLOW_REGISTRATION,
+    middlewares::AuthenticatedUser,
+};
 use actix_http::StatusCode;
-use actix_web::{get, web, HttpResponseBuilder, Responder};
+use actix_web::{get, post, web, HttpResponseBuilder, Responder};
+
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+/// Registers a new user and generates their first api token.
+///
+/// Registration can be turned off with the `ALLOW_REGISTRATION` environment variable.
+///
+/// ## Properties:
+/// - Request type: `POST`
+/// - Needs authentication: **No**
+/// - Routes:
+///   - `/v1/user/register`
+/// - Request Headers:
+///   - `Content-Type: application/x-www-form-urlencoded`
+/// - Request Parameters:
+///   - `name`: The name of the user, it has to be unique.
+/// ## Example Successful Response:
+///  ```json
+///  {
+///    "status": "success",
+///    "data": {
+///      "tokens": [
+///         {
+///           "id": 1,
+///           "name": "Initial token",
+///           "expires": -1, // Infinite
+///           "created_at": "2020-01-01T00:00:00.000Z",
+///           "last_used_at": null, // Nullable
+///         },
+///      ],
+///      "generated_token": "<base64-encoded-string>"
+///    }
+///  }
+///  ```
+#[post("/register")]
+pub async fn register(
+    registration: web::Form<UserRegistrationForm>,
+    database: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    if !*ALLOW_REGISTRATION {
+        return response::with_failure_message(
+            "Registration is not allowed on this registry.",
+            StatusCode::FORBIDDEN,
+        );
+    }
+    let database = database.get_ref();
+    match user::Entity::find()
+        .filter(user::Column::Name.eq(registration.name.as_str()))
+        .one(database)
+        .await
+    {
+        Ok(Some(_)) => {
+            return response::with_failure_message(
+                format!("The name `{}` is already taken.", registration.name),
+                StatusCode::CONFLICT,
+            )
+        }
+        Ok(None) => {}
+        Err(err) => return response::with_error_message(err.to_string()),
+    }
 
-use futures::lock::Mutex;
+    let user = match (user::ActiveModel {
+        name: Set(registration.name.clone()),
+        tier: Set("free".to_owned()),
+        role: Set("user".to_owned()),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    })
+    .insert(database)
+    .await
+    {
+        Ok(user) => user,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    let generated_token = match database::create_token(database, user.id, "Initial token").await {
+        Ok(generated_token) => generated_token,
+        Err(err) => return response::with_error_message(err.to_string()),
+    };
+    match api_token::Entity::find()
+        .filter(api_token::Column::UserId.eq(user.id))
+        .all(database)
+        .await
+    {
+        Ok(tokens) => HttpResponseBuilder::new(StatusCode::OK).json(response::Success::new(Some(
+            response::Data::GeneratedApiToken {
+                tokens: tokens.into_iter().map(ApiTokenSummary::from).collect(),
+                generated_token,
+            },
+        ))),
+        Err(err) => response::with_error_message(err.to_string()),
+    }
+}
 
-/// Gets a list of tokens which belong to a user.
+/// Gets details of the querying user.
 ///
 /// ## Properties:
 /// - Request type: `GET`
@@ -14,7 +112,7 @@ use futures::lock::Mutex;
 /// - Routes:
 ///   - `/v1/user/details`
 /// - Request Headers:
-///   - `Authorization: Bearer <access_token>` or `Authorization: Basic <base64_encoded_api_token>`
+///   - `Authorization: Basic <base64_encoded_api_token>`
 /// ## Example Successful Response:
 ///  ```json
 ///  {
@@ -27,10 +125,10 @@ use futures::lock::Mutex;
 ///  }
 ///  ```
 #[get("/details")]
-pub async fn get_user_details(_data: web::Data<Mutex<AppState>>) -> impl Responder {
+pub async fn get_user_details(AuthenticatedUser(user): AuthenticatedUser) -> impl Responder {
     let response = response::Success::new(Some(response::Data::UserDetails {
-        tier: "free".to_string(),
-        role: "user".to_string(),
+        tier: user.tier,
+        role: user.role,
     }));
     return HttpResponseBuilder::new(StatusCode::OK).json(response);
 }
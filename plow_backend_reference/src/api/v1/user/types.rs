@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+/// A structure to model the form for registering a new user.
+#[derive(Deserialize, Serialize)]
+pub struct UserRegistrationForm {
+    pub name: String,
+}
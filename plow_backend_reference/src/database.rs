@@ -0,0 +1,163 @@
+//! Connection to the registry database and queries which are shared among endpoints.
+
+use crate::entity::{api_token, field, field_owner, organization, organization_member, user};
+use rand::RngCore;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, Schema, Set,
+};
+use sha2::{Digest, Sha256};
+
+/// Connects to the database, e.g. `sqlite://registry.db?mode=rwc`, and creates the tables which are missing.
+pub async fn connect(url: &str) -> Result<DatabaseConnection, DbErr> {
+    let database = Database::connect(url).await?;
+    let backend = database.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(user::Entity),
+        schema.create_table_from_entity(api_token::Entity),
+        schema.create_table_from_entity(organization::Entity),
+        schema.create_table_from_entity(organization_member::Entity),
+        schema.create_table_from_entity(field::Entity),
+        schema.create_table_from_entity(field_owner::Entity),
+    ] {
+        database
+            .execute(backend.build(statement.clone().if_not_exists()))
+            .await?;
+    }
+    Ok(database)
+}
+
+/// A new random API token, base64 encoded.
+pub fn generate_token() -> String {
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base64::encode(bytes)
+}
+
+/// The hash which an API token is stored with, tokens themselves are never stored.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Stores a new token for the user and returns it, it can not be retrieved later.
+pub async fn create_token(
+    database: &DatabaseConnection,
+    user_id: i32,
+    name: &str,
+) -> Result<String, DbErr> {
+    let token = generate_token();
+    api_token::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(name.to_owned()),
+        hash: Set(hash_token(&token)),
+        expires: Set(-1),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        last_used_at: Set(None),
+        ..Default::default()
+    }
+    .insert(database)
+    .await?;
+    Ok(token)
+}
+
+/// The user who the token belongs to, if the token is known and not expired.
+///
+/// Marks the token as used.
+pub async fn user_of_token(
+    database: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<user::Model>, DbErr> {
+    let api_token = match api_token::Entity::find()
+        .filter(api_token::Column::Hash.eq(hash_token(token)))
+        .one(database)
+        .await?
+    {
+        Some(api_token) => api_token,
+        None => return Ok(None),
+    };
+    let now = chrono::Utc::now();
+    if api_token.expires >= 0 && now.timestamp() > i64::from(api_token.expires) {
+        return Ok(None);
+    }
+
+    let user_id = api_token.user_id;
+    let mut api_token: api_token::ActiveModel = api_token.into();
+    api_token.last_used_at = Set(Some(now.to_rfc3339()));
+    api_token.update(database).await?;
+
+    user::Entity::find_by_id(user_id).one(database).await
+}
+
+/// Whether the user is a member of the organization.
+pub async fn is_member(
+    database: &DatabaseConnection,
+    organization_id: i32,
+    user_id: i32,
+) -> Result<bool, DbErr> {
+    Ok(
+        organization_member::Entity::find_by_id((organization_id, user_id))
+            .one(database)
+            .await?
+            .is_some(),
+    )
+}
+
+/// Whether the user owns a field, either as its submitter or as a member of the organization which owns it.
+pub async fn is_owner(
+    database: &DatabaseConnection,
+    submitter_id: i32,
+    organization_id: Option<i32>,
+    user_id: i32,
+) -> Result<bool, DbErr> {
+    match organization_id {
+        Some(organization_id) => is_member(database, organization_id, user_id).await,
+        None => Ok(submitter_id == user_id),
+    }
+}
+
+/// Records the owner of a field unless it has one already and returns the owner which the field has then.
+///
+/// The name of the field is the primary key of its owner,
+/// so of concurrent first submissions of a field only one records its owner and the others get that one.
+pub async fn claim_field(
+    database: &DatabaseConnection,
+    owner: field_owner::Model,
+) -> Result<field_owner::Model, DbErr> {
+    if let Some(existing) = field_owner::Entity::find_by_id(owner.name.clone())
+        .one(database)
+        .await?
+    {
+        return Ok(existing);
+    }
+    let name = owner.name.clone();
+    let inserted = field_owner::ActiveModel {
+        name: Set(owner.name),
+        submitter_id: Set(owner.submitter_id),
+        organization_id: Set(owner.organization_id),
+    }
+    .insert(database)
+    .await;
+    match inserted {
+        Ok(owner) => Ok(owner),
+        // Another submission recorded the owner in the meantime.
+        Err(err) => field_owner::Entity::find_by_id(name)
+            .one(database)
+            .await?
+            .ok_or(err),
+    }
+}
+
+/// Ids of the organizations which the user is a member of.
+pub async fn organization_ids_of_user(
+    database: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<i32>, DbErr> {
+    Ok(organization_member::Entity::find()
+        .filter(organization_member::Column::UserId.eq(user_id))
+        .all(database)
+        .await?
+        .into_iter()
+        .map(|member| member.organization_id)
+        .collect())
+}
@@ -0,0 +1,8 @@
+//! Tables of the registry database.
+
+pub mod api_token;
+pub mod field;
+pub mod field_owner;
+pub mod organization;
+pub mod organization_member;
+pub mod user;
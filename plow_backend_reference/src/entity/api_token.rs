@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+/// An API token of a user, only the hash of the token is stored.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_token")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    /// The SHA256 hash of the token, in hex.
+    #[sea_orm(unique)]
+    pub hash: String,
+    /// `-1` for tokens which do not expire.
+    pub expires: i32,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+/// A submitted version of a field and who owns it.
+///
+/// Every version of a field has the owner of its first version.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "field")]
+pub struct Model {
+    /// The hash of `<namespace>/<name> <version>`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hash: String,
+    /// `<namespace>/<name>` of the field.
+    pub name: String,
+    pub version: String,
+    /// Name of the artifact with the contents of the field, the SHA256 checksum of the contents.
+    pub artifact: String,
+    /// Private fields are only visible to their owner.
+    pub public: bool,
+    pub submitter_id: i32,
+    /// The organization which owns the field, the submitter owns it if there is none.
+    pub organization_id: Option<i32>,
+    pub submitted_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+/// The owner of a field, which the first submitted version of it decides.
+///
+/// There is a single row for every field, so concurrent first submissions of a field can not both become its owner.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "field_owner")]
+pub struct Model {
+    /// `<namespace>/<name>` of the field.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    /// The user who submitted the first version of the field.
+    pub submitter_id: i32,
+    /// The organization which owns the field, the submitter owns it if there is none.
+    pub organization_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// An organization which owns fields together with its members.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "organization")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// The id which the organization is referred to with, e.g. when a field is submitted.
+    #[sea_orm(unique)]
+    pub plo_id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+
+/// A user of the registry, who authenticates with API tokens.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "user")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// The readable name of the user.
+    #[sea_orm(unique)]
+    pub name: String,
+    pub tier: String,
+    pub role: String,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
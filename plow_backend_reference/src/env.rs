@@ -3,9 +3,44 @@ use std::env;
 
 /// Default port for the server.
 pub const DEFAULT_ACTIX_PORT: &str = "80";
+/// Default directory where submitted fields and their index are stored.
+pub const DEFAULT_REGISTRY_DIRECTORY: &str = "registry";
+/// Default database where users, their tokens, organizations and owners of fields are stored.
+pub const DEFAULT_DATABASE_URL: &str = "sqlite://registry.db?mode=rwc";
+/// Default branch of the public index which public fields are pushed to.
+pub const DEFAULT_PUBLIC_INDEX_BRANCH: &str = "main";
+/// Default directory where the public index is cloned to.
+pub const DEFAULT_PUBLIC_INDEX_DIRECTORY: &str = "public-index";
+/// Default for whether anyone may register as a user.
+pub const DEFAULT_ALLOW_REGISTRATION: &str = "true";
 
 lazy_static! {
     /// Port for the server.
     pub static ref ACTIX_PORT: String =
         env::var("ACTIX_PORT").unwrap_or_else(|_| DEFAULT_ACTIX_PORT.into());
+    /// Directory where submitted fields and their index are stored.
+    pub static ref REGISTRY_DIRECTORY: String =
+        env::var("REGISTRY_DIRECTORY").unwrap_or_else(|_| DEFAULT_REGISTRY_DIRECTORY.into());
+    /// Database where users, their tokens, organizations and owners of fields are stored.
+    pub static ref DATABASE_URL: String =
+        env::var("DATABASE_URL").unwrap_or_else(|_| DEFAULT_DATABASE_URL.into());
+    /// Git repository of the public index which public fields are pushed to, they are not published without it.
+    pub static ref PUBLIC_INDEX_REMOTE: Option<String> = env::var("PUBLIC_INDEX_REMOTE").ok();
+    /// Branch of the public index which public fields are pushed to.
+    pub static ref PUBLIC_INDEX_BRANCH: String =
+        env::var("PUBLIC_INDEX_BRANCH").unwrap_or_else(|_| DEFAULT_PUBLIC_INDEX_BRANCH.into());
+    /// Directory where the public index is cloned to.
+    pub static ref PUBLIC_INDEX_DIRECTORY: String =
+        env::var("PUBLIC_INDEX_DIRECTORY").unwrap_or_else(|_| DEFAULT_PUBLIC_INDEX_DIRECTORY.into());
+    /// Private SSH key to push to the public index with, if it is reached through ssh.
+    pub static ref PUBLIC_INDEX_SSH_KEY_PATH: Option<String> =
+        env::var("PUBLIC_INDEX_SSH_KEY_PATH").ok();
+    /// Key which download urls of artifacts are signed with.
+    ///
+    /// A random key is generated on every start if it is not set, signed urls do not survive restarts then.
+    pub static ref ARTIFACT_SIGNING_KEY: String =
+        env::var("ARTIFACT_SIGNING_KEY").unwrap_or_else(|_| crate::database::generate_token());
+    /// Whether anyone may register as a user, `true` or `false`.
+    pub static ref ALLOW_REGISTRATION: bool =
+        env::var("ALLOW_REGISTRATION").unwrap_or_else(|_| DEFAULT_ALLOW_REGISTRATION.into()) == "true";
 }
@@ -63,6 +63,10 @@
 
 /// Endpoints of the API
 pub mod api;
+/// Connection to the database and shared queries.
+pub mod database;
+/// Tables of the database.
+pub mod entity;
 // Environment variables for the service
 pub mod env;
 /// The collection of middlewares to be used.
@@ -74,6 +78,16 @@ pub mod middlewares;
 //     field_summaries: Vec<Field>,
 // }
 
+use plow_package_management::registry::{on_disk::OnDiskRegistry, on_disk_git::OnDiskGitRegistry};
+
 /// App data to be shared among all services.
+///
+/// It is shared behind a mutex which is only locked around reads and writes of the registries,
+/// the database connection is shared on its own since it is a pool.
 #[derive(Debug, Clone)]
-pub struct AppState {}
+pub struct AppState {
+    /// Where submitted fields and their index entries are stored.
+    pub registry: OnDiskRegistry,
+    /// Public fields are pushed to the public index, if there is one.
+    pub public_index: Option<OnDiskGitRegistry>,
+}
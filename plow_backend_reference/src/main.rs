@@ -60,17 +60,19 @@
 )]
 #![allow(dead_code)]
 
-use actix_web::{web, App, HttpServer};
+use actix_web::{middleware::from_fn, web, App, HttpServer};
 
 use anyhow::Result;
 
 use dotenv::dotenv;
 use futures::lock::Mutex;
+use plow_package_management::registry::{on_disk::OnDiskRegistry, on_disk_git::OnDiskGitRegistry};
 use service::env::{
-    ACTIX_PORT
+    ACTIX_PORT, DATABASE_URL, PUBLIC_INDEX_BRANCH, PUBLIC_INDEX_DIRECTORY, PUBLIC_INDEX_REMOTE,
+    PUBLIC_INDEX_SSH_KEY_PATH, REGISTRY_DIRECTORY,
 };
 use service::middlewares::logger::Level;
-use service::{api, middlewares, AppState};
+use service::{api, database, middlewares, AppState};
 
 fn server_url() -> String {
     let mut host = "0.0.0.0:".to_owned();
@@ -87,40 +89,49 @@ async fn main() -> Result<()> {
     middlewares::logger::read_level_or_default(Level::Info);
     env_logger::init();
 
+    // storage
+    std::fs::create_dir_all(REGISTRY_DIRECTORY.as_str())?;
+    let registry = OnDiskRegistry::new(REGISTRY_DIRECTORY.as_str())?;
+    let database = database::connect(DATABASE_URL.as_str()).await?;
+    let public_index = match *PUBLIC_INDEX_REMOTE {
+        Some(ref remote) => {
+            let ssh_priv_key = match *PUBLIC_INDEX_SSH_KEY_PATH {
+                Some(ref path) => Some(std::fs::read_to_string(path)?),
+                None => None,
+            };
+            Some(
+                OnDiskGitRegistry::initialize_from_remote(
+                    remote.as_str(),
+                    PUBLIC_INDEX_DIRECTORY.as_str(),
+                    ssh_priv_key,
+                )?
+                .with_branch(PUBLIC_INDEX_BRANCH.as_str()),
+            )
+        }
+        None => None,
+    };
+
     // app data
-    let data = web::Data::new(Mutex::new(AppState { }));
+    let data = web::Data::new(Mutex::new(AppState {
+        registry,
+        public_index,
+    }));
+    let database = web::Data::new(database);
 
     // server
     let server = HttpServer::new(move || {
         App::new()
             .app_data(web::Data::clone(&data))
+            .app_data(web::Data::clone(&database))
+            // Runs after the middlewares which are wrapped later, e.g. after CORS.
+            .wrap(from_fn(middlewares::authenticate))
             .wrap(middlewares::logger())
             .wrap(middlewares::cors::allow_list())
             .wrap(middlewares::security_headers())
             // Health check endpoints
             .route("/", web::get().to(actix_web::HttpResponse::Ok))
             .route("/health-check", web::get().to(actix_web::HttpResponse::Ok))
-            .service(
-                web::scope(api::Version::V1.as_path())
-                    .service(web::scope("/artifact").service(api::v1::artifact::get_signed_url))
-                    .service(
-                        web::scope("/field")
-                            .service(api::v1::field::search)
-                            .service(api::v1::field::get_all_categories)
-                            .service(api::v1::field::list_new_and_recent_time_scoped)
-                            .service(api::v1::field::list_new_and_recent_n_fields)
-                            .service(api::v1::field::get_field_details_with_field_id)
-                            .service(api::v1::field::get_fields_which_belong_to_a_user),
-                    )
-                    .service(web::scope("/user").service(api::v1::user::get_user_details))
-                    .service(
-                        web::scope("/token")
-                            .service(api::v1::token::generate_api_token)
-                            .service(api::v1::token::get_token_summaries)
-                            .service(api::v1::token::delete_api_token_by_its_id),
-                    ),
-            )
-
+            .service(web::scope(api::Version::V1.as_path()).configure(api::v1::services))
     });
 
     server.bind(server_url())?.run().await?;
@@ -1,6 +1,8 @@
+mod authentication;
 pub mod cors;
 pub mod logger;
 mod security_headers;
 
+pub use self::authentication::{authenticate, AuthenticatedUser};
 pub use self::logger::logger;
 pub use self::security_headers::security_headers;
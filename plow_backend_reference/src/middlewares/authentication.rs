@@ -0,0 +1,76 @@
+use crate::{api::v1::response, database, entity::user};
+use actix_http::StatusCode;
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse,
+};
+use futures::future::{ready, Ready};
+use sea_orm::DatabaseConnection;
+
+/// The user who a request is authenticated for.
+///
+/// Endpoints which need authentication take it as an argument,
+/// they reject requests without a valid API token.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub user::Model);
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(req.extensions().get::<Self>().cloned().ok_or_else(|| {
+            InternalError::from_response("", unauthorized("A valid API token is needed.")).into()
+        }))
+    }
+}
+
+/// Validates the API token in the `Authorization: Basic <api_token>` header of requests.
+///
+/// Requests with an unknown or expired token are rejected,
+/// requests without the header pass without an [`AuthenticatedUser`].
+///
+/// Used with [`actix_web::middleware::from_fn`].
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let token = match req.headers().get(AUTHORIZATION) {
+        Some(header) => match header
+            .to_str()
+            .ok()
+            .and_then(|header| header.strip_prefix("Basic "))
+        {
+            Some(token) => token.trim().to_owned(),
+            None => {
+                return Ok(req.into_response(unauthorized(
+                    "The authorization header needs to be `Basic <api_token>`.",
+                )))
+            }
+        },
+        None => return Ok(next.call(req).await?.map_into_boxed_body()),
+    };
+
+    let database = match req.app_data::<web::Data<DatabaseConnection>>() {
+        Some(database) => web::Data::clone(database),
+        None => {
+            return Ok(req.into_response(response::with_error_message("No database connection.")))
+        }
+    };
+    match database::user_of_token(&database, &token).await {
+        Ok(Some(user)) => {
+            req.extensions_mut().insert(AuthenticatedUser(user));
+            Ok(next.call(req).await?.map_into_boxed_body())
+        }
+        Ok(None) => Ok(req.into_response(unauthorized("The API token is unknown or expired."))),
+        Err(err) => Ok(req.into_response(response::with_error_message(err.to_string()))),
+    }
+}
+
+fn unauthorized(message: &str) -> HttpResponse {
+    response::with_failure_message(message, StatusCode::UNAUTHORIZED)
+}
@@ -0,0 +1,134 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::{field, submission, Backend};
+use actix_http::StatusCode;
+use actix_web::{http::header::AUTHORIZATION, test};
+use plow_package_management::{checksum, registry::http::field_hash};
+use serde_json::Value;
+
+/// A request for a signed url, with the token if there is one.
+fn signed_url_request(route: &str, token: Option<&str>) -> actix_http::Request {
+    let mut request = test::TestRequest::get().uri(route);
+    if let Some(token) = token {
+        request = request.insert_header((AUTHORIZATION, format!("Basic {token}")));
+    }
+    request.to_request()
+}
+
+/// The path and query of a signed url, which test requests are sent to.
+fn path_of(url: &str) -> &str {
+    &url[url.find("/v1/").unwrap()..]
+}
+
+#[actix_web::test]
+async fn public_artifacts_are_downloaded_through_signed_urls() {
+    let backend = Backend::new("public_artifacts_are_downloaded_through_signed_urls").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+    let contents = field("@test/public", "0.1.0");
+    let response = test::call_service(&app, submission(&token, &contents, true, None, false)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Public fields need no token, by their field hash or by their artifact name.
+    for route in [
+        format!(
+            "/v1/artifact/signed-url-by-field-hash/{}",
+            field_hash("@test/public", "0.1.0")
+        ),
+        format!(
+            "/v1/artifact/signed-url/{}",
+            checksum::compute(contents.as_bytes())
+        ),
+    ] {
+        let response = test::call_service(&app, signed_url_request(&route, None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(response).await;
+        let url = body["data"]["url"].as_str().unwrap();
+
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get().uri(path_of(url)).to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(test::read_body(response).await, contents.as_bytes());
+    }
+}
+
+#[actix_web::test]
+async fn artifacts_are_not_downloaded_without_a_valid_signature() {
+    let backend = Backend::new("artifacts_are_not_downloaded_without_a_valid_signature").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+    let contents = field("@test/public", "0.1.0");
+    let response = test::call_service(&app, submission(&token, &contents, true, None, false)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let artifact_name = checksum::compute(contents.as_bytes());
+
+    let response = test::call_service(
+        &app,
+        signed_url_request(&format!("/v1/artifact/signed-url/{artifact_name}"), None),
+    )
+    .await;
+    let body: Value = test::read_body_json(response).await;
+    let url = body["data"]["url"].as_str().unwrap();
+    let expires = url
+        .split(['?', '&'])
+        .find_map(|pair| pair.strip_prefix("expires="))
+        .unwrap();
+
+    for path in [
+        format!("/v1/artifact/{artifact_name}?expires={expires}&signature=00ff"),
+        format!("/v1/artifact/{artifact_name}?expires={expires}&signature=not-hex"),
+        // The expiry is part of the signature.
+        path_of(url).replace(
+            &format!("expires={expires}"),
+            &format!("expires={}", expires.parse::<i64>().unwrap() + 1),
+        ),
+        // Expired urls are not valid anymore.
+        format!("/v1/artifact/{artifact_name}?expires=0&signature=00ff"),
+    ] {
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri(&path).to_request()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{path}");
+    }
+}
+
+#[actix_web::test]
+async fn private_artifacts_are_only_signed_for_their_owners() {
+    let backend = Backend::new("private_artifacts_are_only_signed_for_their_owners").await;
+    let app = backend.app().await;
+    let (submitter_id, submitter_token) = backend.user("submitter").await;
+    let (member_id, member_token) = backend.user("member").await;
+    let (_, outsider_token) = backend.user("outsider").await;
+    backend
+        .organization("organization", &[submitter_id, member_id])
+        .await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            &submitter_token,
+            &field("@organization/private", "0.1.0"),
+            false,
+            Some("organization"),
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let route = format!(
+        "/v1/artifact/signed-url-by-field-hash/{}",
+        field_hash("@organization/private", "0.1.0")
+    );
+
+    for token in [&submitter_token, &member_token] {
+        let response = test::call_service(&app, signed_url_request(&route, Some(token))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    for token in [None, Some(outsider_token.as_str())] {
+        let response = test::call_service(&app, signed_url_request(&route, token)).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,182 @@
+#![allow(dead_code)]
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::header::{AUTHORIZATION, CONTENT_TYPE},
+    middleware::from_fn,
+    test, web, App,
+};
+use futures::lock::Mutex;
+use git2::Repository;
+use plow_package_management::registry::{on_disk::OnDiskRegistry, on_disk_git::OnDiskGitRegistry};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use service::{
+    api, database,
+    entity::{organization, organization_member, user},
+    middlewares, AppState,
+};
+use tempdir::TempDir;
+
+/// A backend with an empty registry and database in a temporary directory.
+pub struct Backend {
+    pub directory: TempDir,
+    pub database: web::Data<DatabaseConnection>,
+    pub state: web::Data<Mutex<AppState>>,
+}
+
+impl Backend {
+    pub async fn new(name: &str) -> Self {
+        Self::with_public_index(name, false).await
+    }
+
+    /// A backend which pushes public fields to a bare repository in `public-index-remote` of its directory, if `public_index` is set.
+    pub async fn with_public_index(name: &str, public_index: bool) -> Self {
+        let directory = TempDir::new(name).unwrap();
+        let registry_directory = directory.path().join("registry");
+        std::fs::create_dir_all(&registry_directory).unwrap();
+        let registry = OnDiskRegistry::new(&registry_directory).unwrap();
+        let database = database::connect(&format!(
+            "sqlite://{}?mode=rwc",
+            directory.path().join("registry.db").display()
+        ))
+        .await
+        .unwrap();
+        let public_index = public_index.then(|| {
+            let remote = directory.path().join("public-index-remote");
+            Repository::init_bare(&remote)
+                .unwrap()
+                .set_head("refs/heads/main")
+                .unwrap();
+            OnDiskGitRegistry::initialize_from_remote(
+                remote.to_str().unwrap(),
+                directory.path().join("public-index"),
+                None,
+            )
+            .unwrap()
+        });
+        Self {
+            directory,
+            database: web::Data::new(database),
+            state: web::Data::new(Mutex::new(AppState {
+                registry,
+                public_index,
+            })),
+        }
+    }
+
+    /// The app as it is served, with the authentication middleware.
+    pub async fn app(
+        &self,
+    ) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error>
+    {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::clone(&self.state))
+                .app_data(web::Data::clone(&self.database))
+                .wrap(from_fn(middlewares::authenticate))
+                .service(web::scope(api::Version::V1.as_path()).configure(api::v1::services)),
+        )
+        .await
+    }
+
+    /// Creates a user and returns their id and an API token of them.
+    pub async fn user(&self, name: &str) -> (i32, String) {
+        let user = user::ActiveModel {
+            name: Set(name.to_owned()),
+            tier: Set("free".to_owned()),
+            role: Set("user".to_owned()),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(self.database.get_ref())
+        .await
+        .unwrap();
+        let token = database::create_token(self.database.get_ref(), user.id, "Test token")
+            .await
+            .unwrap();
+        (user.id, token)
+    }
+
+    /// Creates an organization with the users as its members.
+    pub async fn organization(&self, plo_id: &str, member_ids: &[i32]) {
+        let organization = organization::ActiveModel {
+            plo_id: Set(plo_id.to_owned()),
+            name: Set(plo_id.to_owned()),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(self.database.get_ref())
+        .await
+        .unwrap();
+        for member_id in member_ids {
+            organization_member::ActiveModel {
+                organization_id: Set(organization.id),
+                user_id: Set(*member_id),
+            }
+            .insert(self.database.get_ref())
+            .await
+            .unwrap();
+        }
+    }
+}
+
+/// A field which passes the field manifest lints.
+pub fn field(name: &str, version: &str) -> String {
+    format!(
+        r#"@base <http://field33.com/ontologies/{name}/> .
+@prefix : <http://field33.com/ontologies/{name}/> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xml: <http://www.w3.org/XML/1998/namespace> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix registry: <http://field33.com/ontologies/REGISTRY/> .
+
+<http://field33.com/ontologies/{name}/> rdf:type owl:Ontology ;
+    registry:author "John Doe <john@example.com>" ;
+    registry:packageName "{name}" ;
+    registry:packageVersion "{version}" ;
+    registry:canonicalPrefix "{prefix}" ;
+    registry:category "Core" ;
+    registry:keyword "test" ;
+    registry:shortDescription "A field to test the backend with"@en ;
+    rdfs:comment "A field to test the backend with"@en ;
+    rdfs:label "Test field"@en ;
+    registry:licenseSPDX "MIT" ;
+    registry:ontologyFormatVersion "v1" .
+"#,
+        prefix = name.replace('@', "").replace('/', "__")
+    )
+}
+
+/// A multipart submission of the field to `/v1/field/submit` with the token.
+pub fn submission(
+    token: &str,
+    field: &str,
+    public: bool,
+    owner_plo_id: Option<&str>,
+    dry_run: bool,
+) -> Request {
+    const BOUNDARY: &str = "plow-backend-test-boundary";
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"field\"; filename=\"field.ttl\"\r\nContent-Type: text/turtle\r\n\r\n{field}\r\n--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"public\"\r\n\r\n{public}\r\n"
+    );
+    if let Some(owner_plo_id) = owner_plo_id {
+        body.push_str(&format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"owner_plo_id\"\r\n\r\n{owner_plo_id}\r\n"
+        ));
+    }
+    body.push_str(&format!("--{BOUNDARY}--\r\n"));
+
+    test::TestRequest::post()
+        .uri(&format!("/v1/field/submit?dry-run={dry_run}"))
+        .insert_header((AUTHORIZATION, format!("Basic {token}")))
+        .insert_header((
+            CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        ))
+        .set_payload(body)
+        .to_request()
+}
@@ -0,0 +1,308 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::{field, submission, Backend};
+use actix_http::StatusCode;
+use actix_web::test;
+use plow_package_management::{package::PackageVersion, registry::Registry};
+use serde_json::Value;
+
+fn package_version(name: &str, version: &str) -> PackageVersion {
+    PackageVersion {
+        package_name: name.to_owned(),
+        version: version.to_owned(),
+    }
+}
+
+#[actix_web::test]
+async fn submissions_are_stored() {
+    let backend = Backend::new("submissions_are_stored").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            &token,
+            &field("@test/submitted", "0.1.0"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "success");
+
+    assert!(backend
+        .state
+        .lock()
+        .await
+        .registry
+        .get_package_version_metadata(&package_version("@test/submitted", "0.1.0"))
+        .is_ok());
+}
+
+#[actix_web::test]
+async fn dry_runs_are_not_stored() {
+    let backend = Backend::new("dry_runs_are_not_stored").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+
+    let response = test::call_service(
+        &app,
+        submission(&token, &field("@test/dry_run", "0.1.0"), false, None, true),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(backend
+        .state
+        .lock()
+        .await
+        .registry
+        .get_package_version_metadata(&package_version("@test/dry_run", "0.1.0"))
+        .is_err());
+}
+
+#[actix_web::test]
+async fn submissions_without_a_valid_token_are_rejected() {
+    let backend = Backend::new("submissions_without_a_valid_token_are_rejected").await;
+    let app = backend.app().await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            "unknown",
+            &field("@test/unknown", "0.1.0"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The form is not read without a token either.
+    let response = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/v1/field/submit")
+            .set_payload("not a form")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn submissions_which_fail_the_lints_are_rejected() {
+    let backend = Backend::new("submissions_which_fail_the_lints_are_rejected").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+
+    let unlicensed =
+        field("@test/unlicensed", "0.1.0").replace("    registry:licenseSPDX \"MIT\" ;\n", "");
+    let response =
+        test::call_service(&app, submission(&token, &unlicensed, false, None, false)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "fail");
+    assert!(!body["data"]["failures"].as_array().unwrap().is_empty());
+
+    assert!(backend
+        .state
+        .lock()
+        .await
+        .registry
+        .get_package_version_metadata(&package_version("@test/unlicensed", "0.1.0"))
+        .is_err());
+}
+
+#[actix_web::test]
+async fn versions_can_only_be_submitted_once() {
+    let backend = Backend::new("versions_can_only_be_submitted_once").await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+    let contents = field("@test/duplicate", "0.1.0");
+
+    let response =
+        test::call_service(&app, submission(&token, &contents, false, None, false)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        test::call_service(&app, submission(&token, &contents, false, None, false)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // A new version can be submitted.
+    let response = test::call_service(
+        &app,
+        submission(
+            &token,
+            &field("@test/duplicate", "0.1.1"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn new_versions_can_only_be_submitted_by_the_owner() {
+    let backend = Backend::new("new_versions_can_only_be_submitted_by_the_owner").await;
+    let app = backend.app().await;
+    let (_, owner_token) = backend.user("owner").await;
+    let (_, other_token) = backend.user("other").await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            &owner_token,
+            &field("@test/owned", "0.1.0"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = test::call_service(
+        &app,
+        submission(
+            &other_token,
+            &field("@test/owned", "0.2.0"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn concurrent_first_submissions_have_a_single_owner() {
+    let backend = Backend::new("concurrent_first_submissions_have_a_single_owner").await;
+    let app = backend.app().await;
+    let (_, first_token) = backend.user("first").await;
+    let (_, second_token) = backend.user("second").await;
+
+    let (first, second) = futures::join!(
+        test::call_service(
+            &app,
+            submission(
+                &first_token,
+                &field("@test/contested", "0.1.0"),
+                false,
+                None,
+                false,
+            ),
+        ),
+        test::call_service(
+            &app,
+            submission(
+                &second_token,
+                &field("@test/contested", "0.2.0"),
+                false,
+                None,
+                false,
+            ),
+        )
+    );
+    let mut statuses = vec![first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(statuses, vec![StatusCode::OK, StatusCode::FORBIDDEN]);
+}
+
+#[actix_web::test]
+async fn dry_runs_do_not_make_the_submitter_the_owner() {
+    let backend = Backend::new("dry_runs_do_not_make_the_submitter_the_owner").await;
+    let app = backend.app().await;
+    let (_, dry_run_token) = backend.user("dry_run").await;
+    let (_, token) = backend.user("submitter").await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            &dry_run_token,
+            &field("@test/unclaimed", "0.1.0"),
+            false,
+            None,
+            true,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = test::call_service(
+        &app,
+        submission(
+            &token,
+            &field("@test/unclaimed", "0.1.0"),
+            false,
+            None,
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn public_submissions_are_pushed_to_the_public_index() {
+    let backend =
+        Backend::with_public_index("public_submissions_are_pushed_to_the_public_index", true).await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+
+    let response = test::call_service(
+        &app,
+        submission(&token, &field("@test/public", "0.1.0"), true, None, false),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let remote =
+        git2::Repository::open(backend.directory.path().join("public-index-remote")).unwrap();
+    let last_commit = remote
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(last_commit.message().unwrap(), "Add @test/public 0.1.0");
+}
+
+#[actix_web::test]
+async fn submissions_which_can_not_be_published_are_taken_back() {
+    let backend = Backend::with_public_index(
+        "submissions_which_can_not_be_published_are_taken_back",
+        true,
+    )
+    .await;
+    let app = backend.app().await;
+    let (_, token) = backend.user("submitter").await;
+    git2::Repository::open(backend.directory.path().join("public-index"))
+        .unwrap()
+        .remote_set_pushurl(
+            "origin",
+            Some(backend.directory.path().join("missing").to_str().unwrap()),
+        )
+        .unwrap();
+
+    let contents = field("@test/unpublished", "0.1.0");
+    let response = test::call_service(&app, submission(&token, &contents, true, None, false)).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(backend
+        .state
+        .lock()
+        .await
+        .registry
+        .get_package_version_metadata(&package_version("@test/unpublished", "0.1.0"))
+        .is_err());
+
+    // Nothing of the submission is left, so it can be submitted again.
+    let response =
+        test::call_service(&app, submission(&token, &contents, false, None, false)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,158 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::{field, submission, Backend};
+use actix_http::StatusCode;
+use actix_web::{http::header::AUTHORIZATION, test};
+use plow_package_management::registry::http::field_hash;
+use serde_json::{json, Value};
+
+/// `<name> <version>` of the fields in the private index of the user, and the field hashes which were removed from it.
+///
+/// Both are sorted.
+async fn private_index(
+    app: &impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+        Error = actix_web::Error,
+    >,
+    token: &str,
+    existing_local_field_hashes: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let response = test::call_service(
+        app,
+        test::TestRequest::post()
+            .uri("/v1/index/private/sync")
+            .insert_header((AUTHORIZATION, format!("Basic {token}")))
+            .set_json(json!({ "existing_local_field_hashes": existing_local_field_hashes }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    let mut indexes = body["data"]["indexes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|index| {
+            format!(
+                "{} {}",
+                index["name"].as_str().unwrap(),
+                index["version"].as_str().unwrap()
+            )
+        })
+        .collect::<Vec<_>>();
+    indexes.sort();
+    let mut removed_field_hashes = body["data"]["removed_field_hashes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hash| hash.as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    removed_field_hashes.sort();
+    (indexes, removed_field_hashes)
+}
+
+#[actix_web::test]
+async fn private_indexes_contain_the_private_fields_of_the_user() {
+    let backend = Backend::new("private_indexes_contain_the_private_fields_of_the_user").await;
+    let app = backend.app().await;
+    let (_, owner_token) = backend.user("owner").await;
+    let (_, other_token) = backend.user("other").await;
+
+    for (name, public) in [("@test/private", false), ("@test/public", true)] {
+        let response = test::call_service(
+            &app,
+            submission(&owner_token, &field(name, "0.1.0"), public, None, false),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Public fields are served through the public index.
+    assert_eq!(
+        private_index(&app, &owner_token, &[]).await,
+        (vec!["@test/private 0.1.0".to_owned()], vec![])
+    );
+    assert_eq!(
+        private_index(&app, &other_token, &[]).await,
+        (vec![], vec![])
+    );
+}
+
+#[actix_web::test]
+async fn private_indexes_contain_the_private_fields_of_the_organizations_of_the_user() {
+    let backend =
+        Backend::new("private_indexes_contain_the_private_fields_of_the_organizations_of_the_user")
+            .await;
+    let app = backend.app().await;
+    let (submitter_id, submitter_token) = backend.user("submitter").await;
+    let (member_id, member_token) = backend.user("member").await;
+    let (_, outsider_token) = backend.user("outsider").await;
+    backend
+        .organization("organization", &[submitter_id, member_id])
+        .await;
+
+    let response = test::call_service(
+        &app,
+        submission(
+            &submitter_token,
+            &field("@organization/private", "0.1.0"),
+            false,
+            Some("organization"),
+            false,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for token in [&submitter_token, &member_token] {
+        assert_eq!(
+            private_index(&app, token, &[]).await,
+            (vec!["@organization/private 0.1.0".to_owned()], vec![])
+        );
+    }
+    assert_eq!(
+        private_index(&app, &outsider_token, &[]).await,
+        (vec![], vec![])
+    );
+}
+
+#[actix_web::test]
+async fn private_indexes_only_send_what_the_client_does_not_have() {
+    let backend = Backend::new("private_indexes_only_send_what_the_client_does_not_have").await;
+    let app = backend.app().await;
+    let (_, owner_token) = backend.user("owner").await;
+    let (_, other_token) = backend.user("other").await;
+
+    for version in ["0.1.0", "0.2.0"] {
+        let response = test::call_service(
+            &app,
+            submission(
+                &owner_token,
+                &field("@test/private", version),
+                false,
+                None,
+                false,
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let first = field_hash("@test/private", "0.1.0");
+    let second = field_hash("@test/private", "0.2.0");
+
+    assert_eq!(
+        private_index(&app, &owner_token, std::slice::from_ref(&first)).await,
+        (vec!["@test/private 0.2.0".to_owned()], vec![])
+    );
+    // Fields which the user can not see are removed from their index.
+    assert_eq!(
+        private_index(&app, &other_token, &[first.clone(), second.clone()]).await,
+        (vec![], {
+            let mut removed_field_hashes = vec![first, second];
+            removed_field_hashes.sort();
+            removed_field_hashes
+        })
+    );
+}
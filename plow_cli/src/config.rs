@@ -7,6 +7,7 @@ use crate::subcommand::login::CredentialsFile;
 use camino::Utf8Path;
 use camino::Utf8PathBuf;
 use core::str::FromStr;
+use plow_package_management::resolve::dependency::is_valid_registry_name;
 
 use self::files::workspace_config::WorkspaceConfigFile;
 
@@ -21,6 +22,60 @@ Signature: 8a477f597d28d172789f06886806bc55
 
 // TODO: Change back
 pub const DEFAULT_REGISTRY_URL: &str = "https://api.plow.pm";
+/// The public index of the default registry.
+pub const DEFAULT_PUBLIC_INDEX_URL: &str = "https://github.com/field33/plow-registry-index.git";
+pub const DEFAULT_PUBLIC_INDEX_BRANCH: &str = "main";
+
+/// How strictly the lock file is followed, chosen with `--locked` or `--frozen`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockMode {
+    /// The lock file is updated as needed.
+    #[default]
+    Update,
+    /// Fails if the lock file would change.
+    Locked,
+    /// Fails if the lock file would change and never accesses the network for the index or fields.
+    Frozen,
+}
+
+impl LockMode {
+    pub fn from_flags(locked: bool, frozen: bool) -> Self {
+        if frozen {
+            return Self::Frozen;
+        }
+        if locked {
+            return Self::Locked;
+        }
+        Self::Update
+    }
+
+    /// Whether the lock file must stay as it is, which is also the case when frozen.
+    pub const fn is_locked(self) -> bool {
+        matches!(self, Self::Locked | Self::Frozen)
+    }
+
+    pub const fn is_frozen(self) -> bool {
+        matches!(self, Self::Frozen)
+    }
+}
+
+/// A registry which fields are resolved from, the default one or one in the `[registries]` table of the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryLocation {
+    /// The name which dependencies are qualified with, `None` for the default registry.
+    pub name: Option<String>,
+    pub url: String,
+    /// The git repository of the public index of the registry, if it has one.
+    pub public_index_url: Option<String>,
+    pub public_index_branch: String,
+}
+
+impl RegistryLocation {
+    /// The name of the registry to show in messages.
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+}
 
 #[derive(Debug)]
 pub struct PlowConfig {
@@ -35,7 +90,10 @@ pub struct PlowConfig {
     workspace_config_file: Option<WorkspaceConfigFile>,
     // Fill this if it is provided with a command.
     registry_url: Option<String>,
+    // Set if it is provided with a command, otherwise the workspace config file decides.
+    offline: bool,
     pub fetch_with_cli: bool,
+    pub lock_mode: LockMode,
 }
 
 impl PlowConfig {
@@ -73,24 +131,130 @@ impl PlowConfig {
         Ok(DEFAULT_REGISTRY_URL.to_owned())
     }
 
-    pub fn get_saved_api_token(&self) -> Result<String, CliError> {
-        // Check for .plow folder in the workspace for config.toml which might have a token to override.
+    /// The url of the git repository of the public index.
+    ///
+    /// Unless it is set with `public-index` in the `[registry]` section of the workspace config file,
+    /// it is [`DEFAULT_PUBLIC_INDEX_URL`], so a registry other than the default one needs to set it.
+    pub fn get_public_index_url(&self) -> Result<String, CliError> {
         if let Some(ref workspace_config_file) = self.workspace_config_file {
             let workspace_config_file = workspace_config_file.fetch()?;
-            // TODO: This place should modify if we wish to support multiple registries.
             if let Some(registry) = workspace_config_file.registry {
-                if let Some(token) = registry.token {
-                    return Ok(token);
+                if let Some(url) = registry.public_index {
+                    return Ok(url);
+                }
+            }
+        }
+
+        Ok(DEFAULT_PUBLIC_INDEX_URL.to_owned())
+    }
+
+    pub fn get_public_index_branch(&self) -> Result<String, CliError> {
+        if let Some(ref workspace_config_file) = self.workspace_config_file {
+            let workspace_config_file = workspace_config_file.fetch()?;
+            if let Some(registry) = workspace_config_file.registry {
+                if let Some(branch) = registry.public_index_branch {
+                    return Ok(branch);
+                }
+            }
+        }
+
+        Ok(DEFAULT_PUBLIC_INDEX_BRANCH.to_owned())
+    }
+
+    /// Whether the network should not be used, with `--offline` or `offline = true` in the `[net]` section of the config file.
+    pub fn is_offline(&self) -> Result<bool, CliError> {
+        if self.offline {
+            return Ok(true);
+        }
+
+        if let Some(ref workspace_config_file) = self.workspace_config_file {
+            let workspace_config_file = workspace_config_file.fetch()?;
+            if let Some(net) = workspace_config_file.net {
+                return Ok(net.offline.unwrap_or(false));
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// The default registry followed by the ones in the `[registries]` table of the workspace config file.
+    pub fn get_registries(&self) -> Result<Vec<RegistryLocation>, CliError> {
+        let mut registries = vec![RegistryLocation {
+            name: None,
+            url: self.get_registry_url()?,
+            public_index_url: Some(self.get_public_index_url()?),
+            public_index_branch: self.get_public_index_branch()?,
+        }];
+
+        if let Some(ref workspace_config_file) = self.workspace_config_file {
+            let workspace_config_file = workspace_config_file.fetch()?;
+            for (name, registry) in workspace_config_file.registries.unwrap_or_default() {
+                if !is_valid_registry_name(&name) {
+                    return Err(CliError::from(InvalidRegistryName(name)));
                 }
+                registries.push(RegistryLocation {
+                    name: Some(name),
+                    url: registry.url,
+                    public_index_url: registry.index,
+                    public_index_branch: registry
+                        .index_branch
+                        .unwrap_or_else(|| DEFAULT_PUBLIC_INDEX_BRANCH.to_owned()),
+                });
             }
         }
 
+        Ok(registries)
+    }
+
+    fn read_credentials_file(&self) -> Result<CredentialsFile, CliError> {
         let credentials_file_contents = std::fs::read_to_string(&self.credentials_path)
             .map_err(|_| FailedToReadCredentialsFile)?;
         let credentials = toml::from_str::<CredentialsFile>(&credentials_file_contents)
             .map_err(|_| FailedToReadCredentialsFile)?;
+        Ok(credentials)
+    }
+
+    pub fn get_saved_api_token(&self) -> Result<String, CliError> {
+        // Check for .plow folder in the workspace for config.toml which might have a token to override.
+        if let Some(ref workspace_config_file) = self.workspace_config_file {
+            let workspace_config_file = workspace_config_file.fetch()?;
+            if let Some(registry) = workspace_config_file.registry {
+                if let Some(token) = registry.token {
+                    return Ok(token);
+                }
+            }
+        }
+
+        self.read_credentials_file()?
+            .registry
+            .map(|registry| registry.token)
+            .ok_or_else(|| CliError::from(FailedToReadCredentialsFile))
+    }
+
+    /// The token for a registry, the token of the default registry if no name is given.
+    pub fn get_registry_token(&self, registry_name: Option<&str>) -> Result<String, CliError> {
+        let registry_name = match registry_name {
+            Some(registry_name) => registry_name,
+            None => return self.get_saved_api_token(),
+        };
+
+        // The config file might have a token to override.
+        if let Some(ref workspace_config_file) = self.workspace_config_file {
+            let workspace_config_file = workspace_config_file.fetch()?;
+            if let Some(token) = workspace_config_file
+                .registries
+                .and_then(|mut registries| registries.remove(registry_name))
+                .and_then(|registry| registry.token)
+            {
+                return Ok(token);
+            }
+        }
 
-        Ok(credentials.registry.token.to_owned())
+        self.read_credentials_file()?
+            .registries
+            .remove(registry_name)
+            .map(|registry| registry.token)
+            .ok_or_else(|| CliError::from(NoCredentialsForRegistry(registry_name.to_owned())))
     }
 
     pub fn get_user_id(&self) -> Result<String, CliError> {
@@ -147,15 +311,14 @@ impl From<Utf8PathBuf> for WorkingDirectory {
     }
 }
 
-// TODO: Currently Plow does not support multiple registries.
-// There is one central remote registry which is owned by Field33.
-// Although there are plans to expand support for custom registries in the future.
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::missing_panics_doc)]
 pub fn configure(
     custom_path: Option<Utf8PathBuf>,
     registry_url: Option<String>,
+    offline: bool,
     fetch_with_cli: bool,
+    lock_mode: LockMode,
 ) -> Result<PlowConfig, CliError> {
     let working_dir = WorkingDirectory::from(
         Utf8PathBuf::from_path_buf(
@@ -252,7 +415,9 @@ pub fn configure(
         working_dir,
         workspace_config_file,
         registry_url,
+        offline,
         fetch_with_cli,
+        lock_mode,
     })
 }
 
@@ -1,6 +1,7 @@
 use crate::{config::WorkingDirectory, error::CliError, error::ConfigError::*};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Serialized example:
 ///
@@ -13,6 +14,18 @@ use serde::{Deserialize, Serialize};
 /// [registry]
 /// index = "https://api.plow.pm"
 /// token = "an auth token to override the one in the credentials file"
+/// # The git repository of the public index and its branch.
+/// public-index = "https://github.com/field33/plow-registry-index.git"
+/// public-index-branch = "main"
+///
+/// # Registries next to the default one, dependencies are qualified with their names
+/// # e.g. `internal:@namespace/name ^1.0.0`.
+/// [registries.internal]
+/// url = "https://plow.internal.example.com"
+/// # The git repository of its public index, optional.
+/// index = "https://git.internal.example.com/plow-index.git"
+/// index-branch = "main"
+/// token = "an auth token to override the one in the credentials file"
 ///
 /// [net]
 /// # Stops calls to the remote registry.
@@ -22,6 +35,8 @@ use serde::{Deserialize, Serialize};
 pub struct WorkspaceConfigFile {
     pub plow: Option<Plow>,
     pub registry: Option<Registry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registries: Option<BTreeMap<String, NamedRegistry>>,
     pub net: Option<Net>,
     #[serde(skip_serializing, skip_deserializing)]
     pub path: Utf8PathBuf,
@@ -32,6 +47,7 @@ impl WorkspaceConfigFile {
         Self {
             plow: None,
             registry: None,
+            registries: None,
             net: None,
             path: path.to_path_buf(),
         }
@@ -98,6 +114,34 @@ pub struct Plow {
 pub struct Registry {
     pub index: Option<String>,
     pub token: Option<String>,
+    #[serde(
+        rename = "public-index",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub public_index: Option<String>,
+    #[serde(
+        rename = "public-index-branch",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub public_index_branch: Option<String>,
+}
+
+/// A registry in the `[registries]` table, next to the default registry.
+#[derive(Serialize, Debug, Deserialize, Clone)]
+pub struct NamedRegistry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+    #[serde(
+        rename = "index-branch",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub index_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
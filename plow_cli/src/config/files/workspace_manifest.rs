@@ -1,5 +1,7 @@
 use camino::{Utf8Path, Utf8PathBuf};
+use plow_linter::{lint::LintLevel, lints::LintName};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::{
     error::CliError, error::WorkspaceInitializationError::*, manifest::FieldManifest,
@@ -12,11 +14,38 @@ use crate::{
 ///
 /// [workspace]
 /// members = ["path to field","another", ..]
+/// # Optional, allows incompatible major versions of a dependency side by side.
+/// multiple-major-versions = true
 ///
+/// # Optional, the levels of lints by their names.
+/// [lints]
+/// valid_rdfs_labels = "allow" # or "warn" or "deny"
+///
+/// # Optional, rules of the workspace which are linted after the built-in lints.
+/// [[custom-lints]]
+/// name = "classes_have_definitions"
+/// description = "Every class has one `skos:definition`"
+/// # The rule applies to the instances of the class and of its subclasses, IRIs are written out in full.
+/// class = "http://www.w3.org/2002/07/owl#Class"
+/// # The predicate whose values are checked.
+/// predicate = "http://www.w3.org/2004/02/skos/core#definition"
+/// # At least one of the following, they are all optional.
+/// min-count = 1
+/// max-count = 1
+/// datatype = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"
+/// pattern = "(?i)^an? " # a regular expression which every value matches
+/// # Optional, `{subject}` is replaced with the instance which breaks the rule.
+/// message = "The class {subject} has no definition which starts with `A` or `An`."
+/// # Optional, like in `[lints]`.
+/// level = "warn"
 /// ```
 #[derive(Serialize, Debug, Deserialize, Default)]
 pub struct WorkspaceManifestFile {
     pub workspace: Option<Workspace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lints: Option<HashMap<LintName, LintLevel>>,
+    #[serde(rename = "custom-lints", skip_serializing_if = "Option::is_none")]
+    pub custom_lints: Option<Vec<CustomLintDefinition>>,
     #[serde(skip_serializing, skip_deserializing)]
     pub path: Utf8PathBuf,
 }
@@ -25,6 +54,8 @@ impl WorkspaceManifestFile {
     pub fn empty_with_path(path: &Utf8Path) -> Self {
         Self {
             workspace: None,
+            lints: None,
+            custom_lints: None,
             path: path.to_path_buf(),
         }
     }
@@ -64,6 +95,25 @@ impl WorkspaceManifestFile {
         None
     }
 
+    /// Whether incompatible major versions of a dependency may be resolved side by side.
+    pub fn allows_multiple_major_versions(&self) -> bool {
+        self.workspace
+            .as_ref()
+            .and_then(|workspace| workspace.multiple_major_versions)
+            .unwrap_or(false)
+    }
+
+    /// The levels which the lints are set to in the workspace, including its custom lints.
+    pub fn lint_levels(&self) -> HashMap<LintName, LintLevel> {
+        let mut lint_levels = self.lints.clone().unwrap_or_default();
+        for custom_lint in self.custom_lints.iter().flatten() {
+            if let Some(level) = custom_lint.level {
+                lint_levels.insert(LintName::Custom(custom_lint.name.clone()), level);
+            }
+        }
+        lint_levels
+    }
+
     pub fn from_file(path: &Utf8Path) -> Result<Self, CliError> {
         let contents = std::fs::read(path)
             .map_err(|err| FailedToReadWorkspaceManifestFile(err.to_string()))?;
@@ -79,6 +129,8 @@ impl WorkspaceManifestFile {
         let updated_workspace_manifest_file = toml::from_slice::<Self>(&contents)
             .map_err(|err| FailedToReadWorkspaceManifestFile(err.to_string()))?;
         self.workspace = updated_workspace_manifest_file.workspace;
+        self.lints = updated_workspace_manifest_file.lints;
+        self.custom_lints = updated_workspace_manifest_file.custom_lints;
         Ok(())
     }
 
@@ -94,9 +146,36 @@ impl WorkspaceManifestFile {
     }
 }
 
+/// A lint of the workspace, which is a rule about the values of a predicate of the instances of a class.
+#[derive(Serialize, Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CustomLintDefinition {
+    pub name: String,
+    pub description: String,
+    pub class: String,
+    pub predicate: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<LintLevel>,
+}
+
 #[derive(Serialize, Debug, Deserialize, Default)]
 pub struct Workspace {
     pub members: Option<Vec<String>>,
+    #[serde(
+        rename = "multiple-major-versions",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub multiple_major_versions: Option<bool>,
     #[serde(skip_serializing, skip_deserializing)]
     pub member_map: std::collections::HashMap<String, Utf8PathBuf>,
 }
@@ -125,6 +204,7 @@ impl From<&FieldsDirectory> for Workspace {
         }
         Self {
             members: Some(members),
+            multiple_major_versions: None,
             member_map,
         }
     }
@@ -140,6 +220,11 @@ impl From<&FieldsDirectory> for WorkspaceManifestFile {
             .expect("Src directory is in os root, probably you didn't want this to happen.")
             .to_path_buf()
             .join("Plow.toml");
-        Self { workspace, path }
+        Self {
+            workspace,
+            lints: None,
+            custom_lints: None,
+            path,
+        }
     }
 }
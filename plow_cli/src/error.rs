@@ -9,9 +9,11 @@ mod lint;
 mod list;
 mod login;
 mod protege;
+mod registry;
 mod resolve;
 mod submission;
 mod workspace_init;
+mod yank;
 
 pub use config::ConfigError;
 pub use field_access::FieldAccessError;
@@ -22,9 +24,11 @@ pub use lint::LintSubcommandError;
 pub use list::ListError;
 pub use login::LoginError;
 pub use protege::ProtegeSubcommandError;
+pub use registry::RegistrySubcommandError;
 pub use resolve::ResolveError;
 pub use submission::SubmissionError;
 pub use workspace_init::WorkspaceInitializationError;
+pub use yank::YankError;
 
 use crate::feedback::{command_failed, command_not_complete, Feedback};
 use thiserror::Error;
@@ -55,6 +59,10 @@ pub enum CliError {
     Protege(ProtegeSubcommandError),
     #[error("")]
     List(ListError),
+    #[error("")]
+    Yank(YankError),
+    #[error("")]
+    Registry(RegistrySubcommandError),
     #[error("Command aborted.")]
     Abort(String),
     #[error("The command line option you have provided is not in the list of options. Please run plow --help to see the list of options.")]
@@ -71,6 +79,18 @@ impl From<ListError> for CliError {
     }
 }
 
+impl From<YankError> for CliError {
+    fn from(error: YankError) -> Self {
+        Self::Yank(error)
+    }
+}
+
+impl From<RegistrySubcommandError> for CliError {
+    fn from(error: RegistrySubcommandError) -> Self {
+        Self::Registry(error)
+    }
+}
+
 impl From<SubmissionError> for CliError {
     fn from(error: SubmissionError) -> Self {
         Self::Submission(error)
@@ -155,6 +175,8 @@ impl Feedback for CliError {
             WorkspaceInitialization(error) => error.feedback(),
             FieldAccess(error) => error.feedback(),
             Protege(error) => error.feedback(),
+            Yank(error) => error.feedback(),
+            Registry(error) => error.feedback(),
             UnknownOption => {
                 command_not_complete(&format!("{self}"));
             }
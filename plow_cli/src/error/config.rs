@@ -34,6 +34,10 @@ pub enum ConfigError {
         "Failed to find workspace root directory. Are you running the command under a directory tree which has a Plow.toml file in the root?"
     )]
     FailedToFindWorkspaceRoot,
+    #[error(
+        "The registry name {0} in the workspace config (.plow/config.toml) may only contain letters, digits, `-` or `_`."
+    )]
+    InvalidRegistryName(String),
 }
 
 impl Feedback for ConfigError {
@@ -49,7 +53,8 @@ impl Feedback for ConfigError {
             | FailedToWriteWorkspaceConfigFile(_)
             | FailedToWriteCredentialsFile
             | DirectoryNotWorkspace
-            | FailedToFindWorkspaceRoot => {
+            | FailedToFindWorkspaceRoot
+            | InvalidRegistryName(_) => {
                 command_failed(&format!("{self}"));
             }
         }
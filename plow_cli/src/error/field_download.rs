@@ -8,13 +8,34 @@ pub enum FieldDownloadError {
     FailedToDownloadAndCacheField { reason: String, package_name: String },
     #[error("Failed to read the local cache of downloaded fields.\n\tDetails: {reason:?}")]
     FailedToReadFieldCache { reason: String },
+    #[error("Downloaded contents of {package_name} do not match the checksum {expected}, they hash to {actual}.\n\tThe download is kept at {quarantined_path} for inspection.")]
+    ChecksumMismatch {
+        package_name: String,
+        expected: String,
+        actual: String,
+        quarantined_path: String,
+    },
+    #[error("Failed to move a field which does not match its checksum out of the cache.\n\tDetails: {reason:?} - Path: {path:}")]
+    FailedToQuarantineField { path: String, reason: String },
+    #[error("{package_name} {version} has no checksum in the index, so its contents can not be verified.")]
+    MissingChecksum {
+        package_name: String,
+        version: String,
+    },
+    #[error("These fields are not in the local cache and would need the network to be downloaded: {}", .fields.join(", "))]
+    FieldsNotCached { fields: Vec<String> },
 }
 
 impl Feedback for FieldDownloadError {
     fn feedback(&self) {
         use FieldDownloadError::*;
         match self {
-            FailedToDownloadAndCacheField { .. } | FailedToReadFieldCache { .. } => {
+            FailedToDownloadAndCacheField { .. }
+            | FailedToReadFieldCache { .. }
+            | ChecksumMismatch { .. }
+            | FailedToQuarantineField { .. }
+            | MissingChecksum { .. }
+            | FieldsNotCached { .. } => {
                 command_failed(&format!("{self}"));
             }
         }
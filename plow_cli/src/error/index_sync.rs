@@ -10,8 +10,12 @@ pub enum IndexSyncError {
     FailedToReadIndexDirectory(String),
     #[error("Failed to retrieve public index repository.\n\tDetails: {0}")]
     FailedToGetRepository(String),
+    #[error("Failed to pull the latest changes of the public index repository.\n\tDetails: {0}")]
+    FailedToUpdateRepository(String),
     #[error("Private index sync failed.\n\tDetails: {0}")]
     FailedToGetPrivateIndexUpdates(String),
+    #[error("Failed to read the lock file to use instead of the index.\n\tDetails: {0}")]
+    FailedToReadLockFile(String),
 }
 
 impl Feedback for IndexSyncError {
@@ -21,7 +25,9 @@ impl Feedback for IndexSyncError {
             FailedToParseIndex(_)
             | FailedToReadIndexDirectory(_)
             | FailedToGetRepository(_)
-            | FailedToGetPrivateIndexUpdates(_) => {
+            | FailedToUpdateRepository(_)
+            | FailedToGetPrivateIndexUpdates(_)
+            | FailedToReadLockFile(_) => {
                 command_failed(&format!("{self}"));
             }
         }
@@ -11,11 +11,18 @@ pub enum LintSubcommandError {
     FailedToFindFieldToLint { field_path: String },
     #[error("The field at {field_path:?} exists but not readable please check if the directory and file has read privileges.\n\tDetails: {details:?}")]
     FailedToReadField { field_path: String, details: String },
+    #[error("The fixes for the field at {field_path:?} could not be written to it, please check if the file has write privileges.\n\tDetails: {details:?}")]
+    FailedToWriteFixedField { field_path: String, details: String },
+    #[error("The custom lint {name:?} of the workspace could not be loaded, please check its entry in `[[custom-lints]]` of Plow.toml.\n\tDetails: {details}")]
+    FailedToLoadCustomLint { name: String, details: String },
     #[error("Please provide a field (a valid .ttl file path) for plow to lint")]
     NoFieldProvidedToLint,
     #[error("")]
     LintsContainFailuresOpaque,
     #[error("")]
+    /// The results of the lints were printed in a machine-readable format, which nothing else should be printed with.
+    LintsContainFailuresReported,
+    #[error("")]
     /// This is an internal intermediate error to be used when returning early from functions
     /// which have the purpose of detecting the first error in a lint run.
     /// User feedback is not necessary.
@@ -30,7 +37,9 @@ impl Feedback for LintSubcommandError {
         match self {
             FailedToParseField { .. }
             | FailedToFindFieldToLint { .. }
-            | FailedToReadField { .. } => {
+            | FailedToReadField { .. }
+            | FailedToWriteFixedField { .. }
+            | FailedToLoadCustomLint { .. } => {
                 command_failed(&format!("{self}"));
             }
             NoFieldProvidedToLint => {
@@ -39,6 +48,9 @@ impl Feedback for LintSubcommandError {
             LintsContainFailuresOpaque => {
                 linting_failed();
             }
+            LintsContainFailuresReported => {
+                std::process::exit(0xFF);
+            }
             SingleLintContainsFailure { .. } => { /* Omit feedback */ }
             LintsContainFailures { field_paths } => {
                 println!("\t{} couldn't pass the linting phase thus ignored and not included in the workspace and fields directory.\n\tPlease check if they have valid values for `registry:packageName`, `registry:packageVersion` and if they include dependencies have valid values for `registry:dependency` individually, correct them and run plow init one more time to recreate the workspace.", "Paths listed below".yellow().bold());
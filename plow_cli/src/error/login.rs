@@ -11,13 +11,17 @@ pub enum LoginError {
     FailedToWriteCredentialsFile,
     #[error("Please provide a valid API token to save")]
     NoTokenProvidedToSave,
+    #[error("There is no API token for the registry {0}. You may run `plow login --name {0} <api-token>` to save one.")]
+    NoCredentialsForRegistry(String),
 }
 
 impl Feedback for LoginError {
     fn feedback(&self) {
         use LoginError::*;
         match self {
-            FailedToReadCredentialsFile | FailedToWriteCredentialsFile => {
+            FailedToReadCredentialsFile
+            | FailedToWriteCredentialsFile
+            | NoCredentialsForRegistry(_) => {
                 login_failed(&format!("{self}"));
             }
             NoTokenProvidedToSave => {
@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+use crate::feedback::{command_failed, Feedback};
+
+#[derive(Error, Debug)]
+pub enum RegistrySubcommandError {
+    #[error("The registry in {path} can not be opened. Details: {reason}")]
+    FailedToOpenRegistry { path: String, reason: String },
+    #[error("The registry can not be served. Details: {reason}")]
+    FailedToServeRegistry { reason: String },
+}
+
+impl Feedback for RegistrySubcommandError {
+    fn feedback(&self) {
+        use RegistrySubcommandError::*;
+        match self {
+            FailedToOpenRegistry { .. } | FailedToServeRegistry { .. } => {
+                command_failed(&format!("{self}"));
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::feedback::{dependency_resolution_failed, Feedback};
+use crate::feedback::{dependency_conflict, dependency_resolution_failed, Feedback};
+use plow_package_management::resolve::report::ConflictReport;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,15 +7,32 @@ use thiserror::Error;
 pub enum ResolveError {
     #[error("{0}")]
     FailedToResolveDependencies(String),
+    #[error("{0}")]
+    ConflictingRequirements(ConflictReport),
+    #[error("The version {version} provided with --precise is not a valid semantic version.\n\tDetails: {reason}")]
+    InvalidPreciseVersion { version: String, reason: String },
+    #[error("{0}\n\tThe field may have been changed after it was locked, please check it before running plow update.")]
+    LockFileDisagreesWithRegistry(String),
+    #[error("{0}\n\tRun plow update without --locked or --frozen to update the lock file.")]
+    LockFileOutOfDate(String),
+    #[error("The metadata of {package}, which was resolved, could not be retrieved from the registry.\n\tDetails: {reason}")]
+    MissingMetadata { package: String, reason: String },
 }
 
 impl Feedback for ResolveError {
     fn feedback(&self) {
         use ResolveError::*;
         match self {
-            FailedToResolveDependencies(_) => {
+            FailedToResolveDependencies(_)
+            | InvalidPreciseVersion { .. }
+            | LockFileDisagreesWithRegistry(_)
+            | LockFileOutOfDate(_)
+            | MissingMetadata { .. } => {
                 dependency_resolution_failed(&format!("{self}"));
             }
+            ConflictingRequirements(report) => {
+                dependency_conflict(&format!("{report}"));
+            }
         }
     }
 }
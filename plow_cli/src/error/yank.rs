@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+use crate::feedback::{command_not_complete, yank_failed, Feedback};
+
+#[derive(Error, Debug)]
+pub enum YankError {
+    #[error("Please provide the name of the field to yank in the form of @namespace/name.")]
+    InvalidFieldName(String),
+    #[error("Please provide a valid semantic version of the field to yank, {0} is not one.")]
+    InvalidVersion(String),
+    #[error("There is no registry named {0}. Registries are named in the [registries] table of the workspace config (.plow/config.toml).")]
+    UnknownRegistry(String),
+    #[error("The registry did not take the request. Reason: {reason}")]
+    RequestFailed { reason: String },
+}
+
+impl Feedback for YankError {
+    fn feedback(&self) {
+        use YankError::*;
+        match self {
+            InvalidFieldName(_) | InvalidVersion(_) => {
+                command_not_complete(&format!("{self}"));
+            }
+            UnknownRegistry(_) | RequestFailed { .. } => {
+                yank_failed(&format!("{self}"));
+            }
+        }
+    }
+}
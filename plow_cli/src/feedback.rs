@@ -23,6 +23,12 @@ pub fn submission_failed(info: &str) {
     std::process::exit(0xFF);
 }
 
+pub fn yank_failed(info: &str) {
+    println!("\t{}", "Yank failed".red().bold(),);
+    println!("\t{} {info}", "Info".yellow().bold(),);
+    std::process::exit(0xFF);
+}
+
 pub fn login_failed(advice: &str) {
     println!("\t{}", "Login failed".red().bold(),);
     println!("\t{} {advice}", "Advice".yellow().bold(),);
@@ -50,6 +56,18 @@ pub fn dependency_resolution_failed(reason: &str) {
     std::process::exit(0xFF);
 }
 
+pub fn dependency_conflict(report: &str) {
+    println!("\t{}", "Failed to resolve dependencies".red().bold(),);
+    println!(
+        "\t{} The requirements of fields conflict with each other:",
+        "Reason".yellow().bold(),
+    );
+    for line in report.lines() {
+        println!("\t\t{line}");
+    }
+    std::process::exit(0xFF);
+}
+
 pub fn linting_failed() {
     println!("\t{}", "Linting failed".red().bold(),);
     println!(
@@ -90,28 +90,25 @@ impl PublicIndexRepository {
     #[allow(clippy::type_complexity)]
     /// Convenience function to re-use code in the several methods in this struct.
     ///
-    /// ## Panics
-    /// Panics, if we can not get the absolute path to the key.
+    /// A key which can not be found fails the authentication with a [`git2::Error`].
     fn get_auth_callback(
         ssh_private_key_path: &Path,
     ) -> Box<dyn FnMut(&str, Option<&str>, CredentialType) -> Result<Cred, git2::Error>> {
         // This is to avoid introducing lifetimes, we don't care about the little overhead this will introduce here.
         // Also we need to make sure that the path is absolute because git2::Credential::ssh_key() expects it to be.
-        let key_path =
-            std::fs::canonicalize(PathBuf::from(ssh_private_key_path)).unwrap_or_else(|_| {
-                panic!(
-                    "Failed to canonicalize path: {}",
-                    ssh_private_key_path.display()
-                )
-            });
+        let key_path = std::fs::canonicalize(ssh_private_key_path).map_err(|err| {
+            format!(
+                "Failed to canonicalize path: {}, {err}",
+                ssh_private_key_path.display()
+            )
+        });
 
         Box::new(
             move |_url, username_from_url: Option<&str>, _allowed_types| {
-                Cred::ssh_key(
-                username_from_url.expect("No username specified for remote. We right now expect the registry to lie on github with a username of `git`."),
-                None,
-                 &key_path,
-                None)
+                let key_path = key_path
+                    .as_ref()
+                    .map_err(|err| git2::Error::from_str(err))?;
+                Cred::ssh_key(username(username_from_url)?, None, key_path, None)
             },
         )
     }
@@ -124,15 +121,18 @@ impl PublicIndexRepository {
         Box::new(
             move |_url, username_from_url: Option<&str>, _allowed_types| {
                 Cred::ssh_key_from_memory(
-                username_from_url.expect("No username specified for remote. We right now expect the registry to lie on github with a username of `git`."),
-                None,
-                 &ssh_private_key,
-                None)
+                    username(username_from_url)?,
+                    None,
+                    &ssh_private_key,
+                    None,
+                )
             },
         )
     }
 
     /// Authenticates via a private ssh key provided in the construction of the [`PublicIndexRepository`].
+    ///
+    /// Without a key the remote is accessed anonymously, which is enough for public repositories.
     pub fn auth(&self) -> anyhow::Result<RemoteCallbacks> {
         let Self {
             ssh_private_key_path,
@@ -150,11 +150,8 @@ impl PublicIndexRepository {
             callbacks.credentials(Self::get_auth_callback_with_key_from_memory(
                 ssh_private_key.clone(),
             ));
-            return Ok(callbacks);
         }
-        Err(anyhow::anyhow!(
-            "Couldn't find a private ssh key to authenticate with."
-        ))
+        Ok(callbacks)
     }
 
     /// Clones the repository, if the repository exists opens it.
@@ -353,3 +350,12 @@ impl PublicIndexRepository {
         Ok(())
     }
 }
+
+/// The user name of an ssh remote, which has to be in its url.
+fn username(username_from_url: Option<&str>) -> Result<&str, git2::Error> {
+    username_from_url.ok_or_else(|| {
+        git2::Error::from_str(
+            "No username specified for remote. We right now expect the registry to lie on github with a username of `git`.",
+        )
+    })
+}
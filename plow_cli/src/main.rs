@@ -95,6 +95,24 @@ pub fn main() {
         //         .takes_value(false)
         //         .action(clap::ArgAction::SetTrue),
         // )
+        .arg(
+            Arg::with_name("offline")
+                .long("offline")
+                .help("Works without the network, from the last updated index and the cached fields.")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("locked")
+                .long("locked")
+                .help("Fails if the lock file is missing or would change. Useful for reproducible runs in CI.")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("frozen")
+                .long("frozen")
+                .help("Same as --locked and does not access the network to update the index or download fields.")
+                .global(true),
+        )
         .arg(
             Arg::with_name("config")
                 .value_name("directory")
@@ -109,6 +127,8 @@ pub fn main() {
         .subcommand(subcommand::init::attach_as_sub_command())
         .subcommand(subcommand::update::attach_as_sub_command())
         .subcommand(subcommand::protege::attach_as_sub_command())
+        .subcommand(subcommand::yank::attach_as_sub_command())
+        .subcommand(subcommand::registry::attach_as_sub_command())
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .setting(AppSettings::SubcommandPrecedenceOverArg);
 
@@ -126,13 +146,22 @@ pub fn main() {
 
     let custom_plow_home_path = options.get_one::<String>("config").map(Utf8PathBuf::from);
     let custom_registry_url = options.get_one::<String>("registry").cloned();
+    let offline = options.is_present("offline");
     // let custom_registry_url = None;
     // let fetch_with_cli = options.get_flag("fetch-with-cli");
     // TODO: This option is not read anymore. And will be removed soon.
     let fetch_with_cli = false;
+    let lock_mode =
+        config::LockMode::from_flags(options.is_present("locked"), options.is_present("frozen"));
 
     let matches = app.clone().get_matches();
-    match config::configure(custom_plow_home_path, custom_registry_url, fetch_with_cli) {
+    match config::configure(
+        custom_plow_home_path,
+        custom_registry_url,
+        offline,
+        fetch_with_cli,
+        lock_mode,
+    ) {
         Ok(ref config) => {
             let mut app_for_help_reference = app.clone();
 
@@ -165,6 +194,14 @@ pub fn main() {
                     subcommand::protege::run_command(sub_matches, config).feedback();
                     Some(())
                 }
+                Some(("yank", sub_matches)) => {
+                    subcommand::yank::run_command(sub_matches, config).feedback();
+                    Some(())
+                }
+                Some(("registry", sub_matches)) => {
+                    subcommand::registry::run_command(sub_matches, config).feedback();
+                    Some(())
+                }
                 _ => None,
             }
             .is_none()
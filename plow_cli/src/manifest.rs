@@ -246,6 +246,9 @@ impl<'manifest> FieldManifest<'manifest> {
             cksum,
             ontology_iri: self.ontology_iri.clone(),
             deps,
+            keywords: self.field_keywords().unwrap_or_default(),
+            categories: self.field_categories().unwrap_or_default(),
+            yanked: false,
         })
     }
 
@@ -579,6 +582,7 @@ impl<'manifest> FieldManifest<'manifest> {
         std::fs::write(target_path, new_doc.to_string()).unwrap();
     }
 
+    /// The `owl:imports` of the field, the IRIs which it imports and the beginning of the IRIs of fields.
     pub fn dependencies_stated_in_owl_imports(
         &self,
     ) -> Result<(ObjectList, Vec<String>, String, Vec<Statement>), Vec<Statement>> {
@@ -605,30 +609,18 @@ impl<'manifest> FieldManifest<'manifest> {
                                             );
 
                                             if prefixed_name == "owl:imports" {
-                                                let mut dep_names = vec![];
                                                 let mut object_iris = vec![];
                                                 for (_, _, object) in &object_list.list {
                                                     if let Object::IRI(IRI::IRIReference(
                                                         ref object_iri,
                                                     )) = object
                                                     {
-                                                        object_iris.push(object_iri);
+                                                        object_iris.push(
+                                                            object_iri.iri.as_ref().to_owned(),
+                                                        );
                                                     }
                                                 }
 
-                                                for object_iri in &object_iris {
-                                                    let iri_literal =
-                                                        object_iri.iri.as_ref().to_owned();
-                                                    let iris = iri_literal
-                                                        .split("/")
-                                                        .collect::<Vec<&str>>();
-                                                    let mut it = iris.iter().rev();
-                                                    it.next();
-                                                    let name = it.next().unwrap().to_owned();
-                                                    let namespace = it.next().unwrap().to_owned();
-                                                    let dep_name = format!("{namespace}/{name}");
-                                                    dep_names.push(dep_name);
-                                                }
                                                 let base_iri_parts =
                                                     base_iri.split("/").collect::<Vec<&str>>();
                                                 let base_iri_beginning = base_iri_parts
@@ -637,7 +629,7 @@ impl<'manifest> FieldManifest<'manifest> {
 
                                                 acc.push((
                                                     object_list.clone(),
-                                                    dep_names,
+                                                    object_iris,
                                                     base_iri_beginning,
                                                     self.statements.clone(),
                                                 ));
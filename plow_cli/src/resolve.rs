@@ -1,30 +1,36 @@
-use std::io::Cursor;
-
+use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
 
 use plow_package_management::{
-    lock::LockFile, package::OrganizationToResolveFor, registry::Registry, resolve::Dependency,
+    checksum,
+    lock::{LockFile, LockFileError, LockOptions, PackageInLockFile},
+    package::{OrganizationToResolveFor, PackageSet, PackageToUpdate, PackageVersion},
+    registry::{http::HttpRegistry, Registry},
+    resolve::{error::ResolverError, Dependency},
+    source::PackageSource,
     version::SemanticVersion,
 };
-use reqwest::StatusCode;
-use sha2::{Digest, Sha256};
 use plow_package_management::package::PackageVersionWithRegistryMetadata;
 
 use crate::{
-    config::PlowConfig,
+    config::{files::workspace_manifest::WorkspaceManifestFile, PlowConfig, RegistryLocation},
     error::CliError,
     manifest::FieldManifest,
 };
-use crate::error::FieldDownloadError::{FailedToDownloadAndCacheField, FailedToReadFieldCache};
-use crate::error::ResolveError::FailedToResolveDependencies;
+use crate::error::FieldDownloadError::{
+    ChecksumMismatch, FailedToDownloadAndCacheField, FailedToQuarantineField,
+    FailedToReadFieldCache, FieldsNotCached, MissingChecksum,
+};
+use crate::error::ResolveError::{
+    ConflictingRequirements, FailedToResolveDependencies, LockFileDisagreesWithRegistry,
+    LockFileOutOfDate, MissingMetadata,
+};
 
-/// Generates the "field hash" that is used by the registry backend.
-///
-/// The field hash is the SHA256 hash of the string `<namespace>/<fieldname> <version`.
-fn generate_field_hash(package_metadata: &PackageVersionWithRegistryMetadata) -> String {
-    let string_to_hash = format!("{package_name} {version}", package_name = package_metadata.package_name, version = package_metadata.version);
-    format!("{:x}", Sha256::digest(&string_to_hash))
-}
+/// How often a field is downloaded before giving up if its contents do not match the checksum.
+const DOWNLOAD_ATTEMPTS: usize = 2;
+
+/// Directory in the field cache where fields which do not match their checksum are moved to.
+const QUARANTINE_DIRECTORY: &str = "quarantine";
 
 #[allow(clippy::missing_panics_doc)]
 #[allow(clippy::unwrap_used)]
@@ -34,9 +40,11 @@ pub fn resolve(
     _: &str,
     root_field_manifest: &FieldManifest,
     respect_existing_lock_file: bool,
+    package_to_update: Option<&PackageToUpdate>,
     registry: &dyn Registry,
 ) -> Result<Option<LockFile>, CliError> {
     let workspace_root = config.get_workspace_root().ok();
+    let without_network = config.lock_mode.is_frozen() || config.is_offline()?;
 
     println!(
         "\t{} to resolve dependencies of {} ..",
@@ -59,7 +67,6 @@ pub fn resolve(
         // Needs to get the workspace root and check for lock file.
         // Either in dep resolver.
         // Read it and rewrite it after resolution.
-        // Update command needs to be created to update the lock file.
 
         // Here.. we extend the entry with the initial package we resolve the deps for. Let's give it a try.
 
@@ -69,163 +76,105 @@ pub fn resolve(
             dependencies: deps,
         };
 
+        // Opted in through the workspace manifest.
+        let allow_multiple_major_versions = workspace_root
+            .as_ref()
+            .and_then(|workspace_root| {
+                WorkspaceManifestFile::from_file(&workspace_root.join("Plow.toml")).ok()
+            })
+            .map_or(false, |workspace_manifest| {
+                workspace_manifest.allows_multiple_major_versions()
+            });
+
         let locked_and_resolved = LockFile::lock_with_registry(
             entry,
             registry,
             workspace_root,
-            respect_existing_lock_file,
+            LockOptions {
+                respect_existing_lock_file,
+                package_to_update,
+                allow_multiple_major_versions,
+                locked: config.lock_mode.is_locked(),
+            },
         )
-        .map_err(|err| CliError::from(FailedToResolveDependencies(err.to_string())))?;
+        .map_err(|err| match err.downcast::<ResolverError>() {
+            Ok(ResolverError::Conflict(report)) => CliError::from(ConflictingRequirements(report)),
+            Ok(err @ ResolverError::LockFileOutOfDate { .. }) => {
+                CliError::from(LockFileOutOfDate(err.to_string()))
+            }
+            Ok(err) if without_network => CliError::from(FailedToResolveDependencies(format!(
+                "{err}\n\tThe index was not updated, fields which are missing from the local index would need the network."
+            ))),
+            Ok(err) => CliError::from(FailedToResolveDependencies(err.to_string())),
+            Err(err) => match err.downcast::<LockFileError>() {
+                Ok(err @ LockFileError::ChecksumMismatch { .. }) => {
+                    CliError::from(LockFileDisagreesWithRegistry(err.to_string()))
+                }
+                Ok(err) => CliError::from(LockFileOutOfDate(err.to_string())),
+                Err(err) => CliError::from(FailedToResolveDependencies(err.to_string())),
+            },
+        })?;
 
         let metadatas = locked_and_resolved
             .locked_dependencies
             .packages
             .iter()
-            .map(|package_version| {
-                // Dependency resolution would catch this earlier.
-                // Unwrap is fine.
-                registry
-                    .get_package_version_metadata(package_version)
-                    .unwrap()
-            })
-            .collect::<Vec<_>>();
-
-        let paths = crate::utils::list_files(&config.field_cache_dir, "ttl").map_err(|err| {
-            CliError::from(FailedToReadFieldCache {
-                reason: err.to_string(),
-            })
-        })?;
-
-        let stems = paths
-            .iter()
-            .map(|path| path.file_stem().unwrap())
-            .collect::<Vec<&str>>();
+            .map(|package_version| metadata_of_resolved(registry, package_version))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // Cache check.
-        let package_versions_to_download = metadatas
-            .iter()
-            .filter(|metadata| !stems.contains(&metadata.cksum.clone().expect("Unable to download package with missing checksum").as_str()))
-            .collect::<Vec<_>>();
-
-        let client = reqwest::blocking::Client::new();
-        let registry_url = config.get_registry_url()?;
-        let token = config.get_saved_api_token()?;
-
-        for package_version_to_download in package_versions_to_download {
-            let download = package_version_to_download.cksum.clone().unwrap();
-            let package_name = &package_version_to_download.package_name;
-            println!("\t{} to download field contents ..", "Attempting".bold());
-
-            let field_hash = generate_field_hash(package_version_to_download);
-            let signed_url_request = format!("{registry_url}/v1/artifact/signed-url-by-field-hash/{field_hash}");
+        // Yanked versions are only resolved if they are in the lock file already.
+        for metadata in metadatas.iter().filter(|metadata| metadata.yanked) {
+            println!(
+                "\t{} {} {} is yanked, it is kept because it is locked. You may update it with plow update.",
+                "Warning".yellow().bold(),
+                metadata.package_name.bold(),
+                metadata.version
+            );
+        }
 
-            let mut signed_url_request = client
-                .get(signed_url_request);
-            // Only apply auth header when retrieving private fields
-            if package_version_to_download.private {
-                signed_url_request = signed_url_request.header("Authorization", &format!("Basic {token}"));
-            }
-            let signed_url_response = signed_url_request
-                .send()
-                .map_err(|err| {
-                    CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: format!(
-                            "Attempt of retrieving a download link for the field failed. Error: {err}"
-                        ),
-                    })
-                })?;
-
-            let status = signed_url_response.status();
-            if !status.is_success() {
-                if status == StatusCode::NOT_FOUND {
-                    return Err(CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: "The field was not found in registry.".to_owned(),
-                    }));
-                }
-                return Err(CliError::from(FailedToDownloadAndCacheField {
-                    package_name: package_name.clone(),
-                    reason: format!("Download request failed with status code: {status}"),
-                }));
+        // Cache check, a cached field is only used if its contents match the checksum.
+        let mut package_versions_to_download = vec![];
+        for metadata in &metadatas {
+            let cksum = metadata.cksum.clone().ok_or_else(|| {
+                CliError::from(MissingChecksum {
+                    package_name: metadata.package_name.clone(),
+                    version: metadata.version.to_string(),
+                })
+            })?;
+            let cached_path = config.field_cache_dir.join(format!("{cksum}.ttl"));
+            if !cached_path.exists() {
+                package_versions_to_download.push(metadata);
+                continue;
             }
-
-            let response_body_value =
-                signed_url_response
-                    .json::<serde_json::Value>()
-                    .map_err(|_| {
-                        CliError::from(FailedToDownloadAndCacheField {
-                            package_name: package_name.clone(),
-                            reason: "Corrupt download link retrieved.".to_owned(),
-                        })
-                    })?;
-
-            let signed_url = response_body_value
-                .get("data")
-                .ok_or_else(|| {
-                    CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: "Corrupt download link retrieved.".to_owned(),
-                    })
-                })?
-                .as_object()
-                .ok_or_else(|| {
-                    CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: "Corrupt download link retrieved.".to_owned(),
-                    })
-                })?
-                .get("url")
-                .ok_or_else(|| {
-                    CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: "Corrupt download link retrieved.".to_owned(),
-                    })
-                })?
-                .as_str()
-                .ok_or_else(|| {
-                    CliError::from(FailedToDownloadAndCacheField {
-                        package_name: package_name.clone(),
-                        reason: "Corrupt download link retrieved.".to_owned(),
-                    })
-                })?;
-
-            let download_result = client.get(signed_url).send().map_err(|err| {
-                CliError::from(FailedToDownloadAndCacheField {
-                    package_name: package_name.clone(),
-                    reason: format!("Download link is invalid. Error: {err}"),
+            let cached_contents = std::fs::read(&cached_path).map_err(|err| {
+                CliError::from(FailedToReadFieldCache {
+                    reason: err.to_string(),
                 })
             })?;
+            if checksum::matches(&cached_contents, &cksum) {
+                continue;
+            }
+            println!(
+                "\t{} of {} does not match its checksum, downloading it again ..",
+                "Cached field".yellow().bold(),
+                metadata.package_name.bold()
+            );
+            quarantine(config, &cached_path, &cksum)?;
+            package_versions_to_download.push(metadata);
+        }
 
-            let mut file =
-                std::fs::File::create(&config.field_cache_dir.join(format!("{download}.ttl")))
-                    .map_err(|err| {
-                        CliError::from(FailedToDownloadAndCacheField {
-                            package_name: package_name.clone(),
-                            reason: format!(
-                                "Couldn't write retrieved field to the filesystem. Error: {err}"
-                            ),
-                        })
-                    })?;
-
-            let mut content = Cursor::new(download_result.bytes().map_err(|err| {
-                CliError::from(FailedToDownloadAndCacheField {
-                    package_name: package_name.clone(),
-                    reason: format!(
-                        "Couldn't write retrieved field to the filesystem. Error: {err}"
-                    ),
-                })
-            })?);
+        // Without the network only cached fields can be used, report all which would need a download.
+        if without_network && !package_versions_to_download.is_empty() {
+            return Err(CliError::from(FieldsNotCached {
+                fields: package_versions_to_download
+                    .iter()
+                    .map(|metadata| format!("{} {}", metadata.package_name, metadata.version))
+                    .collect(),
+            }));
+        }
 
-            std::io::copy(&mut content, &mut file).map_err(|err| {
-                CliError::from(FailedToDownloadAndCacheField {
-                    package_name: package_name.clone(),
-                    reason: format!(
-                        "Couldn't write retrieved field to the filesystem. Error: {err}"
-                    ),
-                })
-            })?;
-            println!("\t{} successful.", "Download".green().bold());
+        if !package_versions_to_download.is_empty() {
+            download_fields(config, &package_versions_to_download)?;
         }
 
         println!(
@@ -244,3 +193,177 @@ pub fn resolve(
 
     Ok(None)
 }
+
+/// The metadata of a resolved package version from the registry which it was resolved from.
+pub fn metadata_of_resolved(
+    registry: &dyn Registry,
+    package_version: &PackageVersion,
+) -> Result<PackageVersionWithRegistryMetadata, CliError> {
+    registry
+        .get_package_version_metadata(package_version)
+        .map_err(|err| {
+            CliError::from(MissingMetadata {
+                package: package_version.to_string(),
+                reason: err.to_string(),
+            })
+        })
+}
+
+/// The entry of a resolved dependency in the lock file.
+pub fn locked_package(
+    registry: &dyn Registry,
+    package_version: &PackageVersion,
+    locked_dependencies: &PackageSet,
+) -> Result<PackageInLockFile, CliError> {
+    let metadata = metadata_of_resolved(registry, package_version)?;
+    Ok(PackageInLockFile {
+        name: package_version.package_name.clone(),
+        version: package_version.version.clone(),
+        ontology_iri: metadata.major_version_ontology_iri(),
+        source: metadata.source.clone(),
+        cksum: metadata.cksum.clone(),
+        dependencies: PackageInLockFile::dependency_entries(
+            &metadata.dependencies,
+            locked_dependencies,
+        ),
+        root: false,
+    })
+}
+
+/// Downloads fields into the cache, retrying once if the contents do not match the checksum.
+#[allow(clippy::unwrap_used)]
+fn download_fields(
+    config: &PlowConfig,
+    package_versions_to_download: &[&PackageVersionWithRegistryMetadata],
+) -> Result<(), CliError> {
+    let registries = config.get_registries()?;
+
+    for package_version_to_download in package_versions_to_download.iter().copied() {
+        // Fields are downloaded from the registry which they come from.
+        let registry = registry_of_field(&registries, package_version_to_download).unwrap();
+        let token = config.get_registry_token(registry.name.as_deref())?;
+        let http_registry = HttpRegistry::new(&registry.url).with_token(&token);
+        let download = package_version_to_download.cksum.clone().unwrap();
+        let package_name = &package_version_to_download.package_name;
+        let cached_path = config.field_cache_dir.join(format!("{download}.ttl"));
+
+        // A corrupt download is tried once more before giving up.
+        let mut attempts = 0;
+        let content = loop {
+            attempts += 1;
+            println!("\t{} to download field contents ..", "Attempting".bold());
+            let content = download_field(&http_registry, package_version_to_download)?;
+            if checksum::matches(&content, &download) {
+                break content;
+            }
+            std::fs::write(&cached_path, &content).map_err(|err| {
+                CliError::from(FailedToDownloadAndCacheField {
+                    package_name: package_name.clone(),
+                    reason: format!(
+                        "Couldn't write retrieved field to the filesystem. Error: {err}"
+                    ),
+                })
+            })?;
+            let quarantined_path = quarantine(config, &cached_path, &download)?;
+            if attempts >= DOWNLOAD_ATTEMPTS {
+                return Err(CliError::from(ChecksumMismatch {
+                    package_name: package_name.clone(),
+                    expected: download,
+                    actual: checksum::compute(&content),
+                    quarantined_path: quarantined_path.to_string(),
+                }));
+            }
+            println!(
+                "\t{} does not match its checksum, downloading it again ..",
+                "Downloaded field".yellow().bold(),
+            );
+        };
+
+        std::fs::write(&cached_path, &content).map_err(|err| {
+            CliError::from(FailedToDownloadAndCacheField {
+                package_name: package_name.clone(),
+                reason: format!("Couldn't write retrieved field to the filesystem. Error: {err}"),
+            })
+        })?;
+        println!("\t{} successful.", "Download".green().bold());
+    }
+
+    Ok(())
+}
+
+/// The registry which a field comes from, recognized by the index it is in.
+///
+/// Fields of which the index is not known come from the default registry, which is the first one.
+fn registry_of_field<'reg>(
+    registries: &'reg [RegistryLocation],
+    metadata: &PackageVersionWithRegistryMetadata,
+) -> Option<&'reg RegistryLocation> {
+    registries
+        .iter()
+        .find(|registry| match metadata.source {
+            Some(PackageSource::PrivateIndex(ref url)) => &registry.url == url,
+            Some(PackageSource::PublicIndex(ref url)) => {
+                registry.public_index_url.as_ref() == Some(url)
+            }
+            _ => false,
+        })
+        .or_else(|| registries.first())
+}
+
+/// Fails if the lock file would change by writing the given packages, with `--locked` or `--frozen`.
+pub fn fail_if_lock_file_would_change(
+    config: &PlowConfig,
+    lock_file_contents: &[PackageInLockFile],
+) -> Result<(), CliError> {
+    if !config.lock_mode.is_locked() {
+        return Ok(());
+    }
+    let would_change =
+        LockFile::would_change(Some(config.working_dir.path.clone()), lock_file_contents)
+            .map_err(|err| CliError::from(LockFileOutOfDate(err.to_string())))?;
+    if would_change {
+        return Err(CliError::from(LockFileOutOfDate(
+            "The lock file needs to be updated but it is required to stay as it is.".to_owned(),
+        )));
+    }
+    Ok(())
+}
+
+/// Downloads the contents of a field through a signed url from the registry.
+fn download_field(
+    registry: &HttpRegistry,
+    package_version_to_download: &PackageVersionWithRegistryMetadata,
+) -> Result<Vec<u8>, CliError> {
+    registry
+        .download(package_version_to_download)
+        .map_err(|err| {
+            CliError::from(FailedToDownloadAndCacheField {
+                package_name: package_version_to_download.package_name.clone(),
+                reason: err.to_string(),
+            })
+        })
+}
+
+/// Moves a field which does not match its checksum out of the cache, to keep it for inspection.
+fn quarantine(
+    config: &PlowConfig,
+    path: &Utf8Path,
+    cksum: &str,
+) -> Result<Utf8PathBuf, CliError> {
+    let quarantine_dir = config.field_cache_dir.join(QUARANTINE_DIRECTORY);
+    let quarantined_path = quarantine_dir.join(format!(
+        "{cksum}-{}.quarantined",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    ));
+    std::fs::create_dir_all(&quarantine_dir)
+        .and_then(|_| std::fs::rename(path, &quarantined_path))
+        .map_err(|err| {
+            CliError::from(FailedToQuarantineField {
+                path: path.to_string(),
+                reason: err.to_string(),
+            })
+        })?;
+    Ok(quarantined_path)
+}
@@ -3,5 +3,7 @@ pub mod lint;
 pub mod list;
 pub mod login;
 pub mod protege;
+pub mod registry;
 pub mod submit;
 pub mod update;
+pub mod yank;
@@ -6,13 +6,15 @@ use std::path::PathBuf;
 use dialoguer::console::Style;
 use plow_package_management::lock::{LockFile, PackageInLockFile};
 use plow_package_management::registry::Registry;
+use plow_package_management::source::PackageSource;
 
 use self::fields::FieldsDirectory;
 use crate::config::files::workspace_manifest::WorkspaceManifestFile;
 use crate::config::PlowConfig;
 use crate::feedback::info;
 use crate::manifest::FieldManifest;
-use crate::resolve::resolve;
+use crate::resolve::{fail_if_lock_file_would_change, locked_package, resolve};
+use crate::subcommand::lint::WorkspaceLints;
 use crate::{error::CliError, error::FieldAccessError::*, error::WorkspaceInitializationError::*};
 
 use dialoguer::{theme::ColorfulTheme, Confirm};
@@ -39,6 +41,9 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
         .interact()
         .unwrap()
     {
+        // Lint the fields with the lints of the workspace which is initialized again, if there is one.
+        let workspace_lints = WorkspaceLints::of_workspace(config)?;
+
         // Clean up before creation if running in a workspace
         let manifest_file_path = config.working_dir.path.join("Plow.toml");
 
@@ -47,10 +52,10 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
                 .map_err(|err| FailedToRemoveWorkspaceManifestFile(err.to_string()))?;
         }
 
-        // Clean up before creation
+        // Clean up before creation, unless the lock file is required to stay as it is.
         let lock_file_path = config.working_dir.path.join("Plow.lock");
 
-        if lock_file_path.exists() {
+        if lock_file_path.exists() && !config.lock_mode.is_locked() {
             std::fs::remove_file(&lock_file_path)
                 .map_err(|err| FailedToRemoveWorkspaceManifestFile(err.to_string()))?;
         }
@@ -74,7 +79,7 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
             return Err(NoFieldsInDirectory.into());
         }
 
-        let linting_failures = fields_dir.lint_all_children();
+        let linting_failures = fields_dir.lint_all_children(&workspace_lints);
 
         // Remove the paths from the list of found fields which has failed lints.
         if let Some((ref failed_paths, _)) = linting_failures {
@@ -107,7 +112,7 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
                 .map_err(|err| FailedToRemoveBackupFieldsDirectory(err.to_string()))?;
         }
 
-        let registry = crate::sync::sync(config)?;
+        let registry = crate::sync::sync(config, true)?;
 
         // root -> (resolved_root, deps of root[including transative])
         let mut collection: HashMap<String, (PackageInLockFile, LockFile)> = HashMap::new();
@@ -132,13 +137,22 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
                 .field_dependency_names()
                 .unwrap_or_default();
 
-            if let Ok(Some(fresh_lock_file)) = resolve(
+            let resolved = resolve(
                 config,
                 &root_field_contents,
                 &root_field_manifest,
                 false,
+                None,
                 &registry as &dyn Registry,
-            ) {
+            );
+            // A field which fails to resolve would be missing from the lock file.
+            let fresh_lock_file = if config.lock_mode.is_locked() {
+                resolved?
+            } else {
+                resolved.ok().flatten()
+            };
+
+            if let Some(fresh_lock_file) = fresh_lock_file {
                 // Unwrap is fine here we've linted the field before.
                 #[allow(clippy::unwrap_used)]
                 let root_as_index = root_field_manifest.make_index_from_manifest().unwrap();
@@ -153,7 +167,14 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
                             name: root_as_index.name,
                             version: root_as_index.version,
                             ontology_iri: root_as_index.ontology_iri,
-                            source: None,
+                            // Fields of the workspace are locked by their path relative to it.
+                            source: Some(PackageSource::Path(
+                                child
+                                    .as_path()
+                                    .strip_prefix(&config.working_dir.path)
+                                    .unwrap_or_else(|_| child.as_path())
+                                    .to_string(),
+                            )),
                             cksum: Some(root_as_index.cksum),
                             dependencies: root_dep_names,
                             root: true,
@@ -164,41 +185,19 @@ pub fn prepare(config: &PlowConfig) -> Result<(), CliError> {
             }
         }
 
-        let lock_file_contents = collection
-            .into_iter()
-            .flat_map(|(_, (root, locked_deps))| {
-                let mut v = vec![];
-                v.push(root);
-                let deps = locked_deps
-                    .locked_dependencies
-                    .packages
-                    .iter()
-                    .map(|package_version| {
-                        // Safe here, we passed dep resolution.
-                        #[allow(clippy::unwrap_used)]
-                        let metadata = registry
-                            .get_package_version_metadata(package_version)
-                            .unwrap();
-                        PackageInLockFile {
-                            name: package_version.package_name.clone(),
-                            version: package_version.version.clone(),
-                            ontology_iri: metadata.ontology_iri.clone(),
-                            source: None,
-                            cksum: metadata.cksum.clone(),
-                            dependencies: metadata
-                                .dependencies
-                                .iter()
-                                .cloned()
-                                .map(|dep| dep.full_name)
-                                .collect(),
-                            root: false,
-                        }
-                    })
-                    .collect::<Vec<_>>();
-                v.extend(deps);
-                v
-            })
-            .collect::<Vec<_>>();
+        let mut lock_file_contents = vec![];
+        for (_, (root, locked_deps)) in collection {
+            lock_file_contents.push(root);
+            for package_version in &locked_deps.locked_dependencies.packages {
+                lock_file_contents.push(locked_package(
+                    &registry,
+                    package_version,
+                    &locked_deps.locked_dependencies,
+                )?);
+            }
+        }
+
+        fail_if_lock_file_would_change(config, &lock_file_contents)?;
 
         if !lock_file_contents.is_empty() {
             LockFile::write(Some(config.working_dir.path.clone()), &lock_file_contents)
@@ -17,7 +17,7 @@ use crate::config::PlowConfig;
 use crate::error::CliError;
 use crate::error::WorkspaceInitializationError::*;
 use crate::manifest::FieldManifest;
-use crate::subcommand::lint::lint_file_fail_on_failure;
+use crate::subcommand::lint::{lint_file_fail_on_failure, WorkspaceLints};
 
 // For comparisons later.
 fn hash_file_with_name(path: &Utf8Path) -> Option<String> {
@@ -193,7 +193,10 @@ impl FieldsDirectory {
         self.path.exists()
     }
 
-    pub fn lint_all_children(&self) -> Option<(Vec<String>, CliError)> {
+    pub fn lint_all_children(
+        &self,
+        workspace_lints: &WorkspaceLints,
+    ) -> Option<(Vec<String>, CliError)> {
         // Lint all fields in the directory and collect failures if there are some.
         let failed_field_paths_on_linting = self
             .children
@@ -210,7 +213,9 @@ impl FieldsDirectory {
                 );
 
                 // TODO: Field manifest or all lints?
-                if let Err(err) = lint_file_fail_on_failure(child.as_path().as_ref(), lints) {
+                if let Err(err) =
+                    lint_file_fail_on_failure(child.as_path().as_ref(), lints, workspace_lints)
+                {
                     Some(err)
                 } else {
                     None
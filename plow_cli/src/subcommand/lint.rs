@@ -1,12 +1,19 @@
 use camino::Utf8PathBuf;
-use clap::{arg, App, Command};
+use clap::{arg, App, Arg, Command};
 use clap::{AppSettings, ArgMatches};
 use colored::*;
-use plow_linter::lint::LintResult;
+use harriet::TurtleDocument;
+use plow_linter::custom_lints::{CustomLint, Rule};
+use plow_linter::lint::{FixSuggestion, LintLevel, Severity};
 use plow_linter::lints::*;
+use plow_linter::sarif::sarif_log;
+use plow_linter::source_map::Span;
 use plow_linter::Linter;
 use plow_package_management::registry::Registry;
+use similar::TextDiff;
+use std::collections::{HashMap, HashSet};
 
+use crate::config::files::workspace_manifest::WorkspaceManifestFile;
 use crate::config::PlowConfig;
 use crate::error::CliError;
 use crate::error::FieldAccessError::*;
@@ -14,10 +21,35 @@ use crate::error::LintSubcommandError::*;
 use crate::feedback::{field_info, general_lint_success, lint_start, Feedback};
 use crate::manifest::FieldManifest;
 use crate::resolve::resolve;
-pub struct SuccessfulLint;
+/// How the results of the lints are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintOutputFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl LintOutputFormat {
+    fn from_matches(sub_matches: &ArgMatches) -> Self {
+        match sub_matches.get_one::<String>("format").map(String::as_str) {
+            Some("json") => Self::Json,
+            Some("sarif") => Self::Sarif,
+            _ => Self::Human,
+        }
+    }
+}
+
+pub struct SuccessfulLint {
+    format: LintOutputFormat,
+    /// Only the fixes were previewed, the field was not linted.
+    dry_run: bool,
+}
 impl Feedback for SuccessfulLint {
     fn feedback(&self) {
-        general_lint_success();
+        // Machine-readable results are not followed by anything else.
+        if self.format == LintOutputFormat::Human && !self.dry_run {
+            general_lint_success();
+        }
     }
 }
 
@@ -25,6 +57,26 @@ pub fn attach_as_sub_command() -> App<'static> {
     Command::new("lint")
         .about("Lints a field.")
         .arg(arg!([FIELD_PATH]))
+        .arg(
+            Arg::with_name("format")
+                .value_name("format")
+                .long("format")
+                .help("Prints the results of the lints for humans, as JSON or as SARIF. Dependencies are only resolved for humans, to not print anything else with the results.")
+                .possible_values(["human", "json", "sarif"])
+                .default_value("human")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("fix")
+                .long("fix")
+                .help("Applies the fixes which the lints suggest to the field before linting it."),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .requires("fix")
+                .help("Prints the changes which --fix would make to the field as a diff instead of writing them."),
+        )
         .setting(AppSettings::ArgRequiredElseHelp)
 }
 
@@ -45,15 +97,51 @@ pub fn run_command_flow(
         .ok_or(NoFieldProvidedToLint)?;
 
     let field = camino::Utf8PathBuf::from(field_file_path);
+    let format = LintOutputFormat::from_matches(sub_matches);
+    let workspace_lints = WorkspaceLints::of_workspace(config)?;
+    let lints = all_lints();
+
+    if sub_matches.is_present("fix") {
+        if !field.exists() {
+            return Err(FailedToFindFieldToLint {
+                field_path: field.into(),
+            }
+            .into());
+        }
+        let dry_run = sub_matches.is_present("dry-run");
+        fix_file(field_file_path, &workspace_lints, dry_run, format)?;
+        if dry_run {
+            return Ok(SuccessfulLint { format, dry_run });
+        }
+    }
+
+    if format != LintOutputFormat::Human {
+        if !field.exists() {
+            return Err(FailedToFindFieldToLint {
+                field_path: field.into(),
+            }
+            .into());
+        }
+        lint_file_as(field_file_path, lints, format, &workspace_lints)?;
+        return Ok(SuccessfulLint {
+            format,
+            dry_run: false,
+        });
+    }
 
     field_info(&field)?;
 
     if field.exists() {
-        lint_file(field_file_path, all_lints())?;
+        lint_file_as(
+            field_file_path,
+            lints,
+            LintOutputFormat::Human,
+            &workspace_lints,
+        )?;
 
         let path = Utf8PathBuf::from(field_file_path);
 
-        let registry = crate::sync::sync(config)?;
+        let registry = crate::sync::sync(config, false)?;
 
         let root_field_contents = std::fs::read_to_string(&path).map_err(|_| {
             CliError::from(FailedToFindFieldAtPath {
@@ -71,6 +159,7 @@ pub fn run_command_flow(
             &root_field_contents,
             &root_field_manifest,
             true,
+            None,
             &registry as &dyn Registry,
         )? {
             // Leave an empty line in between.
@@ -90,7 +179,10 @@ pub fn run_command_flow(
                 });
         }
 
-        return Ok(SuccessfulLint);
+        return Ok(SuccessfulLint {
+            format,
+            dry_run: false,
+        });
     }
 
     Err(FailedToFindFieldToLint {
@@ -99,63 +191,269 @@ pub fn run_command_flow(
     .into())
 }
 
-pub fn lint_file(field_path: &str, lints: Vec<LintSet>) -> Result<(), CliError> {
+/// How the workspace configures its lints, with the `[lints]` and `[[custom-lints]]` tables of `Plow.toml`.
+///
+/// Every command which lints fields reports the lints at these levels, runs the custom lints after the other ones
+/// and leaves out what the fields allow with `registry:allowLint`. Registries still enforce the lints which they require for submissions.
+#[derive(Debug, Default)]
+pub struct WorkspaceLints {
+    lint_levels: HashMap<LintName, LintLevel>,
+    custom_lints: Vec<CustomLint>,
+}
+
+impl WorkspaceLints {
+    /// The lints of the workspace which the command runs in, the defaults outside of a workspace.
+    pub fn of_workspace(config: &PlowConfig) -> Result<Self, CliError> {
+        let workspace_manifest = config.get_workspace_root().map_or_else(
+            |_| Ok(None),
+            |workspace_root| {
+                WorkspaceManifestFile::from_file(&workspace_root.join("Plow.toml")).map(Some)
+            },
+        )?;
+        workspace_manifest.map_or_else(
+            || Ok(Self::default()),
+            |workspace_manifest| {
+                Ok(Self {
+                    lint_levels: workspace_manifest.lint_levels(),
+                    custom_lints: custom_lints(&workspace_manifest)?,
+                })
+            },
+        )
+    }
+
+    /// A linter for the field which reports the lints at the levels of the workspace.
+    fn linter<'field>(
+        &self,
+        field_path: &str,
+        field_contents: &'field str,
+    ) -> Result<Linter<'field>, CliError> {
+        let mut linter = Linter::try_from(field_contents).map_err(|_| FailedToParseField {
+            field_path: field_path.to_owned(),
+        })?;
+        linter.set_lint_levels(&self.lint_levels);
+        Ok(linter)
+    }
+
+    /// The custom lints of the workspace as a set of their own, if it has any.
+    fn custom_lint_set(&self) -> Option<LintSet> {
+        if self.custom_lints.is_empty() {
+            return None;
+        }
+        let custom_lints = self
+            .custom_lints
+            .iter()
+            .map(|custom_lint| Box::new(custom_lint.clone()) as PlowLint)
+            .collect();
+        Some(LintSet::new(
+            "custom lints of the workspace",
+            custom_lints,
+            None,
+        ))
+    }
+}
+
+/// Loads the `[[custom-lints]]` of the workspace.
+fn custom_lints(workspace_manifest: &WorkspaceManifestFile) -> Result<Vec<CustomLint>, CliError> {
+    let mut custom_lints = vec![];
+    let mut names = HashSet::new();
+    for definition in workspace_manifest.custom_lints.iter().flatten() {
+        let failed = |details: String| FailedToLoadCustomLint {
+            name: definition.name.clone(),
+            details,
+        };
+        if !names.insert(&definition.name) {
+            return Err(
+                failed("There is another custom lint with the same name.".to_owned()).into(),
+            );
+        }
+        let rule = Rule {
+            class: definition.class.clone(),
+            predicate: definition.predicate.clone(),
+            min_count: definition.min_count,
+            max_count: definition.max_count,
+            datatype: definition.datatype.clone(),
+            pattern: definition.pattern.clone(),
+        };
+        let custom_lint = CustomLint::new(
+            &definition.name,
+            &definition.description,
+            rule,
+            definition.message.as_deref(),
+        )
+        .map_err(|err| failed(err.to_string()))?;
+        custom_lints.push(custom_lint);
+    }
+    Ok(custom_lints)
+}
+
+/// Applies the fixes which the lints suggest for the field and writes it back, or prints them as a diff on a dry run.
+///
+/// Fixes are made to the parsed field, so everything they do not touch stays as it was written.
+fn fix_file(
+    field_path: &str,
+    workspace_lints: &WorkspaceLints,
+    dry_run: bool,
+    format: LintOutputFormat,
+) -> Result<(), CliError> {
     let field_contents = std::fs::read_to_string(field_path).map_err(|err| FailedToReadField {
         field_path: field_path.to_owned(),
         details: err.to_string(),
     })?;
 
-    let mut contains_failures = false;
-    let mut linter = Linter::try_from(field_contents.as_ref()).map_err(|_| FailedToParseField {
+    let mut linter = workspace_lints.linter(field_path, &field_contents)?;
+    for lint_set in all_lints() {
+        linter.add_lint_set(lint_set);
+    }
+    let fixes = linter.suggest_fixes();
+    if fixes.is_empty() {
+        if format == LintOutputFormat::Human || dry_run {
+            println!("\t{} for the lints to fix.", "Nothing".green().bold());
+        }
+        return Ok(());
+    }
+
+    let mut document =
+        TurtleDocument::parse_full(&field_contents).map_err(|_| FailedToParseField {
+            field_path: field_path.to_owned(),
+        })?;
+    for fix in &fixes {
+        fix.apply(&mut document);
+    }
+    let fixed_contents = document.to_string();
+
+    if dry_run {
+        print!(
+            "{}",
+            TextDiff::from_lines(&field_contents, &fixed_contents)
+                .unified_diff()
+                .header(field_path, field_path)
+        );
+        return Ok(());
+    }
+    std::fs::write(field_path, &fixed_contents).map_err(|err| FailedToWriteFixedField {
         field_path: field_path.to_owned(),
+        details: err.to_string(),
     })?;
+    if format == LintOutputFormat::Human {
+        for fix in &fixes {
+            println!("\t{} {fix}", "Fixed".green().bold());
+        }
+    }
+    Ok(())
+}
+
+/// Lints the field with the lints of the workspace and prints their results for humans.
+pub fn lint_file(
+    field_path: &str,
+    lints: Vec<LintSet>,
+    workspace_lints: &WorkspaceLints,
+) -> Result<(), CliError> {
+    lint_file_as(field_path, lints, LintOutputFormat::Human, workspace_lints)
+}
 
-    for lint_set in lints {
+pub fn lint_file_as(
+    field_path: &str,
+    lints: Vec<LintSet>,
+    format: LintOutputFormat,
+    workspace_lints: &WorkspaceLints,
+) -> Result<(), CliError> {
+    let field_contents = std::fs::read_to_string(field_path).map_err(|err| FailedToReadField {
+        field_path: field_path.to_owned(),
+        details: err.to_string(),
+    })?;
+
+    let mut linter = workspace_lints.linter(field_path, &field_contents)?;
+
+    let mut reports = vec![];
+    for lint_set in lints.into_iter().chain(workspace_lints.custom_lint_set()) {
         let set_id = lint_set.id;
         let set_name = linter.add_lint_set(lint_set);
 
-        lint_start(&set_name);
-
-        let results = linter.run_lint_set(set_id);
+        let set_reports = linter.run_lint_set_with_reports(set_id);
 
-        for result in results {
-            use LintResult::*;
-            match result {
-                Success(message) => {
-                    println!("\t\t{}", message.green());
-                }
-                Warning(messages) => {
-                    for message in messages {
-                        println!("\t\t{}", message.yellow());
-                    }
-                }
-                Failure(messages) => {
-                    for message in messages {
-                        println!("\t\t{}", message.red());
-                    }
-                    contains_failures = true;
+        if format == LintOutputFormat::Human {
+            lint_start(&set_name);
+            for report in &set_reports {
+                let message = match report.severity {
+                    Severity::Success => report.message.green(),
+                    Severity::Warning => report.message.yellow(),
+                    Severity::Failure => report.message.red(),
+                };
+                println!("\t\t{message}");
+                if let (Severity::Warning | Severity::Failure, Some(span)) =
+                    (report.severity, report.span)
+                {
+                    println!("{}", source_excerpt(field_path, &field_contents, span));
                 }
             }
         }
+        reports.extend(set_reports);
     }
 
-    if contains_failures {
-        return Err(LintsContainFailuresOpaque.into());
+    match format {
+        LintOutputFormat::Human => {}
+        LintOutputFormat::Json => println!("{}", serde_json::json!(reports)),
+        LintOutputFormat::Sarif => println!("{}", sarif_log(&reports, field_path)),
+    }
+
+    if reports
+        .iter()
+        .any(|report| report.severity == Severity::Failure)
+    {
+        if format == LintOutputFormat::Human {
+            return Err(LintsContainFailuresOpaque.into());
+        }
+        return Err(LintsContainFailuresReported.into());
     }
     Ok(())
 }
 
-pub fn lint_file_fail_on_failure(field_path: &str, lints: LintSet) -> Result<(), CliError> {
+/// Shows the line of the span with carets under it, like rustc does.
+fn source_excerpt(field_path: &str, field_contents: &str, span: Span) -> String {
+    let source_line = field_contents
+        .lines()
+        .nth(span.line - 1)
+        .unwrap_or_default();
+    let gutter = " ".repeat(span.line.to_string().len());
+    // Tabs are kept so that the carets line up with the line above them.
+    let indentation = source_line
+        .chars()
+        .take(span.column - 1)
+        .map(|character| if character == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let width = if span.end_line == span.line {
+        span.end_column.saturating_sub(span.column)
+    } else {
+        source_line.chars().count().saturating_sub(span.column - 1)
+    };
+    let carets = "^".repeat(width.max(1));
+    format!(
+        "\t\t{gutter}{arrow} {field_path}:{line}:{column}\n\t\t{gutter} {bar}\n\t\t{line_number} {bar} {source_line}\n\t\t{gutter} {bar} {indentation}{carets}",
+        arrow = "-->".blue().bold(),
+        bar = "|".blue().bold(),
+        line = span.line,
+        line_number = span.line.to_string().blue().bold(),
+        column = span.column,
+        carets = carets.bold(),
+    )
+}
+
+pub fn lint_file_fail_on_failure(
+    field_path: &str,
+    lints: LintSet,
+    workspace_lints: &WorkspaceLints,
+) -> Result<(), CliError> {
     let field_contents = std::fs::read_to_string(field_path).map_err(|err| FailedToReadField {
         field_path: field_path.to_owned(),
         details: err.to_string(),
     })?;
 
-    let mut linter = Linter::try_from(field_contents.as_ref()).map_err(|_| FailedToParseField {
-        field_path: field_path.to_owned(),
-    })?;
+    let mut linter = workspace_lints.linter(field_path, &field_contents)?;
 
     linter.add_lint_set(lints);
+    if let Some(custom_lint_set) = workspace_lints.custom_lint_set() {
+        linter.add_lint_set(custom_lint_set);
+    }
 
     if linter.run_lints_check_if_contains_any_failure() {
         return Err(SingleLintContainsFailure {
@@ -1,36 +1,56 @@
 use crate::{config::PlowConfig, error::CliError, feedback::Feedback};
 
-use clap::{arg, App, AppSettings, ArgMatches, Command};
+use clap::{arg, App, AppSettings, Arg, ArgMatches, Command};
 use colored::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::error::LoginError::*;
 
-#[derive(Serialize, Debug, Deserialize)]
-pub struct CredentialsFile<'cred> {
-    #[serde(borrow)]
-    pub registry: Registry<'cred>,
+/// Serialized example:
+///
+/// ```toml
+/// # credentials.toml
+///
+/// [registry]
+/// token = "the token for the default registry"
+///
+/// [registries.internal]
+/// token = "the token for the registry named internal in the config file"
+/// ```
+#[derive(Serialize, Debug, Deserialize, Default)]
+pub struct CredentialsFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<Registry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, Registry>,
 }
 
-impl<'cred> CredentialsFile<'cred> {
-    /// Returns the token for the registry.
-    pub const fn with_token(token: &'cred str) -> Self {
-        CredentialsFile {
-            registry: Registry::new(token),
+impl CredentialsFile {
+    /// Sets the token for the default registry, or for a named registry if a name is given.
+    pub fn set_token(&mut self, registry_name: Option<&str>, token: &str) {
+        let registry = Registry::new(token);
+        match registry_name {
+            Some(registry_name) => {
+                self.registries.insert(registry_name.to_owned(), registry);
+            }
+            None => self.registry = Some(registry),
         }
     }
 }
 
 /// Registry table in credentials file (toml).
 #[derive(Serialize, Debug, Deserialize)]
-pub struct Registry<'reg> {
-    pub token: &'reg str,
+pub struct Registry {
+    pub token: String,
 }
 
-impl<'reg> Registry<'reg> {
+impl Registry {
     /// Returns the token for the registry.
-    pub const fn new(token: &'reg str) -> Self {
-        Registry { token }
+    pub fn new(token: &str) -> Self {
+        Registry {
+            token: token.to_owned(),
+        }
     }
 }
 
@@ -48,6 +68,13 @@ pub fn attach_as_sub_command() -> App<'static> {
     Command::new("login")
         .about("Registers an api token to interact with remote registries.")
         .arg(arg!([API_TOKEN]))
+        .arg(
+            Arg::with_name("name")
+                .value_name("registry")
+                .long("name")
+                .help("Saves the token for a registry in the [registries] table of the config file instead of the default registry.")
+                .takes_value(true),
+        )
         .setting(AppSettings::ArgRequiredElseHelp)
 }
 
@@ -67,14 +94,27 @@ pub fn run_command_flow(
         .get_one::<String>("API_TOKEN")
         .ok_or(NoTokenProvidedToSave)?;
 
-    save_credentials_replace_existing(token, config)?;
+    let registry_name = sub_matches.get_one::<String>("name");
+
+    save_credentials_replace_existing(token, registry_name.map(String::as_str), config)?;
     Ok(SuccessfulLogin)
 }
 
-pub fn save_credentials_replace_existing(token: &str, config: &PlowConfig) -> Result<(), CliError> {
-    let credentials_contents =
-        toml::to_string::<CredentialsFile>(&CredentialsFile::with_token(token))
-            .map_err(|_| FailedToReadCredentialsFile)?;
+/// Saves the token for a registry, replacing its existing token and keeping the ones of other registries.
+pub fn save_credentials_replace_existing(
+    token: &str,
+    registry_name: Option<&str>,
+    config: &PlowConfig,
+) -> Result<(), CliError> {
+    // A credentials file which can not be read is written from scratch.
+    let mut credentials = std::fs::read_to_string(&config.credentials_path)
+        .ok()
+        .and_then(|contents| toml::from_str::<CredentialsFile>(&contents).ok())
+        .unwrap_or_default();
+    credentials.set_token(registry_name, token);
+
+    let credentials_contents = toml::to_string::<CredentialsFile>(&credentials)
+        .map_err(|_| FailedToReadCredentialsFile)?;
 
     std::fs::write(&config.credentials_path, credentials_contents)
         .map_err(|_| FailedToWriteCredentialsFile)?;
@@ -28,7 +28,7 @@ use plow_package_management::{
 
 use sha2::{Digest, Sha256};
 
-use super::lint::lint_file;
+use super::lint::{lint_file, WorkspaceLints};
 
 pub struct SuccessfulProtege;
 impl Feedback for SuccessfulProtege {
@@ -79,9 +79,13 @@ pub fn run_command_flow(
             ],
             None,
         );
-        lint_file(field_file_path.as_ref(), vec![lints])?;
+        lint_file(
+            field_file_path.as_ref(),
+            vec![lints],
+            &WorkspaceLints::of_workspace(config)?,
+        )?;
 
-        let registry = crate::sync::sync(config)?;
+        let registry = crate::sync::sync(config, false)?;
 
         let root_field_contents = std::fs::read_to_string(&field_file_path).map_err(|_| {
             CliError::from(FailedToFindFieldAtPath {
@@ -96,17 +100,13 @@ pub fn run_command_flow(
         })?;
 
         match root_field_manifest.dependencies_stated_in_owl_imports() {
-            Ok((
-                mut object_list,
-                stated_dep_names_in_owl_imports,
-                base_iri_beginning,
-                statements,
-            )) => {
+            Ok((mut object_list, stated_iris_in_owl_imports, base_iri_beginning, statements)) => {
                 if let Some(lock_file) = resolve(
                     config,
                     &root_field_contents,
                     &root_field_manifest,
                     true,
+                    None,
                     &registry as &dyn Registry,
                 )? {
                     // Leave an empty line in between.
@@ -125,87 +125,6 @@ pub fn run_command_flow(
                             );
                         });
 
-                    // Only names of resolved dependencies.
-                    let resolved_dep_names = lock_file
-                        .locked_dependencies
-                        .packages
-                        .iter()
-                        .map(|p| p.package_name.clone())
-                        .collect::<Vec<_>>();
-
-                    // Collect deps which does not exist as owl imports.
-                    // Collect deps to be deleted from owl imports.
-                    let (deps_to_add_to_owl_imports, deps_to_remove_from_owl_imports) =
-                        resolved_dep_names.iter().fold(
-                            (vec![], vec![]),
-                            |mut acc, package_name| {
-                                if !stated_dep_names_in_owl_imports.contains(package_name) {
-                                    acc.0.push(package_name.clone());
-                                }
-                                for dep_name in &stated_dep_names_in_owl_imports {
-                                    if !resolved_dep_names.contains(dep_name) {
-                                        acc.1.push(dep_name.clone());
-                                    }
-                                }
-                                acc
-                            },
-                        );
-
-                    // Inject necessary owl:imports
-                    for to_add in deps_to_add_to_owl_imports {
-                        if object_list.list.is_empty() {
-                            object_list.list.push(make_owl_imports_object(
-                                &to_add,
-                                &base_iri_beginning,
-                                true,
-                            ));
-                        } else {
-                            object_list.list.push(make_owl_imports_object(
-                                &to_add,
-                                &base_iri_beginning,
-                                false,
-                            ));
-                        }
-                    }
-
-                    // Delete unnecessary owl:imports
-
-                    object_list.list = object_list
-                        .list
-                        .iter()
-                        .cloned()
-                        .filter(|(_, _, object)| match object {
-                            Object::IRI(harriet::IRI::IRIReference(iri_ref)) => {
-                                let iri_literal = iri_ref.iri.to_string();
-                                let iris = iri_literal.split('/').collect::<Vec<&str>>();
-                                let mut it = iris.iter().rev();
-                                it.next();
-                                // TODO: These unwraps may indeed fail but it is unlikely to happen.
-                                // They will be addressed in the refactoring.
-                                #[allow(clippy::unwrap_used)]
-                                let name = it.next().unwrap().to_owned();
-                                // TODO: These unwraps may indeed fail but it is unlikely to happen.
-                                // They will be addressed in the refactoring.
-                                #[allow(clippy::unwrap_used)]
-                                let namespace = it.next().unwrap().to_owned();
-                                let dep_name = format!("{namespace}/{name}");
-                                !deps_to_remove_from_owl_imports.contains(&dep_name)
-                            }
-                            _ => true,
-                        })
-                        .collect::<Vec<_>>();
-
-                    // Here the owl imports are updated.
-                    // We just need to add them to the existing statements and re-serialize.
-
-                    let remove_owl_imports_line = object_list.list.is_empty();
-                    root_field_manifest.update_owl_imports_and_serialize(
-                        object_list,
-                        statements,
-                        &field_file_path,
-                        remove_owl_imports_line,
-                    );
-
                     // TODO: These unwraps may indeed fail but it is unlikely to happen.
                     // They will be addressed in the refactoring.
                     #[allow(clippy::unwrap_used)]
@@ -217,8 +136,8 @@ pub fn run_command_flow(
                             let metadata = registry
                                 .get_package_version_metadata(package_version)
                                 .unwrap();
+                            let ontology_iri = metadata.major_version_ontology_iri().unwrap();
                             let name = format!("{}.ttl", metadata.cksum.unwrap());
-                            let ontology_iri = metadata.ontology_iri.unwrap();
 
                             // TODO: When types are updated with the upcoming refactoring this will be updated also
                             RetrievedPackageVersion {
@@ -229,6 +148,44 @@ pub fn run_command_flow(
                         })
                         .collect::<Vec<_>>();
 
+                    // Each major version of a dependency is imported by its own ontology IRI.
+                    let resolved_dep_iris = dependency_information
+                        .iter()
+                        .map(|package| package.ontology_iri.clone())
+                        .collect::<Vec<_>>();
+
+                    // Inject necessary owl:imports
+                    for to_add in resolved_dep_iris
+                        .iter()
+                        .filter(|iri| !stated_iris_in_owl_imports.contains(iri))
+                    {
+                        let first = object_list.list.is_empty();
+                        object_list
+                            .list
+                            .push(make_owl_imports_object(to_add, first));
+                    }
+
+                    // Delete owl:imports of fields which are not resolved, e.g. of another major version.
+                    let field_iri_beginning = format!("{base_iri_beginning}/@");
+                    object_list.list.retain(|(_, _, object)| match object {
+                        Object::IRI(harriet::IRI::IRIReference(iri_ref)) => {
+                            !iri_ref.iri.starts_with(&field_iri_beginning)
+                                || resolved_dep_iris.iter().any(|iri| iri == &iri_ref.iri)
+                        }
+                        _ => true,
+                    });
+
+                    // Here the owl imports are updated.
+                    // We just need to add them to the existing statements and re-serialize.
+
+                    let remove_owl_imports_line = object_list.list.is_empty();
+                    root_field_manifest.update_owl_imports_and_serialize(
+                        object_list,
+                        statements,
+                        &field_file_path,
+                        remove_owl_imports_line,
+                    );
+
                     let mut set = RetrievedPackageSet {
                         packages: dependency_information,
                     };
@@ -328,6 +285,7 @@ pub fn run_command_flow(
                     &root_field_contents,
                     &root_field_manifest,
                     true,
+                    None,
                     &registry as &dyn Registry,
                 )? {
                     // Leave an empty line in between.
@@ -346,48 +304,6 @@ pub fn run_command_flow(
                             );
                         });
 
-                    // Only names of resolved dependencies.
-                    let resolved_dep_names = lock_file
-                        .locked_dependencies
-                        .packages
-                        .iter()
-                        .map(|p| p.package_name.clone())
-                        .collect::<Vec<_>>();
-
-                    let base_iri = root_field_manifest.ontology_iri.clone().unwrap();
-                    let base_iri_parts = base_iri.split("/").collect::<Vec<&str>>();
-                    let base_iri_beginning = base_iri_parts[..base_iri_parts.len() - 3].join("/");
-
-                    let mut object_list = ObjectList { list: vec![] };
-
-                    for to_add in &resolved_dep_names {
-                        if object_list.list.is_empty() {
-                            object_list.list.push(make_owl_imports_object(
-                                to_add,
-                                &base_iri_beginning,
-                                true,
-                            ));
-                        } else {
-                            object_list.list.push(make_owl_imports_object(
-                                to_add,
-                                &base_iri_beginning,
-                                false,
-                            ));
-                        }
-                    }
-
-                    let predicate = crate::subcommand::init::field::make_predicate_object(
-                        "owl",
-                        "imports",
-                        object_list,
-                    );
-
-                    root_field_manifest.create_owl_imports_and_serialize(
-                        predicate,
-                        statements,
-                        &field_file_path,
-                    );
-
                     // TODO: These unwraps may indeed fail but it is unlikely to happen.
                     // They will be addressed in the refactoring.
                     #[allow(clippy::unwrap_used)]
@@ -399,8 +315,8 @@ pub fn run_command_flow(
                             let metadata = registry
                                 .get_package_version_metadata(package_version)
                                 .unwrap();
+                            let ontology_iri = metadata.major_version_ontology_iri().unwrap();
                             let name = format!("{}.ttl", metadata.cksum.unwrap());
-                            let ontology_iri = metadata.ontology_iri.unwrap();
 
                             // TODO: When types are updated with the upcoming refactoring this will be updated also
                             RetrievedPackageVersion {
@@ -411,6 +327,27 @@ pub fn run_command_flow(
                         })
                         .collect::<Vec<_>>();
 
+                    // Each major version of a dependency is imported by its own ontology IRI.
+                    let mut object_list = ObjectList { list: vec![] };
+                    for package in &dependency_information {
+                        let first = object_list.list.is_empty();
+                        object_list
+                            .list
+                            .push(make_owl_imports_object(&package.ontology_iri, first));
+                    }
+
+                    let predicate = crate::subcommand::init::field::make_predicate_object(
+                        "owl",
+                        "imports",
+                        object_list,
+                    );
+
+                    root_field_manifest.create_owl_imports_and_serialize(
+                        predicate,
+                        statements,
+                        &field_file_path,
+                    );
+
                     let mut set = RetrievedPackageSet {
                         packages: dependency_information,
                     };
@@ -551,15 +488,14 @@ pub fn mirror_field_to_protege_workspace(
 }
 
 fn make_owl_imports_object<'func>(
-    dep_name: &str,
-    base_iri_root: &str,
+    ontology_iri: &str,
     first: bool,
 ) -> (
     Option<Whitespace<'func>>,
     Option<Whitespace<'func>>,
     Object<'func>,
 ) {
-    let iri_literal = format!("{}/{}/", base_iri_root, dep_name);
+    let iri_literal = ontology_iri.to_owned();
     (
         if first {
             None
@@ -0,0 +1,23 @@
+mod serve;
+
+use crate::{config::PlowConfig, error::CliError, feedback::Feedback};
+
+use clap::{App, AppSettings, ArgMatches, Command};
+
+pub fn attach_as_sub_command() -> App<'static> {
+    Command::new("registry")
+        .about("Runs a registry of your own.")
+        .subcommand(serve::attach_as_sub_command())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+}
+
+#[allow(clippy::as_conversions)]
+pub fn run_command(sub_matches: &ArgMatches, config: &PlowConfig) -> Box<dyn Feedback + 'static> {
+    match sub_matches.subcommand() {
+        Some(("serve", serve_matches)) => match serve::run_command_flow(serve_matches, config) {
+            Ok(feedback) => Box::new(feedback) as Box<dyn Feedback>,
+            Err(feedback) => Box::new(feedback) as Box<dyn Feedback>,
+        },
+        _ => Box::new(CliError::UnknownOption) as Box<dyn Feedback>,
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{config::PlowConfig, error::CliError, feedback::Feedback};
+
+use camino::Utf8PathBuf;
+use clap::{arg, App, Arg, ArgMatches, Command};
+use colored::*;
+use plow_package_management::registry::{on_disk::OnDiskRegistry, server::RegistryServer};
+
+use crate::error::RegistrySubcommandError::*;
+
+/// The address which the registry is served at if none is given.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+pub struct StoppedServing;
+impl Feedback for StoppedServing {
+    fn feedback(&self) {
+        println!("\t{} the registry.", "Stopped serving".yellow().bold());
+    }
+}
+
+pub fn attach_as_sub_command() -> App<'static> {
+    Command::new("serve")
+        .about("Serves the fields in a directory through the registry API which plow speaks, new submissions are stored there.")
+        .arg(arg!([DIRECTORY] "The directory of the registry, the current directory if not given."))
+        .arg(
+            Arg::with_name("address")
+                .value_name("address")
+                .long("address")
+                .help("The address to listen on, 127.0.0.1:8080 if not given.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("token")
+                .value_name("api-token")
+                .long("token")
+                .help("Only answers requests which are authenticated with this API token, the token is not needed to download fields.")
+                .takes_value(true),
+        )
+}
+
+pub fn run_command_flow(
+    sub_matches: &ArgMatches,
+    config: &PlowConfig,
+) -> Result<impl Feedback, CliError> {
+    let directory = sub_matches
+        .get_one::<String>("DIRECTORY")
+        .map_or_else(|| config.working_dir.path.clone(), Utf8PathBuf::from);
+    let address = sub_matches
+        .get_one::<String>("address")
+        .map_or(DEFAULT_ADDRESS, String::as_str);
+
+    std::fs::create_dir_all(&directory).map_err(|err| FailedToOpenRegistry {
+        path: directory.to_string(),
+        reason: err.to_string(),
+    })?;
+    let registry = OnDiskRegistry::new(&directory).map_err(|err| FailedToOpenRegistry {
+        path: directory.to_string(),
+        reason: err.to_string(),
+    })?;
+
+    let mut server =
+        RegistryServer::bind(registry, address).map_err(|err| FailedToServeRegistry {
+            reason: err.to_string(),
+        })?;
+    if let Some(token) = sub_matches.get_one::<String>("token") {
+        server = server.with_token(token);
+    }
+
+    println!(
+        "\t{} the registry in {directory} at {} ..",
+        "Serving".green().bold(),
+        server.url()
+    );
+    server.serve().map_err(|err| FailedToServeRegistry {
+        reason: err.to_string(),
+    })?;
+
+    Ok(StoppedServing)
+}
@@ -13,11 +13,13 @@ use camino::Utf8PathBuf;
 use clap::{arg, App, AppSettings, Arg, ArgMatches, Command};
 use colored::Colorize;
 use plow_linter::lints::field_manifest_lints;
-use plow_package_management::registry::Registry;
-use reqwest::blocking::multipart::Form;
+use plow_package_management::registry::{
+    http::{HttpRegistry, HttpRegistryError},
+    Registry,
+};
 
-use self::response::{RegistryResponse, StatusInfo};
-use super::lint::lint_file;
+use self::response::RegistryResponse;
+use super::lint::{lint_file, WorkspaceLints};
 
 pub fn attach_as_sub_command() -> App<'static> {
     Command::new("submit")
@@ -39,6 +41,13 @@ pub fn attach_as_sub_command() -> App<'static> {
                 .help("Submits the field under the ownership of the specified organization.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("registry")
+                .value_name("name")
+                .long("registry")
+                .help("Submits the field to a registry from the [registries] table of the config file instead of the default registry.")
+                .takes_value(true),
+        )
         .arg(arg!([FIELD_PATH]))
         .setting(AppSettings::ArgRequiredElseHelp)
 }
@@ -63,14 +72,27 @@ fn run_command_flow(
     if field_file_path.exists() {
         field_info(&field_file_path)?;
 
-        lint_file(field_file_path.as_str(), vec![field_manifest_lints()])
-            .map_err(|_| LintingFailed)?;
+        lint_file(
+            field_file_path.as_str(),
+            vec![field_manifest_lints()],
+            &WorkspaceLints::of_workspace(config)?,
+        )
+        .map_err(|_| LintingFailed)?;
 
         general_lint_success();
 
+        let registry_name = sub_matches
+            .get_one::<String>("registry")
+            .map(String::as_str);
+        let registries = config.get_registries()?;
+        let target_registry = registries
+            .iter()
+            .find(|registry| registry.name.as_deref() == registry_name)
+            .ok_or_else(|| UnknownRegistry(registry_name.unwrap_or_default().to_owned()))?;
+
         let path = Utf8PathBuf::from(&field_file_path);
 
-        let registry = crate::sync::sync(config)?;
+        let registry = crate::sync::sync(config, false)?;
 
         let root_field_contents = std::fs::read_to_string(&path).map_err(|_| {
             CliError::from(FailedToFindFieldAtPath {
@@ -88,6 +110,7 @@ fn run_command_flow(
             &root_field_contents,
             &root_field_manifest,
             true,
+            None,
             &registry as &dyn Registry,
         )? {
             // Leave an empty line in between.
@@ -111,84 +134,32 @@ fn run_command_flow(
         let dry_run = sub_matches.is_present("dry-run");
         let plo_owned = sub_matches.get_one::<String>("org-owned").cloned();
 
-        let mut submission = reqwest::blocking::multipart::Form::new()
-            .text("public", if public { "true" } else { "false" });
-
-        if let Some(owner_plo_id) = plo_owned {
-            submission = submission.text("owner_plo_id", owner_plo_id);
-        }
-
-        submission =
-            submission
-                .file("field", &field_file_path)
-                .map_err(|_| FailedToReadFieldAtPath {
-                    field_path: field_file_path.clone().into(),
-                })?;
-
         // Read credentials
-        let token = config.get_saved_api_token()?;
-        let registry_url = config.get_registry_url()?;
-
-        let mut submission_url = format!("{registry_url}/v1/field/submit");
-        if dry_run {
-            submission_url.push_str("?dry-run=true");
+        let token = config.get_registry_token(target_registry.name.as_deref())?;
+
+        let mut submission_registry = HttpRegistry::new(&target_registry.url)
+            .with_token(&token)
+            .submit_privately(!public)
+            .dry_run(dry_run);
+        if let Some(ref owner_plo_id) = plo_owned {
+            submission_registry = submission_registry.submit_as_organization(owner_plo_id);
         }
 
-        return send_submission(&submission_url, &registry_url, submission, &token, dry_run);
+        return match submission_registry.submit_package(&root_field_contents) {
+            Ok(_) => Ok(RegistryResponse::SubmissionSuccess { dry_run }),
+            Err(err) => match err.downcast_ref::<HttpRegistryError>() {
+                Some(HttpRegistryError::Rejected(reasons)) => {
+                    Ok(RegistryResponse::Rejected(reasons.clone()))
+                }
+                _ => Err(RequestFailed {
+                    reason: err.to_string(),
+                }
+                .into()),
+            },
+        };
     }
     Err(FailedToFindFieldAtPath {
         field_path: field_file_path.into(),
     }
     .into())
 }
-
-fn send_submission(
-    submission_url: &str,
-    registry_url: &str,
-    submission: Form,
-    token: &str,
-    dry_run: bool,
-) -> Result<RegistryResponse, CliError> {
-    let client = reqwest::blocking::Client::new();
-    let submission_response = client
-        .post(submission_url)
-        .header(reqwest::header::AUTHORIZATION, &format!("Basic {token}"))
-        .multipart(submission)
-        .send()
-        .map_err(|err| RequestFailed { code: err.status() })?;
-
-    let status = submission_response.status();
-    let response_body_value = submission_response
-        .json::<serde_json::Value>()
-        .map_err(|_| NoValidBodyInResponse {
-            registry_url: registry_url.to_owned(),
-        })?;
-
-    let response_jsend_status_text = response_body_value
-        .get("status")
-        .ok_or(InvalidResponseFromRegistry { code: status })?
-        .as_str()
-        .ok_or(InvalidResponseFromRegistry { code: status })?;
-    let response_body_contents = response_body_value.to_string();
-    let status_info = StatusInfo::try_from(response_jsend_status_text)
-        .map_err(|_| InvalidResponseFromRegistry { code: status })?;
-
-    match status_info {
-        StatusInfo::Error => {
-            let response = serde_json::from_str::<self::response::Error>(&response_body_contents)
-                .map_err(|_| InvalidResponseFromRegistry { code: status })?
-                .into();
-            Ok(response)
-        }
-        StatusInfo::Failure => Ok(serde_json::from_str::<self::response::Failure>(
-            &response_body_contents,
-        )
-        .map_err(|_| InvalidResponseFromRegistry { code: status })?
-        .into()),
-        StatusInfo::Success => {
-            serde_json::from_str::<self::response::Success>(&response_body_contents)
-                .map_err(|_| InvalidResponseFromRegistry { code: status })?;
-            Ok(RegistryResponse::SubmissionSuccess { dry_run })
-        }
-    }
-}
@@ -1,21 +1,27 @@
 use std::collections::HashMap;
 
 use crate::manifest::FieldManifest;
-use crate::{error::CliError, error::FieldAccessError::*, error::WorkspaceInitializationError::*};
+use crate::{
+    error::CliError, error::FieldAccessError::*, error::ResolveError::*,
+    error::WorkspaceInitializationError::*,
+};
 
-use clap::ArgMatches;
-use clap::{App, Command};
+use clap::{arg, App, Arg, ArgMatches, Command};
 
 use plow_package_management::lock::{LockFile, PackageInLockFile};
+use plow_package_management::package::PackageToUpdate;
+use plow_package_management::source::PackageSource;
+use plow_package_management::version::SemanticVersion;
 
 use plow_package_management::registry::Registry;
 
 use crate::config::PlowConfig;
 
 use crate::feedback::{general_update_success, Feedback};
-use crate::resolve::resolve;
+use crate::resolve::{fail_if_lock_file_would_change, locked_package, resolve};
 
 use super::init::workspace::fields::FieldsDirectory;
+use super::lint::WorkspaceLints;
 
 pub struct SuccessfulUpdate;
 impl Feedback for SuccessfulUpdate {
@@ -27,6 +33,17 @@ impl Feedback for SuccessfulUpdate {
 pub fn attach_as_sub_command() -> App<'static> {
     Command::new("update")
         .about("Updates the registry index, caches dependencies and updates the lock file.")
+        .arg(arg!([FIELD_NAME]).help(
+            "Only update this dependency and what it depends on, e.g. @namespace/name. Everything else in the lock file stays as it is.",
+        ))
+        .arg(
+            Arg::with_name("precise")
+                .value_name("version")
+                .long("precise")
+                .help("Update the dependency to exactly this version.")
+                .requires("FIELD_NAME")
+                .takes_value(true),
+        )
 }
 
 #[allow(clippy::as_conversions)]
@@ -37,13 +54,38 @@ pub fn run_command(sub_matches: &ArgMatches, config: &PlowConfig) -> Box<dyn Fee
     }
 }
 
-pub fn run_command_flow(_: &ArgMatches, config: &PlowConfig) -> Result<impl Feedback, CliError> {
+pub fn run_command_flow(
+    sub_matches: &ArgMatches,
+    config: &PlowConfig,
+) -> Result<impl Feedback, CliError> {
+    let package_to_update = sub_matches
+        .get_one::<String>("FIELD_NAME")
+        .map(|package_name| {
+            let precise_version = sub_matches
+                .get_one::<String>("precise")
+                .map(|version| {
+                    SemanticVersion::try_from(version.as_str()).map_err(|err| {
+                        CliError::from(InvalidPreciseVersion {
+                            version: version.clone(),
+                            reason: err.to_string(),
+                        })
+                    })
+                })
+                .transpose()?;
+            Ok::<_, CliError>(PackageToUpdate {
+                package_name: package_name.clone(),
+                precise_version,
+            })
+        })
+        .transpose()?;
+
     let workspace_root = config.working_dir.fail_if_not_under_a_workspace()?;
     let mut fields_dir = FieldsDirectory::fill_from_root(&workspace_root.join("src"))?;
     if fields_dir.children.is_empty() && !fields_dir.exists_in_filesystem() {
         return Err(NoFieldsInDirectory.into());
     }
-    let linting_failures = fields_dir.lint_all_children();
+    let workspace_lints = WorkspaceLints::of_workspace(config)?;
+    let linting_failures = fields_dir.lint_all_children(&workspace_lints);
     // Remove the paths from the list of found fields which has failed lints.
     if let Some((ref failed_paths, _)) = linting_failures {
         fields_dir
@@ -53,7 +95,7 @@ pub fn run_command_flow(_: &ArgMatches, config: &PlowConfig) -> Result<impl Feed
     // Remove if there are duplicate paths. Which is unlikely and probably this is unnecessary.
     fields_dir.dedup();
 
-    let registry = crate::sync::sync(config)?;
+    let registry = crate::sync::sync(config, true)?;
 
     // root -> (resolved_root, deps of root[including transative])
     let mut collection: HashMap<String, (PackageInLockFile, LockFile)> = HashMap::new();
@@ -77,13 +119,23 @@ pub fn run_command_flow(_: &ArgMatches, config: &PlowConfig) -> Result<impl Feed
             .field_dependency_names()
             .unwrap_or_default();
 
-        if let Ok(Some(fresh_lock_file)) = resolve(
+        // A selective update keeps the rest of the lock file, a full update resolves everything again.
+        let resolved = resolve(
             config,
             &root_field_contents,
             &root_field_manifest,
-            false,
+            package_to_update.is_some(),
+            package_to_update.as_ref(),
             &registry as &dyn Registry,
-        ) {
+        );
+        // Failing silently would drop the field's entries from the lock file in a selective update.
+        let fresh_lock_file = if package_to_update.is_some() || config.lock_mode.is_locked() {
+            resolved?
+        } else {
+            resolved.ok().flatten()
+        };
+
+        if let Some(fresh_lock_file) = fresh_lock_file {
             // Unwrap is fine here we've linted the field before.
             #[allow(clippy::unwrap_used)]
             let root_as_index = root_field_manifest.make_index_from_manifest().unwrap();
@@ -98,7 +150,14 @@ pub fn run_command_flow(_: &ArgMatches, config: &PlowConfig) -> Result<impl Feed
                         name: root_as_index.name,
                         version: root_as_index.version,
                         ontology_iri: root_as_index.ontology_iri,
-                        source: None,
+                        // Fields of the workspace are locked by their path relative to it.
+                        source: Some(PackageSource::Path(
+                            child
+                                .as_path()
+                                .strip_prefix(&workspace_root)
+                                .unwrap_or_else(|_| child.as_path())
+                                .to_string(),
+                        )),
                         cksum: Some(root_as_index.cksum),
                         dependencies: root_dep_names,
                         root: true,
@@ -109,41 +168,19 @@ pub fn run_command_flow(_: &ArgMatches, config: &PlowConfig) -> Result<impl Feed
         }
     }
 
-    let lock_file_contents = collection
-        .into_iter()
-        .flat_map(|(_, (root, locked_deps))| {
-            let mut v = vec![];
-            v.push(root);
-            let deps = locked_deps
-                .locked_dependencies
-                .packages
-                .iter()
-                .map(|package_version| {
-                    // Safe here, we passed dep resolution.
-                    #[allow(clippy::unwrap_used)]
-                    let metadata = registry
-                        .get_package_version_metadata(package_version)
-                        .unwrap();
-                    PackageInLockFile {
-                        name: package_version.package_name.clone(),
-                        version: package_version.version.clone(),
-                        ontology_iri: metadata.ontology_iri.clone(),
-                        source: None,
-                        cksum: metadata.cksum.clone(),
-                        dependencies: metadata
-                            .dependencies
-                            .iter()
-                            .cloned()
-                            .map(|dep| dep.full_name)
-                            .collect(),
-                        root: false,
-                    }
-                })
-                .collect::<Vec<_>>();
-            v.extend(deps);
-            v
-        })
-        .collect::<Vec<_>>();
+    let mut lock_file_contents = vec![];
+    for (_, (root, locked_deps)) in collection {
+        lock_file_contents.push(root);
+        for package_version in &locked_deps.locked_dependencies.packages {
+            lock_file_contents.push(locked_package(
+                &registry,
+                package_version,
+                &locked_deps.locked_dependencies,
+            )?);
+        }
+    }
+
+    fail_if_lock_file_would_change(config, &lock_file_contents)?;
 
     if !lock_file_contents.is_empty() {
         LockFile::write(Some(config.working_dir.path.clone()), &lock_file_contents)
@@ -0,0 +1,106 @@
+use crate::{config::PlowConfig, error::CliError, feedback::Feedback};
+
+use clap::{arg, App, AppSettings, Arg, ArgMatches, Command};
+use colored::*;
+use plow_package_management::{
+    package::PackageVersion,
+    registry::{http::HttpRegistry, Registry},
+    version::SemanticVersion,
+};
+
+use crate::error::YankError::*;
+
+pub struct SuccessfulYank {
+    field_name: String,
+    version: String,
+    yanked: bool,
+}
+
+impl Feedback for SuccessfulYank {
+    fn feedback(&self) {
+        let action = if self.yanked { "Yanked" } else { "Unyanked" };
+        println!(
+            "\t{} {} {}",
+            action.green().bold(),
+            self.field_name.bold(),
+            self.version
+        );
+    }
+}
+
+pub fn attach_as_sub_command() -> App<'static> {
+    Command::new("yank")
+        .about("Retracts a version of a field, fields which depend on it keep it only if it is in their lock file.")
+        .arg(arg!([FIELD_NAME]))
+        .arg(arg!([VERSION]))
+        .arg(
+            Arg::with_name("undo")
+                .long("undo")
+                .help("Takes a yank back, the version of the field can be resolved again."),
+        )
+        .arg(
+            Arg::with_name("name")
+                .value_name("registry")
+                .long("name")
+                .help("Yanks the field in a registry from the [registries] table of the config file instead of the default registry.")
+                .takes_value(true),
+        )
+        .setting(AppSettings::ArgRequiredElseHelp)
+}
+
+#[allow(clippy::as_conversions)]
+pub fn run_command(sub_matches: &ArgMatches, config: &PlowConfig) -> Box<dyn Feedback + 'static> {
+    match run_command_flow(sub_matches, config) {
+        Ok(feedback) => Box::new(feedback) as Box<dyn Feedback>,
+        Err(feedback) => Box::new(feedback) as Box<dyn Feedback>,
+    }
+}
+
+pub fn run_command_flow(
+    sub_matches: &clap::ArgMatches,
+    config: &PlowConfig,
+) -> Result<impl Feedback, CliError> {
+    let field_name = sub_matches
+        .get_one::<String>("FIELD_NAME")
+        .cloned()
+        .unwrap_or_default();
+    if !field_name.starts_with('@') || field_name.split_once('/').is_none() {
+        return Err(CliError::from(InvalidFieldName(field_name)));
+    }
+    let version = sub_matches
+        .get_one::<String>("VERSION")
+        .cloned()
+        .unwrap_or_default();
+    // Only an exact version can be yanked, not a requirement like `1.2`.
+    if SemanticVersion::try_from(&version).map_or(true, |parsed| parsed.to_string() != version) {
+        return Err(CliError::from(InvalidVersion(version)));
+    }
+    let yanked = !sub_matches.is_present("undo");
+    let registry_name = sub_matches.get_one::<String>("name").map(String::as_str);
+
+    let registries = config.get_registries()?;
+    let registry = registries
+        .iter()
+        .find(|registry| registry.name.as_deref() == registry_name)
+        .ok_or_else(|| UnknownRegistry(registry_name.unwrap_or_default().to_owned()))?;
+    let token = config.get_registry_token(registry.name.as_deref())?;
+
+    HttpRegistry::new(&registry.url)
+        .with_token(&token)
+        .yank(
+            &PackageVersion {
+                package_name: field_name.clone(),
+                version: version.clone(),
+            },
+            yanked,
+        )
+        .map_err(|err| RequestFailed {
+            reason: err.to_string(),
+        })?;
+
+    Ok(SuccessfulYank {
+        field_name,
+        version,
+        yanked,
+    })
+}
@@ -3,283 +3,443 @@
 
 use core::{convert::TryFrom, str::FromStr};
 
+use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
 use plow_package_management::{
+    lock::LockFile,
     package::{PackageVersion, PackageVersionWithRegistryMetadata},
     registry::{
+        http::{field_hash, HttpRegistry, HttpRegistryError, PrivateIndex, PrivateIndexes},
         in_memory::InMemoryRegistry,
         index::{IndexContents, IndexedPackageVersion},
+        merged::MergedRegistry,
     },
     resolve::Dependency,
+    source::PackageSource,
     version::SemanticVersion,
 };
-use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::{
-    config::PlowConfig, error::CliError, error::IndexSyncError::*, feedback::command_failed,
+    config::{PlowConfig, RegistryLocation},
+    error::CliError,
+    error::IndexSyncError::*,
     git::PublicIndexRepository,
 };
 
-#[derive(Serialize, Deserialize, Default)]
-pub struct DifferenceQuery {
-    pub existing_local_field_hashes: Vec<String>,
-}
+/// Name of the file in the index cache which keeps the last synced private index of the default registry.
+///
+/// The ones of named registries are kept in `private-index-<name>.json`.
+const PRIVATE_INDEX_FILE_NAME: &str = "private-index.json";
+
+/// How long a synced private index is used before it is synced again, in seconds.
+const PRIVATE_INDEX_SYNC_INTERVAL: u64 = 5 * 60;
 
+/// The last synced private index, kept to sync incrementally and to work offline.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DependencyRequirement {
-    pub name: String,
-    pub req: String,
+pub struct PersistedPrivateIndex {
+    /// The registry which the private index was synced from.
+    registry_url: String,
+    /// Seconds since the unix epoch of the last successful sync.
+    #[serde(default)]
+    synced_at: u64,
+    indexes: Vec<PrivateIndex>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PrivateIndex {
-    pub name: String,
-    pub version: String,
-    pub cksum: String,
-    pub ontology_iri: Option<String>,
-    pub deps: Vec<DependencyRequirement>,
+impl PersistedPrivateIndex {
+    /// Reads the private index last synced from the given registry, or an empty one if there is none.
+    fn read(config: &PlowConfig, registry: &RegistryLocation) -> Result<Self, CliError> {
+        let path = private_index_path(config, registry);
+        let registry_url = &registry.url;
+        let empty = Self {
+            registry_url: registry_url.clone(),
+            synced_at: 0,
+            indexes: vec![],
+        };
+        if !path.exists() {
+            return Ok(empty);
+        }
+        let contents =
+            std::fs::read(&path).map_err(|err| FailedToReadIndexDirectory(err.to_string()))?;
+        let persisted: Self =
+            serde_json::from_slice(&contents).map_err(|err| FailedToParseIndex(err.to_string()))?;
+        // Another registry has other private fields.
+        if &persisted.registry_url != registry_url {
+            return Ok(empty);
+        }
+        Ok(persisted)
+    }
+
+    fn write(&self, config: &PlowConfig, registry: &RegistryLocation) -> Result<(), CliError> {
+        let contents = serde_json::to_vec(self)
+            .map_err(|err| FailedToGetPrivateIndexUpdates(err.to_string()))?;
+        std::fs::write(private_index_path(config, registry), contents)
+            .map_err(|err| FailedToGetPrivateIndexUpdates(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Whether the last sync is recent enough to not sync again.
+    const fn is_recent(&self, now: u64) -> bool {
+        now.saturating_sub(self.synced_at) < PRIVATE_INDEX_SYNC_INTERVAL
+    }
+
+    fn field_hashes(&self) -> Vec<String> {
+        self.indexes
+            .iter()
+            .map(|index| field_hash(&index.name, &index.version))
+            .collect()
+    }
+
+    /// Applies the changes since the last sync, re-published entries replace the existing ones.
+    fn apply(&mut self, changes: PrivateIndexes, synced_at: u64) {
+        let PrivateIndexes {
+            indexes,
+            removed_field_hashes,
+        } = changes;
+        self.indexes.retain(|existing| {
+            !removed_field_hashes.contains(&field_hash(&existing.name, &existing.version))
+                && !indexes.iter().any(|index| {
+                    index.name == existing.name && index.version == existing.version
+                })
+        });
+        self.indexes.extend(indexes);
+        self.synced_at = synced_at;
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PrivateIndexes {
-    indexes: Vec<PrivateIndex>,
+fn private_index_path(config: &PlowConfig, registry: &RegistryLocation) -> Utf8PathBuf {
+    registry.name.as_ref().map_or_else(
+        || config.index_cache_dir.join(PRIVATE_INDEX_FILE_NAME),
+        |name| {
+            config
+                .index_cache_dir
+                .join(format!("private-index-{name}.json"))
+        },
+    )
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PrivateIndexResponse {
-    status: String,
-    data: PrivateIndexes,
+
+/// How an index of a registry is called in messages, e.g. `Private index of internal` for a named registry.
+fn index_label(index: &str, registry: &RegistryLocation) -> String {
+    registry
+        .name
+        .as_ref()
+        .map_or_else(|| index.to_owned(), |name| format!("{index} of {name}"))
 }
 
-#[allow(clippy::too_many_lines)]
-pub fn sync(config: &PlowConfig) -> Result<InMemoryRegistry, CliError> {
-    let token = config.get_saved_api_token()?;
-    let registry_url = config.get_registry_url()?;
-    let private_index_sync_url = format!("{registry_url}/v1/index/private/sync");
-    let client = reqwest::blocking::Client::new();
+fn seconds_since_unix_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Builds a registry from the private and the public indexes of the default and the named registries,
+/// updating them first unless working without the network.
+///
+/// The private indexes are only synced again if they were not synced recently, or if forced.
+pub fn sync(
+    config: &PlowConfig,
+    force_private_index_update: bool,
+) -> Result<MergedRegistry, CliError> {
+    let frozen = config.lock_mode.is_frozen();
+    let offline = config.is_offline()?;
+    let without_network = frozen || offline;
+
+    if frozen {
+        println!(
+            "\t{} the index is not updated, using what is locked and the local index ..",
+            "Frozen,".yellow().bold()
+        );
+    } else if offline {
+        println!(
+            "\t{} the index is not updated, using the last updated local index ..",
+            "Offline,".yellow().bold()
+        );
+    }
+
+    let mut default_registry = InMemoryRegistry::default();
+    let mut named_registries = vec![];
+    for location in config.get_registries()? {
+        let registry = sync_registry(
+            config,
+            &location,
+            without_network,
+            force_private_index_update,
+        )?;
+        match location.name {
+            Some(name) => named_registries.push((name, registry)),
+            None => default_registry = registry,
+        }
+    }
 
-    // This is a naive and temporary solution we always get the whole private index for now.
-    // This will be replaced very soon.
+    if without_network {
+        add_locked_packages(config, &mut default_registry, &named_registries)?;
+    }
 
-    let query = DifferenceQuery::default();
+    Ok(named_registries.into_iter().fold(
+        MergedRegistry::new(Box::new(default_registry)),
+        |merged, (name, registry)| merged.with_registry(&name, Box::new(registry)),
+    ))
+}
+
+/// Builds a registry from the private and the public index of a single registry.
+fn sync_registry(
+    config: &PlowConfig,
+    location: &RegistryLocation,
+    without_network: bool,
+    force_private_index_update: bool,
+) -> Result<InMemoryRegistry, CliError> {
     let mut registry = InMemoryRegistry::default();
 
-    println!(
-        "\t{} to update the private index ..",
-        "Attempting".green().bold()
-    );
+    if !without_network {
+        update_private_index(config, location, force_private_index_update)?;
+    }
+
+    add_persisted_private_index(config, location, &mut registry)?;
+
+    let public_index_url = match location.public_index_url {
+        Some(ref public_index_url) => public_index_url,
+        None => return Ok(registry),
+    };
+    let public_index_source = PackageSource::PublicIndex(public_index_url.clone());
+    let public_index_git_repo_path = &config
+        .index_dir
+        .join(public_index_directory_name(public_index_url));
+
+    if !without_network {
+        println!(
+            "\t{} to update the {} ..",
+            "Attempting".green().bold(),
+            index_label("public index", location)
+        );
+        // Public indexes are readable without authentication, unless they are reached through ssh.
+        let ssh_key_path = config
+            .user_home
+            .as_ref()
+            .map(|user_home| user_home.join(".ssh").join("id_rsa"))
+            .filter(|ssh_key_path| is_ssh_url(public_index_url) && ssh_key_path.exists());
+        update_public_index(
+            public_index_url,
+            &location.public_index_branch,
+            public_index_git_repo_path,
+            ssh_key_path.as_deref(),
+        )?;
+    }
 
-    let private_index_sync_response = client
-        .post(private_index_sync_url)
-        .header(reqwest::header::AUTHORIZATION, &format!("Basic {token}"))
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .json(&query)
-        .send();
-
-    if let Ok(response) = private_index_sync_response {
-        let status = response.status();
-
-        match status {
-            StatusCode::OK => {
-                // Get the response
-                if let Ok(response_body) = response.bytes() {
-                    let priv_indexes: Result<PrivateIndexResponse, _> =
-                        serde_json::from_slice(&response_body);
-
-                    if let Ok(priv_indexes) = priv_indexes {
-                        let priv_indexes = priv_indexes.data.indexes;
-                        for index in priv_indexes {
-                            let deps = index
-                                .deps
-                                .into_iter()
-                                .map(|dep| {
-                                    Dependency::<SemanticVersion>::try_from(
-                                        format!("{} {}", dep.name, dep.req).as_str(),
-                                    )
-                                    .unwrap()
-                                })
-                                .collect::<Vec<_>>();
-
-                            let metadata = PackageVersionWithRegistryMetadata {
-                                package_name: index.name.clone(),
-                                version: SemanticVersion::from_str(&index.version.clone()).unwrap(),
-                                ontology_iri: index.ontology_iri,
-                                dependencies: deps,
-                                cksum: Some(index.cksum),
-                                private: true,
-                            };
-                            if registry
-                                .packages_metadata
-                                .get(&PackageVersion {
-                                    package_name: index.name.clone(),
-                                    version: index.version.clone(),
-                                })
-                                .is_none()
-                            {
-                                registry.packages_metadata.insert(
-                                    PackageVersion {
-                                        package_name: index.name,
-                                        version: index.version,
-                                    },
-                                    metadata,
-                                );
-                            }
-                        }
-
-                        println!(
-                            "\t{} is updated successfully.",
-                            "Private index".green().bold(),
-                        );
-                    } else {
-                        println!(
-                            "\t{} skipping update ..",
-                            "Remote private index fetch failed.".red().bold(),
-                        );
-                    }
-                    // for line in response_body.split(|byte| byte == &b'\n') {
-                    //     if line.is_empty() {
-                    //         continue;
-                    //     }
-
-                    //     let (_name, versions) = parse(line)?;
-
-                    //     for version in versions {
-                    //         let ver = PackageVersion {
-                    //             package_name: version.package_name.clone(),
-                    //             version: version.version.clone().to_string(),
-                    //         };
-                    //         registry.packages_metadata.insert(ver, version);
-                    //     }
-                    // }
-                } else {
-                    println!(
-                        "\t{} skipping update ..",
-                        "Remote private index is not reachable".red().bold(),
+    // Without network access there may be no clone of the public index yet.
+    if !without_network || public_index_git_repo_path.exists() {
+        let paths = crate::utils::list_files(&public_index_git_repo_path, "json")
+            .map_err(|err| FailedToReadIndexDirectory(err.to_string()))?;
+
+        for path in paths {
+            let contents =
+                std::fs::read(path).map_err(|err| FailedToReadIndexDirectory(err.to_string()))?;
+            let contents: IndexContents = serde_json::from_slice(&contents)
+                .map_err(|err| FailedToParseIndex(err.to_string()))?;
+            for version in contents.versions {
+                let ver = PackageVersion {
+                    package_name: version.name.clone(),
+                    version: version.version.clone(),
+                };
+
+                let mut deps = vec![];
+                for dep in version.deps {
+                    // TODO: Corrupt index
+                    deps.push(
+                        Dependency::<SemanticVersion>::try_from(dep.clone())
+                            .map_err(|err| FailedToParseIndex(err.to_string()))?,
                     );
                 }
-            }
-            StatusCode::UNAUTHORIZED => {
-                println!(
-                    "\t{} try authenticating with plow login <api-token>, skipping update ..",
-                    "Unauthorized get updates from the private index"
-                        .red()
-                        .bold(),
-                );
-            }
-            _ => {
-                // TODO: Give real feedback and continue..
-                println!(
-                    "\t{} skipping update ..",
-                    "Remote private index is not reachable".red().bold(),
-                );
+
+                let version = PackageVersionWithRegistryMetadata {
+                    package_name: version.name.clone(),
+                    version: SemanticVersion::from_str(&version.version)
+                        .map_err(|err| FailedToParseIndex(err.to_string()))?,
+                    ontology_iri: version.ontology_iri,
+                    cksum: Some(version.cksum),
+                    dependencies: deps,
+                    private: false,
+                    source: Some(public_index_source.clone()),
+                    keywords: version.keywords,
+                    categories: version.categories,
+                    yanked: version.yanked,
+                };
+                registry.packages_metadata.insert(ver, version);
             }
         }
-    } else {
+    }
+
+    if !without_network {
         println!(
-            "\t{} skipping update ..",
-            "Remote private index is not reachable".red().bold(),
+            "\t{} is updated successfully.",
+            index_label("Public index", location).green().bold(),
         );
     }
 
-    println!(
-        "\t{} to update the public index ..",
-        "Attempting".green().bold(),
-    );
-
-    let public_index_name = match config.get_registry_url()? {
-        url if url.contains("localhost") => "test-public-registry-index",
-        url if url.contains("staging-api") => "staging-public-registry-index",
-        _ => "plow-registry-index",
-    };
-
-    // TODO: Move these somewhere else?
-    let clone_from = format!("git@github.com:field33/{public_index_name}.git");
-    let public_index_git_repo_path = &config.index_dir.join(public_index_name);
+    Ok(registry)
+}
 
-    let pull_command = if !config.index_dir.join(public_index_name).exists() {
-        format!("cd ~/.plow/registry/index && git clone https://github.com/field33/{public_index_name}.git && git pull")
-    } else {
-        format!("cd ~/.plow/registry/index/{public_index_name} && git fetch --all && git reset --hard origin/main && git pull")
+/// Makes the packages in the lock file of the workspace available, for the ones the local index does not have.
+///
+/// Private packages are only known through the lock file when the private index can not be reached.
+fn add_locked_packages(
+    config: &PlowConfig,
+    registry: &mut InMemoryRegistry,
+    named_registries: &[(String, InMemoryRegistry)],
+) -> Result<(), CliError> {
+    let lock_file_path = match LockFile::previous_lock_file_exists(config.get_workspace_root().ok())
+    {
+        Some(lock_file_path) => lock_file_path,
+        None => return Ok(()),
     };
+    let lock_file = LockFile::deserialize_lock_file(&lock_file_path)
+        .map_err(|err| FailedToReadLockFile(err.to_string()))?;
+
+    for package in lock_file.packages().iter().filter(|package| !package.root) {
+        let package_version: PackageVersion = package.into();
+        if registry.packages_metadata.contains_key(&package_version)
+            || named_registries.iter().any(|(_, named_registry)| {
+                named_registry
+                    .packages_metadata
+                    .contains_key(&package_version)
+            })
+        {
+            continue;
+        }
+        let metadata = package
+            .to_registry_metadata(lock_file.packages())
+            .map_err(|err| FailedToReadLockFile(err.to_string()))?;
+        registry.packages_metadata.insert(package_version, metadata);
+    }
+    Ok(())
+}
 
-    if let Some(ref user_home) = config.user_home {
-        if which::which("git").is_ok() {
-            // TODO: Proper error handling
-            std::process::Command::new("/bin/bash")
-                .arg("-c")
-                .arg(pull_command)
-                .output()
-                .expect("failed to execute process");
-        } else {
-            let ssh_key_path = user_home.join(".ssh").join("id_rsa");
-            let repository = PublicIndexRepository::clone_or_open(
-                clone_from,
-                &public_index_git_repo_path,
-                "main".to_owned(),
-                Some(&&ssh_key_path),
-                None,
-            )
-            .map_err(|err| FailedToGetRepository(err.to_string()))?;
+/// Adds the private index which was last synced from the registry, if there is one.
+fn add_persisted_private_index(
+    config: &PlowConfig,
+    location: &RegistryLocation,
+    registry: &mut InMemoryRegistry,
+) -> Result<(), CliError> {
+    let persisted = PersistedPrivateIndex::read(config, location)?;
+
+    for index in persisted.indexes {
+        let metadata = index
+            .to_registry_metadata(&location.url)
+            .map_err(|err| FailedToParseIndex(err.to_string()))?;
+        let package_version = PackageVersion {
+            package_name: index.name,
+            version: index.version,
+        };
+        registry
+            .packages_metadata
+            .entry(package_version)
+            .or_insert(metadata);
+    }
+    Ok(())
+}
 
-            repository
-                .pull_from_origin_fast_forward()
-                .map_err(|err| FailedToGetRepository(err.to_string()))?;
-        }
-    } else {
-        if which::which("git").is_ok() {
-            // TODO: Proper error handling
-            std::process::Command::new("/bin/bash")
-                .arg("-c")
-                .arg(pull_command)
-                .output()
-                .expect("failed to execute process");
-        } else {
-            command_failed("Please install git to update the public index.");
-        }
+/// Syncs the private index in the index cache with the registry.
+///
+/// Only what changed since the last sync is fetched, and not at all if it was synced recently unless forced.
+fn update_private_index(
+    config: &PlowConfig,
+    location: &RegistryLocation,
+    force: bool,
+) -> Result<(), CliError> {
+    let mut persisted = PersistedPrivateIndex::read(config, location)?;
+    let now = seconds_since_unix_epoch();
+    if !force && persisted.is_recent(now) {
+        println!(
+            "\t{} is up to date.",
+            index_label("Private index", location).green().bold()
+        );
+        return Ok(());
     }
 
-    let paths = crate::utils::list_files(&public_index_git_repo_path, "json")
-        .map_err(|err| FailedToReadIndexDirectory(err.to_string()))?;
+    let token = config.get_registry_token(location.name.as_deref())?;
+    let registry = HttpRegistry::new(&location.url).with_token(&token);
 
-    for path in paths {
-        let contents =
-            std::fs::read(path).map_err(|err| FailedToReadIndexDirectory(err.to_string()))?;
-        let contents: IndexContents =
-            serde_json::from_slice(&contents).map_err(|err| FailedToParseIndex(err.to_string()))?;
-        for version in contents.versions {
-            let ver = PackageVersion {
-                package_name: version.name.clone(),
-                version: version.version.clone(),
-            };
+    println!(
+        "\t{} to update the {} ..",
+        "Attempting".green().bold(),
+        index_label("private index", location)
+    );
 
-            let mut deps = vec![];
-            for dep in version.deps {
-                // TODO: Corrupt index
-                deps.push(
-                    Dependency::<SemanticVersion>::try_from(dep.clone())
-                        .map_err(|err| FailedToParseIndex(err.to_string()))?,
-                );
-            }
+    match registry.private_index_changes(persisted.field_hashes()) {
+        Ok(changes) => {
+            persisted.apply(changes, now);
+            persisted.write(config, location)?;
 
-            let version = PackageVersionWithRegistryMetadata {
-                package_name: version.name.clone(),
-                version: SemanticVersion::from_str(&version.version)
-                    .map_err(|err| FailedToParseIndex(err.to_string()))?,
-                ontology_iri: version.ontology_iri,
-                cksum: Some(version.cksum),
-                dependencies: deps,
-                private: false,
-            };
-            registry.packages_metadata.insert(ver, version);
+            println!(
+                "\t{} is updated successfully.",
+                index_label("Private index", location).green().bold(),
+            );
+        }
+        Err(HttpRegistryError::InvalidResponse { .. }) => {
+            println!(
+                "\t{} skipping update ..",
+                "Remote private index fetch failed.".red().bold(),
+            );
+        }
+        Err(HttpRegistryError::Unauthorized { .. }) => {
+            let login = location.name.as_ref().map_or_else(
+                || "plow login <api-token>".to_owned(),
+                |name| format!("plow login --name {name} <api-token>"),
+            );
+            println!(
+                "\t{} try authenticating with {login}, skipping update ..",
+                "Unauthorized get updates from the private index"
+                    .red()
+                    .bold(),
+            );
+        }
+        Err(_) => {
+            // TODO: Give real feedback and continue..
+            println!(
+                "\t{} skipping update ..",
+                "Remote private index is not reachable".red().bold(),
+            );
         }
     }
 
-    println!(
-        "\t{} is updated successfully.",
-        "Public index".green().bold(),
-    );
+    Ok(())
+}
 
-    Ok(registry)
+/// Clones the public index or pulls the latest changes of its branch if it is cloned already.
+pub fn update_public_index(
+    public_index_url: &str,
+    public_index_branch: &str,
+    public_index_git_repo_path: &Utf8Path,
+    ssh_key_path: Option<&Utf8Path>,
+) -> Result<(), CliError> {
+    let repository = PublicIndexRepository::clone_or_open(
+        public_index_url,
+        public_index_git_repo_path,
+        public_index_branch,
+        ssh_key_path,
+        None,
+    )
+    .map_err(|err| FailedToGetRepository(err.to_string()))?;
+
+    repository
+        .pull_from_origin_fast_forward()
+        .map_err(|err| FailedToUpdateRepository(err.to_string()))?;
+
+    Ok(())
+}
+
+/// Name of the directory in the index directory which the public index is cloned to, e.g. `plow-registry-index`.
+fn public_index_directory_name(public_index_url: &str) -> String {
+    let name = public_index_url
+        .trim_end_matches('/')
+        .rsplit(|c| c == '/' || c == ':')
+        .next()
+        .unwrap_or(public_index_url);
+    name.strip_suffix(".git").unwrap_or(name).to_owned()
+}
+
+fn is_ssh_url(url: &str) -> bool {
+    url.starts_with("ssh://") || (url.contains('@') && !url.contains("://"))
 }
 
 #[allow(clippy::indexing_slicing)]
@@ -385,6 +545,9 @@ fn parse(data: &[u8]) -> Result<(String, Vec<PackageVersionWithRegistryMetadata>
                     ontology_iri,
                     cksum,
                     deps,
+                    keywords,
+                    categories,
+                    yanked,
                     ..
                 } = serde_json::from_slice::<IndexedPackageVersion>(content)
                     .map_err(|err| FailedToParseIndex(err.to_string()))?;
@@ -403,6 +566,10 @@ fn parse(data: &[u8]) -> Result<(String, Vec<PackageVersionWithRegistryMetadata>
                     dependencies,
                     cksum: Some(cksum),
                     private: version_type[0] == PRIVATE_VERSION,
+                    source: None,
+                    keywords,
+                    categories,
+                    yanked,
                 });
             }
             _ => {
@@ -413,3 +580,138 @@ fn parse(data: &[u8]) -> Result<(String, Vec<PackageVersionWithRegistryMetadata>
     }
     Ok((index_name.to_owned(), versions))
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction)]
+
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn private_index(name: &str, version: &str, cksum: &str) -> PrivateIndex {
+        PrivateIndex {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            cksum: cksum.to_owned(),
+            ontology_iri: None,
+            deps: vec![],
+            yanked: false,
+        }
+    }
+
+    /// Commits a file on top of the main branch of a bare repository.
+    fn commit_to_bare_repository(repository: &git2::Repository, path: &str, contents: &str) {
+        let parent = repository
+            .find_reference("refs/heads/main")
+            .ok()
+            .and_then(|reference| reference.peel_to_commit().ok());
+        let blob = repository.blob(contents.as_bytes()).unwrap();
+        let mut tree_builder = repository
+            .treebuilder(parent.as_ref().map(|commit| commit.tree().unwrap()).as_ref())
+            .unwrap();
+        tree_builder.insert(path, blob, 0o100_644).unwrap();
+        let tree = repository.find_tree(tree_builder.write().unwrap()).unwrap();
+        let signature = git2::Signature::now("plow", "plow@field33.com").unwrap();
+        repository
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                &format!("Add {path}"),
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn public_index_is_cloned_and_pulled_from_a_local_bare_repository() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let root = Utf8Path::from_path(tmp_dir.path()).unwrap();
+        let remote_path = root.join("public-index.git");
+        let remote = git2::Repository::init_bare(&remote_path).unwrap();
+        remote.set_head("refs/heads/main").unwrap();
+        commit_to_bare_repository(&remote, "Berlin.json", r#"{"versions":[]}"#);
+
+        let remote_url = remote_path.to_string();
+        let local_path = root
+            .join("index")
+            .join(public_index_directory_name(&remote_url));
+        update_public_index(&remote_url, "main", &local_path, None).unwrap();
+        assert!(local_path.ends_with("public-index"));
+        assert!(local_path.join("Berlin.json").exists());
+
+        commit_to_bare_repository(&remote, "Paris.json", r#"{"versions":[]}"#);
+        update_public_index(&remote_url, "main", &local_path, None).unwrap();
+        assert!(local_path.join("Paris.json").exists());
+
+        assert!(matches!(
+            update_public_index(
+                root.join("missing.git").as_str(),
+                "main",
+                &root.join("missing"),
+                None
+            ),
+            Err(CliError::IndexSync(FailedToGetRepository(_)))
+        ));
+    }
+
+    #[test]
+    fn public_index_directory_names_are_taken_from_the_url() {
+        for url in [
+            "https://github.com/field33/plow-registry-index.git",
+            "git@github.com:field33/plow-registry-index.git",
+            "/srv/git/plow-registry-index/",
+        ] {
+            assert_eq!(public_index_directory_name(url), "plow-registry-index");
+        }
+        assert!(is_ssh_url("git@github.com:field33/plow-registry-index.git"));
+        assert!(!is_ssh_url("https://github.com/field33/plow-registry-index.git"));
+    }
+
+    #[test]
+    fn private_index_changes_are_applied_incrementally() {
+        let mut persisted = PersistedPrivateIndex {
+            registry_url: "https://api.plow.pm".to_owned(),
+            synced_at: 0,
+            indexes: vec![
+                private_index("@cities/Berlin", "1.0.0", "ab12"),
+                private_index("@cities/Paris", "1.0.0", "cd34"),
+                private_index("@cities/Rome", "1.0.0", "ef56"),
+            ],
+        };
+        assert!(!persisted.is_recent(PRIVATE_INDEX_SYNC_INTERVAL));
+
+        persisted.apply(
+            PrivateIndexes {
+                indexes: vec![
+                    private_index("@cities/Paris", "1.0.0", "0000"),
+                    private_index("@cities/Berlin", "1.1.0", "ab34"),
+                ],
+                removed_field_hashes: vec![field_hash("@cities/Rome", "1.0.0")],
+            },
+            100,
+        );
+
+        let mut entries = persisted
+            .indexes
+            .iter()
+            .map(|index| format!("{} {} {}", index.name, index.version, index.cksum))
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                "@cities/Berlin 1.0.0 ab12",
+                "@cities/Berlin 1.1.0 ab34",
+                "@cities/Paris 1.0.0 0000",
+            ]
+        );
+        assert!(persisted.is_recent(100 + PRIVATE_INDEX_SYNC_INTERVAL - 1));
+        assert!(!persisted.is_recent(100 + PRIVATE_INDEX_SYNC_INTERVAL));
+        assert_eq!(
+            field_hash("@cities/Rome", "1.0.0"),
+            format!("{:x}", Sha256::digest("@cities/Rome 1.0.0"))
+        );
+    }
+}
@@ -35,4 +35,736 @@ fn plow_init_field_empty_dir() {
 
     let generated_ontology_contents = std::fs::read_to_string(outfile_path).unwrap();
     insta::assert_snapshot!(generated_ontology_contents);
-}
\ No newline at end of file
+}
+/// Test that `plow --offline lint` resolves from the local index and only uses cached fields.
+#[test]
+fn plow_lint_offline_uses_only_cached_fields() {
+    use sha2::{Digest, Sha256};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+    let workspace = tmp_dir.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/offline")
+        .env("HOME", &home)
+        .current_dir(&workspace)
+        .unwrap()
+        .assert()
+        .code(0);
+
+    // Fill in what the field linter requires and add a dependency.
+    let field_path = workspace.join("offline.ttl");
+    let field = std::fs::read_to_string(&field_path)
+        .unwrap()
+        .replace("#registry:", "registry:")
+        .replace("#rdfs:", "rdfs:")
+        .replace(
+            "# registry:dependency \"@namespace/name <version requirement>\", \"@namespace/name <version requirement>\" and so on..\" ;",
+            "registry:dependency \"@other/dependency =0.1.2\" ;",
+        );
+    std::fs::write(&field_path, field).unwrap();
+
+    let dependency_contents = "# The contents of a dependency.";
+    let cksum = format!("{:x}", Sha256::digest(dependency_contents));
+    let public_index = home.join(".plow/registry/index/plow-registry-index");
+    std::fs::create_dir_all(&public_index).unwrap();
+    std::fs::write(
+        public_index.join("dependency.json"),
+        format!(r#"{{"versions":[{{"name":"@other/dependency","version":"0.1.2","cksum":"{cksum}","ontology_iri":null,"deps":[]}}]}}"#),
+    )
+    .unwrap();
+
+    let lint_offline = || {
+        Command::cargo_bin("plow").unwrap()
+            .arg("--offline")
+            .arg("lint")
+            .arg("offline.ttl")
+            .env("HOME", &home)
+            .current_dir(&workspace)
+            .output()
+            .unwrap()
+    };
+
+    let out = lint_offline();
+    assert!(!out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("would need the network"));
+    assert!(stdout.contains("@other/dependency 0.1.2"));
+
+    let field_cache = home.join(".plow/registry/cache");
+    std::fs::write(field_cache.join(format!("{cksum}.ttl")), dependency_contents).unwrap();
+    let out = lint_offline();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stdout));
+}
+
+/// Test that multiple major versions of a field are locked with an ontology IRI each.
+#[test]
+fn plow_update_locks_multiple_major_versions_with_their_own_ontology_iri() {
+    use sha2::{Digest, Sha256};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+    let workspace = tmp_dir.path().join("workspace");
+    let fields = workspace.join("src");
+    std::fs::create_dir_all(&fields).unwrap();
+    std::fs::write(
+        workspace.join("Plow.toml"),
+        "[workspace]\nmultiple-major-versions = true\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/majors")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+
+    // The field depends on the first major version of `@other/cities` and on a field which depends on the second.
+    let field_path = fields.join("majors.ttl");
+    let field = std::fs::read_to_string(tmp_dir.path().join("majors.ttl"))
+        .unwrap()
+        .replace("#registry:", "registry:")
+        .replace("#rdfs:", "rdfs:")
+        .replace(
+            "# registry:dependency \"@namespace/name <version requirement>\", \"@namespace/name <version requirement>\" and so on..\" ;",
+            "registry:dependency \"@other/cities ^1.0.0\", \"@other/countries ^1.0.0\" ;",
+        );
+    std::fs::write(&field_path, field).unwrap();
+
+    let public_index = home.join(".plow/registry/index/plow-registry-index");
+    let field_cache = home.join(".plow/registry/cache");
+    std::fs::create_dir_all(&public_index).unwrap();
+    std::fs::create_dir_all(&field_cache).unwrap();
+    let index_entry = |name: &str, version: &str, deps: &str| {
+        let contents = format!("# {name} {version}");
+        let cksum = format!("{:x}", Sha256::digest(&contents));
+        std::fs::write(field_cache.join(format!("{cksum}.ttl")), contents).unwrap();
+        format!(
+            r#"{{"name":"@other/{name}","version":"{version}","cksum":"{cksum}","ontology_iri":"http://field33.com/ontologies/@other/{name}/","deps":[{deps}]}}"#
+        )
+    };
+    let cities = [
+        index_entry("cities", "1.2.0", ""),
+        index_entry("cities", "2.0.1", ""),
+    ];
+    let countries = index_entry(
+        "countries",
+        "1.0.0",
+        r#"{"name":"@other/cities","req":"^2.0.0"}"#,
+    );
+    std::fs::write(
+        public_index.join("cities.json"),
+        format!(r#"{{"versions":[{}]}}"#, cities.join(",")),
+    )
+    .unwrap();
+    std::fs::write(
+        public_index.join("countries.json"),
+        format!(r#"{{"versions":[{countries}]}}"#),
+    )
+    .unwrap();
+
+    let out = Command::cargo_bin("plow").unwrap()
+        .arg("--offline")
+        .arg("update")
+        .env("HOME", &home)
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stdout));
+
+    let lock_file = std::fs::read_to_string(workspace.join("Plow.lock")).unwrap();
+    assert!(
+        lock_file.contains("ontology_iri = 'http://field33.com/ontologies/@other/cities/1/'"),
+        "{lock_file}"
+    );
+    assert!(
+        lock_file.contains("ontology_iri = 'http://field33.com/ontologies/@other/cities/2/'"),
+        "{lock_file}"
+    );
+    assert!(
+        lock_file.contains("ontology_iri = 'http://field33.com/ontologies/@other/countries/1/'"),
+        "{lock_file}"
+    );
+}
+
+/// Test that `plow login --name <registry>` saves the token next to the ones of other registries.
+#[test]
+fn plow_login_keeps_tokens_of_other_registries() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    let login = |args: &[&str]| {
+        Command::cargo_bin("plow").unwrap()
+            .arg("login")
+            .args(args)
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .unwrap()
+            .assert()
+            .code(0);
+    };
+
+    login(&["default-token"]);
+    login(&["--name", "internal", "internal-token"]);
+    login(&["--name", "internal", "new-internal-token"]);
+
+    let credentials = std::fs::read_to_string(home.join(".plow/credentials.toml")).unwrap();
+    let credentials: toml::Value = toml::from_str(&credentials).unwrap();
+    assert_eq!(credentials["registry"]["token"].as_str(), Some("default-token"));
+    assert_eq!(
+        credentials["registries"]["internal"]["token"].as_str(),
+        Some("new-internal-token")
+    );
+}
+
+/// Test that `plow yank` checks its arguments before contacting a registry.
+#[test]
+fn plow_yank_fails_for_invalid_arguments() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    let yank = |args: &[&str]| {
+        let out = Command::cargo_bin("plow").unwrap()
+            .arg("yank")
+            .args(args)
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(out.status.code(), Some(0xFF));
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+
+    assert!(yank(&["namespace/name", "1.2.3"]).contains("@namespace/name"));
+    assert!(yank(&["@namespace/name", "1.2"]).contains("1.2 is not one"));
+    assert!(yank(&["@namespace/name", "1.2.3", "--undo", "--name", "internal"])
+        .contains("no registry named internal"));
+}
+
+/// Test that `plow submit --registry` only submits to registries named in the config file.
+#[test]
+fn plow_submit_fails_for_unknown_registry() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/submitted")
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+    // Fill in the annotations which the generated field leaves commented out for the lints to pass.
+    let field_path = tmp_dir.path().join("submitted.ttl");
+    let field = std::fs::read_to_string(&field_path)
+        .unwrap()
+        .replace("\n#registry:", "\nregistry:")
+        .replace("\n#rdfs:", "\nrdfs:");
+    std::fs::write(&field_path, field).unwrap();
+
+    let out = Command::cargo_bin("plow").unwrap()
+        .arg("submit")
+        .arg("--registry")
+        .arg("internal")
+        .arg("submitted.ttl")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(0xFF));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("no registry named internal"));
+}
+
+/// Test that `plow registry serve` serves a registry in a directory which `plow submit` submits to and `plow update` resolves from.
+#[test]
+fn plow_registry_serve_serves_the_directory() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+    let registry_dir = tmp_dir.path().join("registry");
+    let workspace = tmp_dir.path().join("workspace");
+    std::fs::create_dir_all(workspace.join("src")).unwrap();
+    std::fs::write(workspace.join("Plow.toml"), "[workspace]\n").unwrap();
+
+    let mut server = Command::cargo_bin("plow").unwrap()
+        .args(["registry", "serve", "--address", "127.0.0.1:0", "--token", "secret"])
+        .arg(&registry_dir)
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut serving = String::new();
+    BufReader::new(server.stdout.take().unwrap())
+        .read_line(&mut serving)
+        .unwrap();
+    let url = serving
+        .split_whitespace()
+        .find(|word| word.starts_with("http://"))
+        .unwrap()
+        .to_owned();
+
+    // An empty public index, so that nothing is fetched from the network.
+    let public_index = tmp_dir.path().join("public-index");
+    let repository = git2::Repository::init(&public_index).unwrap();
+    let tree = repository
+        .find_tree(repository.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("plow", "plow@example.com").unwrap();
+    repository
+        .commit(Some("refs/heads/main"), &signature, &signature, "Empty index", &tree, &[])
+        .unwrap();
+    repository.set_head("refs/heads/main").unwrap();
+    std::fs::create_dir_all(workspace.join(".plow")).unwrap();
+    std::fs::write(
+        workspace.join(".plow/config.toml"),
+        format!(
+            "[registry]\nindex = \"{url}\"\ntoken = \"secret\"\npublic-index = \"{}\"\npublic-index-branch = \"main\"\n",
+            public_index.display()
+        ),
+    )
+    .unwrap();
+    let plow = |args: &[&str]| {
+        Command::cargo_bin("plow").unwrap()
+            .arg("--config")
+            .arg(&workspace)
+            .args(args)
+            .env("HOME", &home)
+            .current_dir(&workspace)
+            .output()
+            .unwrap()
+    };
+
+    // Fills in the annotations which the generated fields leave commented out for the lints to pass,
+    // registries also need the canonical prefix.
+    let field = |name: &str, dependency: Option<&str>| {
+        Command::cargo_bin("plow").unwrap()
+            .arg("init")
+            .arg("--field")
+            .arg(format!("@test/{name}"))
+            .current_dir(tmp_dir.path())
+            .unwrap()
+            .assert()
+            .code(0);
+        let mut contents = std::fs::read_to_string(tmp_dir.path().join(format!("{name}.ttl")))
+            .unwrap()
+            .replace("\n#registry:", "\nregistry:")
+            .replace("\n#rdfs:", "\nrdfs:")
+            .replace(
+                "registry:ontologyFormatVersion",
+                &format!("registry:canonicalPrefix \"{name}\" ;\n\nregistry:ontologyFormatVersion"),
+            );
+        if let Some(dependency) = dependency {
+            contents = contents.replace(
+                "# registry:dependency \"@namespace/name <version requirement>\", \"@namespace/name <version requirement>\" and so on..\" ;",
+                &format!("registry:dependency \"{dependency}\" ;"),
+            );
+        }
+        contents
+    };
+
+    let served = field("served", None);
+    let served_path = tmp_dir.path().join("served.ttl");
+    std::fs::write(&served_path, &served).unwrap();
+    let submitted = plow(&["submit", served_path.to_str().unwrap()]);
+    std::fs::write(
+        workspace.join("src/dependent.ttl"),
+        field("dependent", Some("@test/served ^0.1.0")),
+    )
+    .unwrap();
+    let updated = plow(&["update"]);
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert_eq!(
+        submitted.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&submitted.stdout)
+    );
+    assert!(registry_dir.join("index/@test/served.json").exists());
+    assert_eq!(
+        updated.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&updated.stdout)
+    );
+    let lock_file = std::fs::read_to_string(workspace.join("Plow.lock")).unwrap();
+    assert!(lock_file.contains("@test/served"));
+    // The field was downloaded from the registry through a signed url.
+    let cached = std::fs::read_dir(workspace.join(".plow/registry/cache"))
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .map_or(false, |extension| extension == "ttl")
+        })
+        .map(|entry| std::fs::read_to_string(entry.path()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(cached, vec![served]);
+}
+
+/// Test that `plow lint --format json|sarif` prints nothing but the results of the lints.
+#[test]
+fn plow_lint_prints_machine_readable_results() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/formats")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+
+    let lint = |format: &str| {
+        Command::cargo_bin("plow").unwrap()
+            .arg("lint")
+            .arg("--format")
+            .arg(format)
+            .arg("formats.ttl")
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap()
+    };
+
+    // The generated field leaves out annotations which the field linter requires.
+    let out = lint("json");
+    assert_eq!(out.status.code(), Some(0xFF));
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+    assert!(reports.iter().any(|report| report["severity"] == "failure"));
+    assert!(reports.iter().any(|report| {
+        report["lint"] == "has_registry_package_name"
+            && report["severity"] == "success"
+            && report["span"]["line"].is_number()
+    }));
+
+    let out = lint("sarif");
+    assert_eq!(out.status.code(), Some(0xFF));
+    let log: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), reports.len());
+    assert!(results.iter().any(|result| result["level"] == "error"));
+}
+
+/// Test that the `[lints]` table of `Plow.toml` sets the levels which `plow lint` reports lints at.
+#[test]
+fn plow_lint_respects_lint_levels_of_the_workspace() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/levels")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+
+    let lint = || {
+        Command::cargo_bin("plow").unwrap()
+            .arg("lint")
+            .arg("--format")
+            .arg("json")
+            .arg("levels.ttl")
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap()
+    };
+
+    let out = lint();
+    assert_eq!(out.status.code(), Some(0xFF));
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let failing_lints = reports
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|report| report["severity"] == "failure")
+        .map(|report| format!("{} = \"warn\"\n", report["lint"].as_str().unwrap()))
+        .collect::<std::collections::HashSet<_>>();
+
+    std::fs::write(
+        tmp_dir.path().join("Plow.toml"),
+        format!("[lints]\n{}", failing_lints.into_iter().collect::<String>()),
+    )
+    .unwrap();
+    let out = lint();
+    assert_eq!(out.status.code(), Some(0));
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(reports
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|report| report["severity"] != "failure"));
+
+    std::fs::write(
+        tmp_dir.path().join("Plow.toml"),
+        "[lints]\nvalid_rdfs_labels = \"forbid\"\n",
+    )
+    .unwrap();
+    assert_ne!(lint().status.code(), Some(0));
+}
+
+/// Test that `plow submit` lints the field with the `[lints]` table of `Plow.toml` as well.
+#[test]
+fn plow_submit_respects_lint_levels_of_the_workspace() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/submitted")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+
+    let submit = || {
+        Command::cargo_bin("plow").unwrap()
+            .arg("submit")
+            .arg("--registry")
+            .arg("internal")
+            .arg("submitted.ttl")
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap()
+    };
+
+    // The generated field leaves out annotations which the field linter requires.
+    assert!(String::from_utf8_lossy(&submit().stdout).contains("Linting failed"));
+
+    let out = Command::cargo_bin("plow").unwrap()
+        .arg("lint")
+        .arg("--format")
+        .arg("json")
+        .arg("submitted.ttl")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .output()
+        .unwrap();
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let failing_lints = reports
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|report| report["severity"] == "failure")
+        .map(|report| format!("{} = \"warn\"\n", report["lint"].as_str().unwrap()))
+        .collect::<std::collections::HashSet<_>>();
+    std::fs::write(
+        tmp_dir.path().join("Plow.toml"),
+        format!("[lints]\n{}", failing_lints.into_iter().collect::<String>()),
+    )
+    .unwrap();
+
+    let out = submit();
+    assert_eq!(out.status.code(), Some(0xFF));
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("Linting failed"));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("no registry named internal"));
+}
+
+/// Test that `plow lint --fix` writes the fixes of the lints to the field and `--dry-run` only shows them.
+#[test]
+fn plow_lint_fixes_the_field() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+    let field_path = tmp_dir.path().join("fixes.ttl");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/fixes")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+
+    let field = std::fs::read_to_string(&field_path)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with("@base"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("registry:ontologyFormatVersion \"v1\"", "registry:ontologyFormatVersion \"v2\"");
+    std::fs::write(&field_path, &field).unwrap();
+
+    let out = Command::cargo_bin("plow").unwrap()
+        .arg("lint")
+        .arg("--fix")
+        .arg("--dry-run")
+        .arg("fixes.ttl")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(0));
+    let diff = String::from_utf8(out.stdout).unwrap();
+    assert!(diff.contains("+@base <http://field33.com/ontologies/@test/fixes/> ."));
+    assert!(diff.contains("-registry:ontologyFormatVersion \"v2\""));
+    assert!(diff.contains("+registry:ontologyFormatVersion \"v1\""));
+    assert_eq!(std::fs::read_to_string(&field_path).unwrap(), field);
+
+    let out = Command::cargo_bin("plow").unwrap()
+        .arg("lint")
+        .arg("--fix")
+        .arg("--format")
+        .arg("json")
+        .arg("fixes.ttl")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .output()
+        .unwrap();
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(reports
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|report| {
+            report["lint"] == "base_matches_root_prefix"
+                || report["lint"] == "has_ontology_format_version"
+        })
+        .all(|report| report["severity"] == "success"));
+    let fixed_field = std::fs::read_to_string(&field_path).unwrap();
+    assert!(fixed_field.contains("@base <http://field33.com/ontologies/@test/fixes/> ."));
+    assert!(fixed_field.contains("registry:ontologyFormatVersion \"v1\""));
+}
+
+/// Test that `plow lint` and `plow submit` run the `[[custom-lints]]` of `Plow.toml` and report them under their names.
+#[test]
+fn plow_lint_runs_custom_lints_of_the_workspace() {
+    let tmp_dir = TempDir::new().unwrap();
+    let home = tmp_dir.path().join("home");
+
+    Command::cargo_bin("plow").unwrap()
+        .arg("init")
+        .arg("--field")
+        .arg("@test/custom")
+        .env("HOME", &home)
+        .current_dir(tmp_dir.path())
+        .unwrap()
+        .assert()
+        .code(0);
+    // Fill in the annotations which the generated field leaves commented out for the built-in lints to pass.
+    let field_path = tmp_dir.path().join("custom.ttl");
+    let field = std::fs::read_to_string(&field_path)
+        .unwrap()
+        .replace("\n#registry:", "\nregistry:")
+        .replace("\n#rdfs:", "\nrdfs:");
+    std::fs::write(&field_path, field).unwrap();
+
+    let write_manifest = |custom_lints: &str| {
+        std::fs::write(tmp_dir.path().join("Plow.toml"), custom_lints).unwrap();
+    };
+    write_manifest(
+        r#"[[custom-lints]]
+name = "ontology_has_definition"
+description = "The ontology has a `skos:definition`"
+class = "http://www.w3.org/2002/07/owl#Ontology"
+predicate = "http://www.w3.org/2004/02/skos/core#definition"
+min-count = 1
+message = "{subject} has no `skos:definition`."
+level = "warn"
+
+[[custom-lints]]
+name = "ontology_has_package_name"
+description = "The ontology has one `registry:packageName`"
+class = "http://www.w3.org/2002/07/owl#Ontology"
+predicate = "http://field33.com/ontologies/REGISTRY/packageName"
+min-count = 1
+max-count = 1
+pattern = "^@test/"
+"#,
+    );
+
+    let lint = || {
+        Command::cargo_bin("plow").unwrap()
+            .arg("lint")
+            .arg("--format")
+            .arg("json")
+            .arg("custom.ttl")
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap()
+    };
+
+    let out = lint();
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+    assert!(reports.iter().any(|report| {
+        report["lint"] == "ontology_has_definition"
+            && report["severity"] == "warning"
+            && report["message"]
+                == "http://field33.com/ontologies/@test/custom/ has no `skos:definition`."
+            && report["span"]["line"].is_number()
+    }));
+    assert!(reports.iter().any(|report| {
+        report["lint"] == "ontology_has_package_name" && report["severity"] == "success"
+    }));
+
+    let submit = || {
+        Command::cargo_bin("plow").unwrap()
+            .arg("submit")
+            .arg("--registry")
+            .arg("internal")
+            .arg("custom.ttl")
+            .env("HOME", &home)
+            .current_dir(tmp_dir.path())
+            .output()
+            .unwrap()
+    };
+    let out = submit();
+    assert!(String::from_utf8_lossy(&out.stdout).contains("no registry named internal"));
+
+    write_manifest(
+        r#"[[custom-lints]]
+name = "ontology_has_definition"
+description = "The ontology has a `skos:definition`"
+class = "http://www.w3.org/2002/07/owl#Ontology"
+predicate = "http://www.w3.org/2004/02/skos/core#definition"
+min-count = 1
+"#,
+    );
+    let out = submit();
+    assert_eq!(out.status.code(), Some(0xFF));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Linting failed"));
+
+    write_manifest(
+        r#"[[custom-lints]]
+name = "valid_rdfs_labels"
+description = "Shadows a built-in lint"
+class = "http://www.w3.org/2002/07/owl#Ontology"
+predicate = "http://www.w3.org/2004/02/skos/core#definition"
+min-count = 1
+"#,
+    );
+    let out = lint();
+    assert_ne!(out.status.code(), Some(0));
+    assert!(String::from_utf8(out.stdout)
+        .unwrap()
+        .contains("has the name of a built-in lint"));
+}
@@ -0,0 +1,198 @@
+//! Lints which a workspace declares itself, for rules which only hold in it.
+//!
+//! A custom lint is a declarative [`Rule`] about the values which the instances of a class have for a predicate:
+//! how many of them there are, which datatype they have and which pattern they match.
+//! It is deliberately not a query language, rules which need more than that are better written as built-in lints.
+
+mod graph;
+
+use crate::{
+    lint::{lint_failure, lint_success, Lint, LintMessage, LintResult},
+    lints::LintName,
+    Linter,
+};
+use graph::{Graph, Term};
+use regex::Regex;
+use std::any::Any;
+use std::str::FromStr;
+
+/// What the instances of a class have to follow for a predicate, every IRI is written out in full.
+#[derive(Debug, Clone, Default)]
+pub struct Rule {
+    /// The subjects which the rule applies to are the instances of the class and of its subclasses.
+    pub class: String,
+    /// The predicate whose values are checked.
+    pub predicate: String,
+    /// The least number of values which an instance has.
+    pub min_count: Option<usize>,
+    /// The most number of values which an instance has.
+    pub max_count: Option<usize>,
+    /// The datatype of every value, which has to be a literal then.
+    pub datatype: Option<String>,
+    /// A regular expression which every value matches, the lexical form of literals and IRIs as they are.
+    /// Flags are set inline, e.g. `(?i)` to ignore the case.
+    pub pattern: Option<String>,
+}
+
+/// A lint of the workspace, reported like the built-in lints under its own name.
+#[derive(Debug, Clone)]
+pub struct CustomLint {
+    name: String,
+    description: String,
+    rule: Rule,
+    pattern: Option<Regex>,
+    /// Replaces the messages of the values which break the rule, `{subject}` is replaced with the instance.
+    message: Option<String>,
+}
+
+impl CustomLint {
+    /// A lint which fails for every instance of the class of the rule which does not follow it.
+    pub fn new(
+        name: &str,
+        description: &str,
+        rule: Rule,
+        message: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            anyhow::bail!("The name of the custom lint `{name}` is empty or contains whitespace.");
+        }
+        if LintName::from_str(name).is_ok() {
+            anyhow::bail!("The custom lint `{name}` has the name of a built-in lint.");
+        }
+        for (key, iri) in [("class", &rule.class), ("predicate", &rule.predicate)] {
+            if iri.is_empty()
+                || iri.contains(|character: char| {
+                    character.is_whitespace() || "<>\"".contains(character)
+                })
+            {
+                anyhow::bail!("The {key} of `{name}` has to be a full IRI, but it is `{iri}`.");
+            }
+        }
+        if rule.min_count.is_none()
+            && rule.max_count.is_none()
+            && rule.datatype.is_none()
+            && rule.pattern.is_none()
+        {
+            anyhow::bail!("`{name}` checks nothing, it needs at least one of a minimum or maximum count, a datatype or a pattern.");
+        }
+        if let (Some(min_count), Some(max_count)) = (rule.min_count, rule.max_count) {
+            if min_count > max_count {
+                anyhow::bail!("The minimum count of `{name}` is larger than its maximum count.");
+            }
+        }
+        let pattern = rule
+            .pattern
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|err| {
+                    anyhow::anyhow!(
+                        "The pattern `{pattern}` of `{name}` is not a valid regular expression: {err}"
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            rule,
+            pattern,
+            message: message.map(ToOwned::to_owned),
+        })
+    }
+
+    /// The messages for what the instance breaks of the rule.
+    fn check(&self, instance: &Term, graph: &Graph) -> Vec<String> {
+        let Rule {
+            predicate,
+            min_count,
+            max_count,
+            datatype,
+            pattern,
+            ..
+        } = &self.rule;
+        let mut messages = vec![];
+
+        let values = graph.objects(instance, predicate).collect::<Vec<_>>();
+        if let Some(min_count) = min_count.filter(|min_count| values.len() < *min_count) {
+            messages.push(format!(
+                "{instance} has {} values for {predicate}, but at least {min_count} are required.",
+                values.len()
+            ));
+        }
+        if let Some(max_count) = max_count.filter(|max_count| values.len() > *max_count) {
+            messages.push(format!(
+                "{instance} has {} values for {predicate}, but at most {max_count} are allowed.",
+                values.len()
+            ));
+        }
+        for value in values {
+            if let Some(datatype) = datatype {
+                if value
+                    .as_literal()
+                    .map_or(true, |literal| &literal.datatype != datatype)
+                {
+                    messages.push(format!(
+                        "The value {value} of {predicate} of {instance} does not have the datatype {datatype}."
+                    ));
+                }
+            }
+            if let (Some(pattern), Some(regex)) = (pattern, &self.pattern) {
+                if matches!(value, Term::BlankNode(_)) || !regex.is_match(&value.to_string()) {
+                    messages.push(format!(
+                        "The value {value} of {predicate} of {instance} does not match the pattern `{pattern}`."
+                    ));
+                }
+            }
+        }
+
+        match &self.message {
+            Some(message) if !messages.is_empty() => {
+                vec![message.replace("{subject}", &instance.to_string())]
+            }
+            _ => messages,
+        }
+    }
+}
+
+impl Lint for CustomLint {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn name(&self) -> LintName {
+        LintName::Custom(self.name.clone())
+    }
+    fn short_description(&self) -> &str {
+        &self.description
+    }
+
+    /// Messages point at the instance which breaks the rule.
+    fn run(&self, linter: &Linter) -> LintResult {
+        let graph = match Graph::from_document(&linter.document) {
+            Ok(graph) => graph,
+            Err(err) => {
+                return lint_failure!(format!(
+                    "The triples of the field could not be read for `{}`: {err}",
+                    self.name
+                ))
+            }
+        };
+
+        let messages = graph
+            .instances_of(&self.rule.class)
+            .iter()
+            .flat_map(|instance| {
+                let span = instance
+                    .as_iri()
+                    .and_then(|iri| linter.source_map.subject_span(iri));
+                self.check(instance, &graph)
+                    .into_iter()
+                    .map(move |message| LintMessage::from(message).with_span(span))
+            })
+            .collect::<Vec<_>>();
+
+        if messages.is_empty() {
+            return lint_success!(format!("The field passes `{}`.", self.name));
+        }
+        LintResult::Failure(messages)
+    }
+}
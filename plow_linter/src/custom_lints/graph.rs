@@ -0,0 +1,164 @@
+use harriet::triple_production::{
+    RdfBlankNode, RdfObject, RdfPredicate, RdfSubject, TripleProducer,
+};
+use harriet::TurtleDocument;
+use plow_ontology::constants::RDF_TYPE;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+pub const RDFS_SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// A node of a [`Graph`], blank nodes are numbered in the order in which they appear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    BlankNode(usize),
+    Literal(Literal),
+}
+
+/// A literal, strings without a datatype are `xsd:string` and ones with a language tag `rdf:langString`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Literal {
+    pub lexical_form: String,
+    pub datatype: String,
+    pub language: Option<String>,
+}
+
+impl Term {
+    pub fn iri(iri: &str) -> Self {
+        Self::Iri(iri.to_owned())
+    }
+
+    pub fn as_iri(&self) -> Option<&str> {
+        match self {
+            Self::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    pub const fn as_literal(&self) -> Option<&Literal> {
+        match self {
+            Self::Literal(literal) => Some(literal),
+            _ => None,
+        }
+    }
+}
+
+/// Terms are written like in messages for humans, IRIs and literals without any delimiters.
+impl Display for Term {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Iri(iri) => f.write_str(iri),
+            Self::BlankNode(id) => write!(f, "_:b{id}"),
+            Self::Literal(literal) => f.write_str(&literal.lexical_form),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: String,
+    pub object: Term,
+}
+
+/// The triples of a document, including the ones of blank nodes and collections.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    pub triples: Vec<Triple>,
+}
+
+impl Graph {
+    pub fn from_document(document: &TurtleDocument) -> anyhow::Result<Self> {
+        let mut blank_nodes: HashMap<RdfBlankNode, usize> = HashMap::new();
+        let mut blank_node = |node: RdfBlankNode| {
+            let next_id = blank_nodes.len();
+            Term::BlankNode(*blank_nodes.entry(node).or_insert(next_id))
+        };
+        let triples = TripleProducer::produce_for_document(document)?
+            .into_iter()
+            .map(|triple| {
+                let subject = match triple.subject {
+                    RdfSubject::IRI(iri) => Term::Iri(iri.iri.into_owned()),
+                    RdfSubject::BlankNode(node) => blank_node(node),
+                };
+                let RdfPredicate::IRI(predicate) = triple.predicate;
+                let object = match triple.object {
+                    RdfObject::IRI(iri) => Term::Iri(iri.iri.into_owned()),
+                    RdfObject::BlankNode(node) => blank_node(node),
+                    RdfObject::Literal(literal) => {
+                        let datatype = match (&literal.language_tag, literal.datatype_iri) {
+                            (Some(_), _) => RDF_LANG_STRING.to_owned(),
+                            (None, Some(datatype)) => datatype.iri.into_owned(),
+                            (None, None) => XSD_STRING.to_owned(),
+                        };
+                        Term::Literal(Literal {
+                            lexical_form: literal.lexical_form.into_owned(),
+                            datatype,
+                            language: literal.language_tag.map(std::borrow::Cow::into_owned),
+                        })
+                    }
+                };
+                Triple {
+                    subject,
+                    predicate: predicate.iri.into_owned(),
+                    object,
+                }
+            })
+            .collect();
+        Ok(Self { triples })
+    }
+
+    pub fn objects<'graph: 'query, 'query>(
+        &'graph self,
+        subject: &'query Term,
+        predicate: &'query str,
+    ) -> impl Iterator<Item = &'graph Term> + 'query {
+        self.triples
+            .iter()
+            .filter(move |triple| &triple.subject == subject && triple.predicate == predicate)
+            .map(|triple| &triple.object)
+    }
+
+    pub fn subjects<'graph: 'query, 'query>(
+        &'graph self,
+        predicate: &'query str,
+        object: &'query Term,
+    ) -> impl Iterator<Item = &'graph Term> + 'query {
+        self.triples
+            .iter()
+            .filter(move |triple| triple.predicate == predicate && &triple.object == object)
+            .map(|triple| &triple.subject)
+    }
+
+    /// The class and every class which is declared to be a subclass of it, directly or not.
+    pub fn subclasses(&self, class: &str) -> HashSet<Term> {
+        let mut classes = HashSet::from([Term::iri(class)]);
+        let mut pending = vec![Term::iri(class)];
+        while let Some(class) = pending.pop() {
+            for subclass in self.subjects(RDFS_SUB_CLASS_OF, &class) {
+                if classes.insert(subclass.clone()) {
+                    pending.push(subclass.clone());
+                }
+            }
+        }
+        classes
+    }
+
+    /// Every node which is typed with the class or one of its subclasses.
+    pub fn instances_of(&self, class: &str) -> Vec<Term> {
+        let classes = self.subclasses(class);
+        let mut instances = vec![];
+        for triple in &self.triples {
+            if triple.predicate == RDF_TYPE
+                && classes.contains(&triple.object)
+                && !instances.contains(&triple.subject)
+            {
+                instances.push(triple.subject.clone());
+            }
+        }
+        instances
+    }
+}
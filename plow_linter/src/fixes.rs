@@ -0,0 +1,381 @@
+//! Fixes which lints suggest for what they find, applied to the parsed field so that the rest of it stays as it was written.
+
+use crate::lint::FixSuggestion;
+use crate::source_map::{expand, resolve};
+use harriet::{
+    BaseDirective, Directive, IRIReference, Literal, Object, ObjectList, PredicateObjectList,
+    PrefixDirective, PrefixedName, RDFLiteral, Statement, StringLiteralQuote, Subject, Triples,
+    TurtleDocument, TurtleString, Verb, Whitespace, IRI,
+};
+use plow_ontology::constants::{REGISTRY_PREFIX, REGISTRY_PREFIX_IRI};
+use plow_package_management::metadata::get_root_prefix;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Adds `@prefix` directives after the directives of the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddPrefixes {
+    prefixes: Vec<(String, String)>,
+}
+
+impl AddPrefixes {
+    pub const fn new(prefixes: Vec<(String, String)>) -> Self {
+        Self { prefixes }
+    }
+
+    pub fn for_missing_registry() -> Self {
+        Self {
+            prefixes: vec![(REGISTRY_PREFIX.to_owned(), REGISTRY_PREFIX_IRI.to_owned())],
+        }
+    }
+}
+
+impl FixSuggestion for AddPrefixes {
+    fn apply(&self, document: &mut TurtleDocument) {
+        for (prefix, iri) in &self.prefixes {
+            insert_directive(document, |leading_whitespace| {
+                Directive::Prefix(PrefixDirective {
+                    leading_whitespace,
+                    prefix: Some(prefix.clone().into()),
+                    iri: IRIReference {
+                        iri: iri.clone().into(),
+                    },
+                })
+            });
+        }
+    }
+}
+
+impl Display for AddPrefixes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let directives = self
+            .prefixes
+            .iter()
+            .map(|(prefix, iri)| format!("`@prefix {prefix}: <{iri}> .`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Add {directives}")
+    }
+}
+
+/// Sets the IRI of the `@base` directive, leaving only one of them in the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetBase {
+    iri: String,
+}
+
+impl SetBase {
+    pub fn new(iri: &str) -> Self {
+        Self {
+            iri: iri.to_owned(),
+        }
+    }
+}
+
+impl FixSuggestion for SetBase {
+    fn apply(&self, document: &mut TurtleDocument) {
+        let mut is_set = false;
+        document.statements.retain_mut(|statement| {
+            if let Statement::Directive(Directive::Base(directive)) = statement {
+                if is_set {
+                    return false;
+                }
+                directive.iri.iri = self.iri.clone().into();
+                is_set = true;
+            }
+            true
+        });
+        if !is_set {
+            insert_directive(document, |leading_whitespace| {
+                Directive::Base(BaseDirective {
+                    leading_whitespace,
+                    iri: IRIReference {
+                        iri: self.iri.clone().into(),
+                    },
+                })
+            });
+        }
+    }
+}
+
+impl Display for SetBase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Set the @base directive to `<{}>`", self.iri)
+    }
+}
+
+/// Sets the value of an annotation on the ontology to a string literal, adding the annotation if there is none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetOntologyAnnotation {
+    predicate: String,
+    value: String,
+}
+
+impl SetOntologyAnnotation {
+    pub fn new(predicate: &str, value: &str) -> Self {
+        Self {
+            predicate: predicate.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl FixSuggestion for SetOntologyAnnotation {
+    fn apply(&self, document: &mut TurtleDocument) {
+        let ontology = match get_root_prefix(document) {
+            Some(root_prefix) => root_prefix.to_string(),
+            None => return,
+        };
+        let namespaces = Namespaces::of(document);
+        let value = Object::Literal(Literal::RDFLiteral(RDFLiteral {
+            string: TurtleString::StringLiteralQuote(StringLiteralQuote {
+                string: self.value.replace('\\', "\\\\").replace('"', "\\\"").into(),
+            }),
+            language_tag: None,
+            iri: None,
+        }));
+
+        let mut annotations_of_ontology = None;
+        for statement in &mut document.statements {
+            if let Statement::Triples(Triples::Labeled(_, Subject::IRI(subject), list)) = statement
+            {
+                if namespaces.expand(subject).as_deref() != Some(ontology.as_str()) {
+                    continue;
+                }
+                let annotation = list.list.iter_mut().find(|(_, verb, _, _)| {
+                    matches!(verb, Verb::IRI(predicate) if namespaces.expand(predicate).as_deref() == Some(self.predicate.as_str()))
+                });
+                if let Some((_, _, objects, _)) = annotation {
+                    let whitespace_after_verb = objects
+                        .list
+                        .first()
+                        .and_then(|(_, whitespace, _)| whitespace.clone())
+                        .or_else(|| Some(Whitespace::space()));
+                    objects.list = vec![(None, whitespace_after_verb, value)];
+                    return;
+                }
+                annotations_of_ontology.get_or_insert(list);
+            }
+        }
+
+        let verb = Verb::IRI(namespaces.shorten(&self.predicate));
+        let objects = ObjectList {
+            list: vec![(None, Some(Whitespace::space()), value)],
+        };
+        if let Some(list) = annotations_of_ontology {
+            // Continues the annotations on the line after the last one, indented like it.
+            let leading_whitespace = list
+                .list
+                .last()
+                .and_then(|(whitespace, _, _, _)| whitespace.as_ref())
+                .filter(|whitespace| whitespace.whitespace.contains('\n'))
+                .map_or_else(
+                    || "\n".to_owned(),
+                    |whitespace| {
+                        let indentation = whitespace
+                            .whitespace
+                            .rsplit('\n')
+                            .next()
+                            .unwrap_or_default();
+                        format!("\n{indentation}")
+                    },
+                );
+            if let Some((_, _, _, trailing_whitespace)) = list.list.last_mut() {
+                trailing_whitespace.get_or_insert_with(Whitespace::space);
+            }
+            list.list.push((
+                Some(Whitespace {
+                    whitespace: leading_whitespace.into(),
+                }),
+                verb,
+                objects,
+                None,
+            ));
+        } else {
+            document
+                .statements
+                .push(Statement::Triples(Triples::Labeled(
+                    Some(Whitespace {
+                        whitespace: "\n\n".into(),
+                    }),
+                    Subject::IRI(IRI::IRIReference(IRIReference {
+                        iri: ontology.into(),
+                    })),
+                    PredicateObjectList {
+                        list: vec![(Some(Whitespace::space()), verb, objects, None)],
+                    },
+                )));
+        }
+    }
+}
+
+impl Display for SetOntologyAnnotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Set `<{}>` of the ontology to \"{}\"",
+            self.predicate, self.value
+        )
+    }
+}
+
+/// The string value of the first annotation of the ontology with the predicate.
+pub(crate) fn ontology_annotation_value(
+    document: &TurtleDocument,
+    predicate: &str,
+) -> Option<String> {
+    let ontology = get_root_prefix(document)?;
+    let namespaces = Namespaces::of(document);
+    document
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Triples(Triples::Labeled(_, Subject::IRI(subject), list))
+                if namespaces.expand(subject).as_deref() == Some(ontology.as_ref()) =>
+            {
+                Some(list)
+            }
+            _ => None,
+        })
+        .flat_map(|list| &list.list)
+        .filter(|(_, verb, _, _)| {
+            matches!(verb, Verb::IRI(iri) if namespaces.expand(iri).as_deref() == Some(predicate))
+        })
+        .flat_map(|(_, _, objects, _)| &objects.list)
+        .find_map(|(_, _, object)| match object {
+            Object::Literal(Literal::RDFLiteral(literal)) => Some(match &literal.string {
+                TurtleString::StringLiteralQuote(quote) => quote.string.to_string(),
+                TurtleString::StringLiteralSingleQuote(quote) => quote.string.to_string(),
+                TurtleString::StringLiteralLongQuote(quote) => quote.string.to_string(),
+                TurtleString::StringLiteralLongSingleQuote(quote) => quote.string.to_string(),
+            }),
+            _ => None,
+        })
+}
+
+/// The prefixes out of the ones given which the field has no `@prefix` directive for.
+pub(crate) fn missing_prefixes(
+    document: &TurtleDocument,
+    prefixes: &[(&str, &str)],
+) -> Vec<(String, String)> {
+    prefixes
+        .iter()
+        .filter(|(prefix, _)| {
+            !document.statements.iter().any(|statement| {
+                matches!(statement, Statement::Directive(Directive::Prefix(directive)) if directive.prefix.as_deref() == Some(*prefix))
+            })
+        })
+        .map(|(prefix, iri)| ((*prefix).to_owned(), (*iri).to_owned()))
+        .collect()
+}
+
+/// The `@base` and the prefixes which are declared in the field.
+struct Namespaces {
+    base: Option<String>,
+    prefixes: HashMap<String, String>,
+}
+
+impl Namespaces {
+    fn of(document: &TurtleDocument) -> Self {
+        let mut base: Option<String> = None;
+        let mut prefixes = HashMap::new();
+        for statement in &document.statements {
+            match statement {
+                Statement::Directive(Directive::Base(directive)) => {
+                    base = Some(resolve(base.as_deref(), &directive.iri.iri));
+                }
+                Statement::Directive(Directive::SparqlBase(directive)) => {
+                    base = Some(resolve(base.as_deref(), &directive.iri.iri));
+                }
+                Statement::Directive(Directive::Prefix(directive)) => {
+                    prefixes.insert(
+                        directive.prefix.as_deref().unwrap_or_default().to_owned(),
+                        resolve(base.as_deref(), &directive.iri.iri),
+                    );
+                }
+                Statement::Directive(Directive::SparqlPrefix(directive)) => {
+                    prefixes.insert(
+                        directive.prefix.as_deref().unwrap_or_default().to_owned(),
+                        resolve(base.as_deref(), &directive.iri.iri),
+                    );
+                }
+                Statement::Triples(_) => {}
+            }
+        }
+        Self { base, prefixes }
+    }
+
+    fn expand(&self, iri: &IRI) -> Option<String> {
+        expand(iri, self.base.as_deref(), &self.prefixes)
+    }
+
+    /// Writes the IRI with a named prefix of the field if there is one for it.
+    fn shorten(&self, iri: &str) -> IRI<'static> {
+        self.prefixes
+            .iter()
+            .filter(|(prefix, _)| !prefix.is_empty())
+            .find_map(|(prefix, namespace)| {
+                let name = iri.strip_prefix(namespace.as_str())?;
+                let is_name = !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|character| character.is_alphanumeric() || character == '_');
+                is_name.then(|| {
+                    IRI::PrefixedName(PrefixedName {
+                        prefix: Some(prefix.clone().into()),
+                        name: Some(name.to_owned().into()),
+                    })
+                })
+            })
+            .unwrap_or_else(|| {
+                IRI::IRIReference(IRIReference {
+                    iri: iri.to_owned().into(),
+                })
+            })
+    }
+}
+
+/// Inserts the directive after the directives which the field starts with, on a line of its own.
+fn insert_directive<'document>(
+    document: &mut TurtleDocument<'document>,
+    directive: impl FnOnce(Option<Whitespace<'document>>) -> Directive<'document>,
+) {
+    let index = document
+        .statements
+        .iter()
+        .take_while(|statement| matches!(statement, Statement::Directive(_)))
+        .count();
+    if index == 0 {
+        if let Some(next_statement) = document.statements.first_mut() {
+            leading_whitespace(next_statement).get_or_insert_with(|| Whitespace {
+                whitespace: "\n".into(),
+            });
+        }
+        document
+            .statements
+            .insert(0, Statement::Directive(directive(None)));
+    } else {
+        let newline = Whitespace {
+            whitespace: "\n".into(),
+        };
+        document
+            .statements
+            .insert(index, Statement::Directive(directive(Some(newline))));
+    }
+}
+
+fn leading_whitespace<'statement, 'document>(
+    statement: &'statement mut Statement<'document>,
+) -> &'statement mut Option<Whitespace<'document>> {
+    match statement {
+        Statement::Directive(Directive::Base(directive)) => &mut directive.leading_whitespace,
+        Statement::Directive(Directive::SparqlBase(directive)) => &mut directive.leading_whitespace,
+        Statement::Directive(Directive::Prefix(directive)) => &mut directive.leading_whitespace,
+        Statement::Directive(Directive::SparqlPrefix(directive)) => {
+            &mut directive.leading_whitespace
+        }
+        Statement::Triples(
+            Triples::Labeled(whitespace, _, _) | Triples::Blank(whitespace, _, _),
+        ) => whitespace,
+    }
+}
@@ -79,16 +79,24 @@
 //! For a full documentation of the package management process see [`doc_process`].
 
 use harriet::TurtleDocument;
-use lint::LintResult;
-use lints::{LintSet, PlowLint};
+use lint::{Fixes, Lint, LintLevel, LintMessage, LintReport, LintResult};
+use lints::{LintName, LintSet, PlowLint};
 use plow_graphify::document_to_graph;
+use plow_ontology::constants::REGISTRY_ALLOW_LINT;
+use plow_package_management::metadata::get_root_prefix;
+use source_map::SourceMap;
+use std::collections::{HashMap, HashSet};
 
 use field33_rdftk_core_temporary_fork::model::graph::GraphRef;
 // use rayon::prelude::IntoParallelRefIterator;
 // use rayon::prelude::ParallelIterator;
 
+pub mod custom_lints;
+pub mod fixes;
 pub mod lint;
 pub mod lints;
+pub mod sarif;
+pub mod source_map;
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// Documentation of the processes executed by the package manager
@@ -145,7 +153,11 @@ pub struct MultiReaderRdfGraph {
 pub struct Linter<'linter> {
     document: TurtleDocument<'linter>,
     graph: MultiReaderRdfGraph,
+    source_map: SourceMap,
     lints: Vec<LintSet>,
+    lint_levels: HashMap<LintName, LintLevel>,
+    /// Names of the lints which are allowed with `registry:allowLint` annotations, by the subject which they are allowed on.
+    allowed_lints: HashMap<String, HashSet<String>>,
 }
 
 impl<'linter> TryFrom<&'linter str> for Linter<'linter> {
@@ -155,10 +167,15 @@ impl<'linter> TryFrom<&'linter str> for Linter<'linter> {
             .map_err(|_| anyhow::anyhow!("Parse error."))?;
         let graph = document_to_graph(&document)?;
         let multi_reader_graph = MultiReaderRdfGraph { inner: graph };
+        let source_map = SourceMap::new(field_contents, &document);
+        let allowed_lints = allowed_lints(&multi_reader_graph);
         Ok(Self {
             document,
             graph: multi_reader_graph,
+            source_map,
             lints: vec![],
+            lint_levels: HashMap::new(),
+            allowed_lints,
         })
     }
 }
@@ -202,14 +219,55 @@ impl Linter<'_> {
     pub fn remove_all_lints(&mut self) {
         self.lints.clear();
     }
+
+    /// Sets the level which the results of the lint are reported at.
+    pub fn set_lint_level(&mut self, lint: LintName, level: LintLevel) {
+        self.lint_levels.insert(lint, level);
+    }
+
+    /// Sets the levels which the results of the lints are reported at, e.g. the ones of a workspace.
+    pub fn set_lint_levels(&mut self, lint_levels: &HashMap<LintName, LintLevel>) {
+        for (lint, level) in lint_levels {
+            self.set_lint_level(lint.clone(), *level);
+        }
+    }
+
+    /// Reports every lint as it is, regardless of the levels which were set and the lints which the field allows.
+    ///
+    /// For lints which must pass no matter what, e.g. the ones required for submission to a registry.
+    pub fn enforce_all_lints(&mut self) {
+        self.lint_levels.clear();
+        self.allowed_lints.clear();
+    }
+}
+
+/// Collects the names of the lints which the `registry:allowLint` annotations in the graph allow.
+///
+/// Names are not checked against the known lints, as custom lints are only known to the workspace.
+fn allowed_lints(graph: &MultiReaderRdfGraph) -> HashMap<String, HashSet<String>> {
+    let mut allowed_lints: HashMap<String, HashSet<String>> = HashMap::new();
+    for statement in graph.inner.borrow().statements() {
+        if statement.predicate().to_string() != REGISTRY_ALLOW_LINT {
+            continue;
+        }
+        if let (Some(subject), Some(literal)) = (
+            statement.subject().as_iri(),
+            statement.object().as_literal(),
+        ) {
+            allowed_lints
+                .entry(subject.to_string())
+                .or_default()
+                .insert(literal.lexical_form().trim().to_owned());
+        }
+    }
+    allowed_lints
 }
 
 impl Linter<'_> {
     pub fn run_all_lints(&self) -> Vec<LintResult> {
         let results = self.lints.iter().fold(vec![], |mut results, lint_set| {
             let set_results = lint_set.lints.iter().fold(vec![], |mut set_results, lint| {
-                let result = lint.run(self);
-                set_results.push(result);
+                set_results.extend(self.run_lint(lint.as_ref()));
                 set_results
             });
             results.extend(set_results);
@@ -222,9 +280,7 @@ impl Linter<'_> {
         let set_results = self.lints.iter().fold(vec![], |set_results, lint_set| {
             if lint_set.id == id {
                 let set_results = lint_set.lints.iter().fold(vec![], |mut set_results, lint| {
-                    let result = lint.run(self);
-
-                    set_results.push(result);
+                    set_results.extend(self.run_lint(lint.as_ref()));
                     set_results
                 });
                 return set_results;
@@ -233,12 +289,117 @@ impl Linter<'_> {
         });
         set_results
     }
-    pub fn run_lints_check_if_contains_any_failure(&self) -> bool {
-        self.lints.iter().any(|lint_set| {
+
+    /// Runs the lints of the set like [`Linter::run_lint_set`] and reports every message of their results.
+    pub fn run_lint_set_with_reports(&self, id: uuid::Uuid) -> Vec<LintReport> {
+        self.lints
+            .iter()
+            .filter(|lint_set| lint_set.id == id)
+            .flat_map(|lint_set| &lint_set.lints)
+            .flat_map(|lint| {
+                self.run_lint(lint.as_ref())
+                    .map(|result| self.reports_of(lint.as_ref(), &result))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Runs the lint and reports its result at the level which is set for it, unless it is allowed.
+    ///
+    /// Messages which point at a subject that the lint is allowed on are left out,
+    /// and so is the whole result if none of its messages are left.
+    fn run_lint(&self, lint: &dyn Lint) -> Option<LintResult> {
+        let name = lint.name();
+        let level = self.lint_levels.get(&name).copied();
+        let is_allowed_on = |subject: &str| {
+            self.allowed_lints
+                .get(subject)
+                .map_or(false, |allowed_lints| allowed_lints.contains(name.id()))
+        };
+        let is_allowed_in_field =
+            get_root_prefix(&self.document).map_or(false, |ontology| is_allowed_on(ontology));
+        if level == Some(LintLevel::Allow) || is_allowed_in_field {
+            return None;
+        }
+
+        let is_reported = |message: &LintMessage| {
+            !message
+                .span
+                .and_then(|span| self.source_map.subject_at(span))
+                .map_or(false, is_allowed_on)
+        };
+        let result = match lint.run(self) {
+            LintResult::Success(message) => LintResult::Success(message),
+            LintResult::Warning(messages) => {
+                LintResult::Warning(messages.into_iter().filter(is_reported).collect())
+            }
+            LintResult::Failure(messages) => {
+                LintResult::Failure(messages.into_iter().filter(is_reported).collect())
+            }
+        };
+        if result.messages().is_empty() {
+            return None;
+        }
+        match level {
+            Some(level) => Some(result.with_level(level)),
+            None => Some(result),
+        }
+    }
+
+    /// Reports every message of the result of the lint with the annotation which it refers to.
+    pub fn reports_of(&self, lint: &dyn Lint, result: &LintResult) -> Vec<LintReport> {
+        let predicate = lint.related_predicate();
+        let subject = predicate.and_then(|_| get_root_prefix(&self.document));
+        let annotation_span = subject
+            .zip(predicate)
+            .and_then(|(subject, predicate)| self.source_map.annotation_span(subject, predicate));
+        result
+            .messages()
+            .iter()
+            .map(|message| LintReport {
+                lint: lint.name(),
+                severity: result.severity(),
+                message: message.text.clone(),
+                subject: subject.map(ToString::to_string),
+                predicate: predicate.map(ToOwned::to_owned),
+                span: message.span.or(annotation_span),
+            })
+            .collect()
+    }
+
+    /// Collects the fixes which the lints and sub lints of every set suggest for their warnings and failures.
+    ///
+    /// Lints which are allowed or pass suggest nothing, fixes suggested by more than one lint are only collected once.
+    pub fn suggest_fixes(&self) -> Vec<Fixes> {
+        let mut fixes = vec![];
+        let lints = self.lints.iter().flat_map(|lint_set| {
             lint_set
                 .lints
                 .iter()
-                .any(|lint| lint.run(self).is_failure())
+                .chain(lint_set.sub_lints.iter().flatten())
+        });
+        for lint in lints {
+            let needs_fixing = self
+                .run_lint(lint.as_ref())
+                .map_or(false, |result| !result.is_success());
+            if !needs_fixing {
+                continue;
+            }
+            for fix in lint.suggest_fix(&self.document).unwrap_or_default() {
+                if !fixes.contains(&fix) {
+                    fixes.push(fix);
+                }
+            }
+        }
+        fixes
+    }
+
+    pub fn run_lints_check_if_contains_any_failure(&self) -> bool {
+        self.lints.iter().any(|lint_set| {
+            lint_set.lints.iter().any(|lint| {
+                self.run_lint(lint.as_ref())
+                    .map_or(false, |result| result.is_failure())
+            })
         })
     }
     // pub fn run_lints_in_parallel(&self) -> Vec<LintResult> {
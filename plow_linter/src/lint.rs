@@ -3,15 +3,28 @@ pub(crate) mod helpers;
 
 use std::any::Any;
 
-use crate::{lints::AddPrefixes, Linter};
+use crate::{
+    fixes::{AddPrefixes, SetBase, SetOntologyAnnotation},
+    lints::LintName,
+    source_map::Span,
+    Linter,
+};
 pub use harriet::TurtleDocument;
 use rayon::prelude::{FromParallelIterator, IntoParallelIterator, ParallelIterator};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 /// A lint that can be applied to an ontology.
 pub trait Lint {
+    /// Identifies the lint in its results.
+    fn name(&self) -> LintName;
     /// A short layman-readable description of what the lint is checking for.
     fn short_description(&self) -> &str;
+    /// The predicate of the annotation on the ontology which the lint checks, if it checks one.
+    fn related_predicate(&self) -> Option<&str> {
+        None
+    }
     /// Checks the lint for the ontology.
     fn run(&self, linter: &Linter) -> LintResult;
     /// If possible returns fixes that can be automatically applied to the ontology to resolve the warning/failure.
@@ -32,9 +45,91 @@ pub trait FixSuggestion {
 
 #[derive(Debug, Serialize, Clone)]
 pub enum LintResult {
-    Success(String),
-    Warning(Vec<String>),
-    Failure(Vec<String>),
+    Success(LintMessage),
+    Warning(Vec<LintMessage>),
+    Failure(Vec<LintMessage>),
+}
+
+/// A message of a [`LintResult`], which may point at what it is about in the source of the field.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct LintMessage {
+    pub text: String,
+    pub span: Option<Span>,
+}
+
+impl LintMessage {
+    /// Points the message at the span, unless it is unknown.
+    #[must_use]
+    pub fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span.or(self.span);
+        self
+    }
+}
+
+impl From<String> for LintMessage {
+    fn from(text: String) -> Self {
+        Self { text, span: None }
+    }
+}
+
+impl From<&str> for LintMessage {
+    fn from(text: &str) -> Self {
+        text.to_owned().into()
+    }
+}
+
+impl Display for LintMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Success,
+    Warning,
+    Failure,
+}
+
+/// How the results of a lint are reported, set per lint in the `[lints]` table of `Plow.toml`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// The lint is not run.
+    Allow,
+    /// Failures of the lint are reported as warnings.
+    Warn,
+    /// Warnings of the lint are reported as failures.
+    Deny,
+}
+
+impl FromStr for LintLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(anyhow::anyhow!(
+                "The lint level `{level}` is not one of `allow`, `warn` or `deny`."
+            )),
+        }
+    }
+}
+
+/// A message of a [`LintResult`] together with the lint it comes from and what in the field it refers to.
+#[derive(Debug, Serialize, Clone)]
+pub struct LintReport {
+    pub lint: LintName,
+    pub severity: Severity,
+    pub message: String,
+    /// The subject of the annotation which the message refers to, the ontology for annotations of the field manifest.
+    pub subject: Option<String>,
+    pub predicate: Option<String>,
+    /// Where the message points at in the field, or the annotation which the lint checks if it does not point anywhere.
+    pub span: Option<Span>,
 }
 
 // Wrapper type for lint results needed for parallel execution.
@@ -54,6 +149,45 @@ impl FromParallelIterator<Vec<LintResult>> for LintResults {
 }
 
 impl LintResult {
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::Success(_) => Severity::Success,
+            Self::Warning(_) => Severity::Warning,
+            Self::Failure(_) => Severity::Failure,
+        }
+    }
+
+    pub fn messages(&self) -> &[LintMessage] {
+        match self {
+            Self::Success(message) => std::slice::from_ref(message),
+            Self::Warning(messages) | Self::Failure(messages) => messages,
+        }
+    }
+
+    /// Points every message which does not point anywhere yet at the span.
+    #[must_use]
+    pub fn with_span(self, span: Option<Span>) -> Self {
+        let point = |message: LintMessage| LintMessage {
+            span: message.span.or(span),
+            ..message
+        };
+        match self {
+            Self::Success(message) => Self::Success(point(message)),
+            Self::Warning(messages) => Self::Warning(messages.into_iter().map(point).collect()),
+            Self::Failure(messages) => Self::Failure(messages.into_iter().map(point).collect()),
+        }
+    }
+
+    /// Escalates warnings or relaxes failures to the level, results of allowed lints stay as they are.
+    #[must_use]
+    pub fn with_level(self, level: LintLevel) -> Self {
+        match (self, level) {
+            (Self::Warning(messages), LintLevel::Deny) => Self::Failure(messages),
+            (Self::Failure(messages), LintLevel::Warn) => Self::Warning(messages),
+            (result, _) => result,
+        }
+    }
+
     pub const fn is_success(&self) -> bool {
         matches!(self, Self::Success(_))
     }
@@ -69,43 +203,57 @@ impl LintResult {
 
 macro_rules! lint_success {
     ($exp:expr) => {
-        LintResult::Success($exp.to_owned())
+        LintResult::Success($crate::lint::LintMessage::from($exp.to_owned()))
     };
     ($l:literal) => {
-        LintResult::Success($l.to_owned())
+        LintResult::Success($crate::lint::LintMessage::from($l.to_owned()))
     };
 }
 
 macro_rules! lint_warning {
     ($( $exp:expr ),+) => {
-        LintResult::Warning(vec![$( $exp.to_owned() ),+])
+        LintResult::Warning(vec![$( $crate::lint::LintMessage::from($exp.to_owned()) ),+])
     };
     ($( $l:literal ),+) => {
-        LintResult::Warning(vec![$( $l.to_owned() ),+])
+        LintResult::Warning(vec![$( $crate::lint::LintMessage::from($l.to_owned()) ),+])
     };
 }
 
 macro_rules! lint_failure {
     ($( $exp:expr ),+) => {
-        LintResult::Failure(vec![$( $exp.to_owned() ),+])
+        LintResult::Failure(vec![$( $crate::lint::LintMessage::from($exp.to_owned()) ),+])
     };
     ($( $l:literal ),+) => {
-        LintResult::Failure(vec![$( $l.to_owned() ),+])
+        LintResult::Failure(vec![$( $crate::lint::LintMessage::from($l.to_owned()) ),+])
     };
 
 }
 
 pub(crate) use {lint_failure, lint_success, lint_warning};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Fixes {
     AddPrefixes(AddPrefixes),
+    SetBase(SetBase),
+    SetOntologyAnnotation(SetOntologyAnnotation),
 }
 
 impl FixSuggestion for Fixes {
     fn apply(&self, document: &mut TurtleDocument) {
         match self {
             Self::AddPrefixes(fix) => fix.apply(document),
+            Self::SetBase(fix) => fix.apply(document),
+            Self::SetOntologyAnnotation(fix) => fix.apply(document),
+        }
+    }
+}
+
+impl Display for Fixes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddPrefixes(fix) => fix.fmt(f),
+            Self::SetBase(fix) => fix.fmt(f),
+            Self::SetOntologyAnnotation(fix) => fix.fmt(f),
         }
     }
 }
@@ -184,8 +184,6 @@ pub enum VersionLiteralLintFailureOrWarning {
 pub enum VersionLiteralLintFailure {
     #[error("does not allow for empty version literals")]
     Empty,
-    #[error("does not allow for pre-release or build indicators, e.g. `1.0.0-alpha.1+001`")]
-    PreReleaseOrBuildNotAllowed,
     #[error("does not allow versions other than a single version or a pair of versions.")]
     OnlySingleOrPair,
     #[error("does not allow version pairs with `=` character in it.")]
@@ -209,36 +207,30 @@ pub enum VersionLiteralLintWarning {
 /// An internal helper which validates fields where the expected value is a version
 /// and the expected format is `major.minor.patch` .
 ///
-/// Only simple and fully complete version strings are allowed
-/// with no prefix or suffixes. e.g. major.minor.patch.
+/// Only fully complete version strings are allowed with no prefixes.
+/// Pre-release and build suffixes are allowed, e.g. `major.minor.patch-pre+build`.
 ///
 /// # Example
 /// ```rust,ignore
 /// assert_eq!(validate_version_literal_conservative("1.0.0").is_ok(), true);
 /// assert_eq!(validate_version_literal_conservative(">1.0.0").is_err(), true);
 /// assert_eq!(validate_version_literal_conservative("^1.0.0").is_err(), true);
-/// assert_eq!(validate_version_literal_conservative("1.0.0-alpha.1").is_err(), true);
+/// assert_eq!(validate_version_literal_conservative("1.0.0-alpha.1").is_ok(), true);
+/// assert_eq!(validate_version_literal_conservative("1.0.0-alpha.1+001").is_ok(), true);
 /// ```
 pub fn validate_semantic_version_literal(
     version_literal: &str,
 ) -> Result<(), VersionLiteralLintFailureOrWarning> {
     // Check if the version string is valid
     use VersionLiteralLintFailureOrWarning::*;
-    Version::parse(version_literal)
-        .map_err(|err| {
-            Failure(VersionLiteralLintFailure::InvalidSemanticVersionLiteral(
-                err.to_string(),
+    Version::parse(version_literal).map_or_else(
+        |err| {
+            Err(Failure(
+                VersionLiteralLintFailure::InvalidSemanticVersionLiteral(err.to_string()),
             ))
-        })
-        .and_then(|parsed| {
-            if parsed.pre.is_empty() && parsed.build.is_empty() {
-                Ok(())
-            } else {
-                Err(Failure(
-                    VersionLiteralLintFailure::PreReleaseOrBuildNotAllowed,
-                ))
-            }
-        })
+        },
+        |_| Ok(()),
+    )
 }
 
 /// An internal helper which validates fields where the expected value is a semantic version.
@@ -280,7 +272,12 @@ pub fn validate_semantic_version_requirement_literal(
 
     // Bare version and wildcard checks for both versions and pairs
     for version in &versions {
-        let has_wildcards = version.contains('*') || version.contains('x');
+        // Pre-release and build identifiers may contain `x`, only the numeric part may have wildcards.
+        let numeric_part = version
+            .split(|character| character == '-' || character == '+')
+            .next()
+            .unwrap_or(version);
+        let has_wildcards = numeric_part.contains('*') || numeric_part.contains('x');
         #[allow(clippy::else_if_without_else)]
         if has_wildcards {
             // Has wildcards, omit bare version check.
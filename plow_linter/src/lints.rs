@@ -27,10 +27,11 @@
 // TODO: add lint to check that there is a dependency for every defined import
 // TODO: add lint to check that there is an import for every defined dependency
 // TODO: add lint to check that only IRIs from direct imports (= NOT transitive imports) are used
-use crate::lint::{FixSuggestion, Lint};
-use harriet::{Directive, IRIReference, PrefixDirective, Statement, TurtleDocument};
-use plow_ontology::constants::{REGISTRY_PREFIX, REGISTRY_PREFIX_IRI};
+pub use crate::fixes::AddPrefixes;
+use crate::lint::Lint;
 use plow_package_management::metadata::get_root_prefix;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
 
 pub mod base_matches_root_prefix;
 pub mod contains_owl_prefixes;
@@ -84,7 +85,8 @@ pub use valid_registry_documentation::ValidRegistryDocumentation;
 pub use valid_registry_homepage::ValidRegistryHomepage;
 pub use valid_registry_repository::ValidRegistryRepository;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Identifies a lint, e.g. in machine-readable lint results.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LintName {
     BaseMatchesRootPrefix,
     ContainsOWLPrefixes,
@@ -111,42 +113,104 @@ pub enum LintName {
     ValidRegistryDocumentation,
     ValidRegistryHomepage,
     ValidRegistryRepository,
+    /// A lint of the workspace, see [`CustomLint`](crate::custom_lints::CustomLint).
+    Custom(String),
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct AddPrefixes {
-    prefixes: Vec<(String, String)>,
-}
+impl LintName {
+    /// Every lint which comes with the linter.
+    pub const ALL: [Self; 25] = [
+        Self::BaseMatchesRootPrefix,
+        Self::ContainsOWLPrefixes,
+        Self::ContainsRegistryPrefix,
+        Self::ExistsRegistryLicense,
+        Self::ExistsRegistryLicenseSPDX,
+        Self::HasAtLeastOneValidLicenseAnnotation,
+        Self::HasCanonicalPrefix,
+        Self::HasOntologyDeclaration,
+        Self::HasOntologyFormatVersion,
+        Self::HasRdfsCommentManifestContext,
+        Self::HasRdfsLabelManifestContext,
+        Self::HasRegistryAuthor,
+        Self::HasRegistryCategory,
+        Self::HasRegistryKeyword,
+        Self::HasRegistryLicense,
+        Self::HasRegistryLicenseSPDX,
+        Self::HasRegistryPackageName,
+        Self::HasRegistryPackageVersion,
+        Self::HasRegistryShortDescription,
+        Self::RootPrefixMatchesPattern,
+        Self::ValidRdfsLabels,
+        Self::ValidRegistryDependencies,
+        Self::ValidRegistryDocumentation,
+        Self::ValidRegistryHomepage,
+        Self::ValidRegistryRepository,
+    ];
 
-impl AddPrefixes {
-    pub fn for_missing_registry() -> Self {
-        Self {
-            prefixes: vec![(REGISTRY_PREFIX.to_owned(), REGISTRY_PREFIX_IRI.to_owned())],
+    /// The identifier of the lint, which is the name of its module, e.g. `valid_rdfs_labels`,
+    /// or the name which a custom lint is given.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::BaseMatchesRootPrefix => "base_matches_root_prefix",
+            Self::ContainsOWLPrefixes => "contains_owl_prefixes",
+            Self::ContainsRegistryPrefix => "contains_registry_prefix",
+            Self::ExistsRegistryLicense => "exists_registry_license",
+            Self::ExistsRegistryLicenseSPDX => "exists_registry_license_spdx",
+            Self::HasAtLeastOneValidLicenseAnnotation => {
+                "has_at_least_one_valid_license_annotation"
+            }
+            Self::HasCanonicalPrefix => "has_canonical_prefix",
+            Self::HasOntologyDeclaration => "has_ontology_declaration",
+            Self::HasOntologyFormatVersion => "has_ontology_format_version",
+            Self::HasRdfsCommentManifestContext => "has_rdfs_comment_manifest_context",
+            Self::HasRdfsLabelManifestContext => "has_rdfs_label_manifest_context",
+            Self::HasRegistryAuthor => "has_registry_author",
+            Self::HasRegistryCategory => "has_registry_category",
+            Self::HasRegistryKeyword => "has_registry_keyword",
+            Self::HasRegistryLicense => "has_registry_license",
+            Self::HasRegistryLicenseSPDX => "has_registry_license_spdx",
+            Self::HasRegistryPackageName => "has_registry_package_name",
+            Self::HasRegistryPackageVersion => "has_registry_package_version",
+            Self::HasRegistryShortDescription => "has_registry_short_description",
+            Self::RootPrefixMatchesPattern => "root_prefix_matches_pattern",
+            Self::ValidRdfsLabels => "valid_rdfs_labels",
+            Self::ValidRegistryDependencies => "valid_registry_dependencies",
+            Self::ValidRegistryDocumentation => "valid_registry_documentation",
+            Self::ValidRegistryHomepage => "valid_registry_homepage",
+            Self::ValidRegistryRepository => "valid_registry_repository",
+            Self::Custom(name) => name,
         }
     }
 }
 
-impl FixSuggestion for AddPrefixes {
-    fn apply(&self, document: &mut TurtleDocument) {
-        let mut index_of_last_prefix = 0;
-        for (i, statement) in document.statements.iter().enumerate() {
-            if let Statement::Directive(Directive::Prefix(_)) = statement {
-                index_of_last_prefix = i;
-            }
-        }
-        index_of_last_prefix += 1;
-
-        for prefix in &self.prefixes {
-            let new_prefix = Statement::Directive(Directive::Prefix(PrefixDirective {
-                leading_whitespace: None,
-                prefix: Some(prefix.0.clone().into()),
-                iri: IRIReference {
-                    iri: prefix.1.clone().into(),
-                },
-            }));
-            document.statements.insert(index_of_last_prefix, new_prefix);
-            index_of_last_prefix += 1;
-        }
+impl std::fmt::Display for LintName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl Serialize for LintName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl FromStr for LintName {
+    type Err = anyhow::Error;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|name| name.id() == id)
+            .ok_or_else(|| anyhow::anyhow!("There is no lint with the name `{id}`."))
+    }
+}
+
+impl<'de> Deserialize<'de> for LintName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
     }
 }
 
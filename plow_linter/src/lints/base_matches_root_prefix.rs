@@ -1,9 +1,10 @@
 use std::any::Any;
 
+use crate::fixes::SetBase;
 use crate::lint::common_error_literals::NO_ROOT_PREFIX;
-use crate::lint::{lint_failure, lint_success, Lint, LintResult};
+use crate::lint::{lint_failure, lint_success, Fixes, Lint, LintResult};
 use crate::Linter;
-use harriet::{Directive, Statement};
+use harriet::{Directive, Statement, TurtleDocument};
 use plow_package_management::metadata::get_root_prefix;
 
 use super::LintName;
@@ -24,6 +25,9 @@ impl Lint for BaseMatchesRootPrefix {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        self.name.clone()
+    }
 
     fn short_description(&self) -> &str {
         "Check that the @base directive matches the value for the `:` prefix."
@@ -36,7 +40,7 @@ impl Lint for BaseMatchesRootPrefix {
             for statement in &document.statements {
                 if let Statement::Directive(Directive::Base(directive)) = statement {
                     if base_directive.is_some() {
-                        return LintResult::Failure(vec!["Found more than one @base directive. While it is valid Turtle to redeclare the @base throughout the file, this can easily be misused and is not supported in Field 33 ontologies".to_owned()]);
+                        return LintResult::Failure(vec!["Found more than one @base directive. While it is valid Turtle to redeclare the @base throughout the file, this can easily be misused and is not supported in Field 33 ontologies".into()]);
                     }
                     base_directive = Some(directive);
                 }
@@ -52,4 +56,18 @@ impl Lint for BaseMatchesRootPrefix {
         }
         lint_failure!(NO_ROOT_PREFIX)
     }
+
+    /// Sets the @base directive to the root prefix, without one there is nothing to match.
+    fn suggest_fix(&self, document: &TurtleDocument) -> Option<Vec<Fixes>> {
+        let root_prefix = get_root_prefix(document)?;
+        let base_directives = document
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Directive(Directive::Base(directive)) => Some(&directive.iri.iri),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        (base_directives != [root_prefix]).then(|| vec![Fixes::SetBase(SetBase::new(root_prefix))])
+    }
 }
@@ -1,8 +1,11 @@
 use std::any::Any;
 
-use crate::lint::{lint_success, Lint, LintResult};
+use crate::fixes::{missing_prefixes, AddPrefixes};
+use crate::lint::{lint_success, Fixes, Lint, LintResult};
+use crate::lints::LintName;
 use crate::Linter;
-use harriet::{Directive, Statement};
+use harriet::TurtleDocument;
+use plow_ontology::constants::{REGISTRY_PREFIX, REGISTRY_PREFIX_IRI};
 
 #[derive(Debug, Default)]
 pub struct ContainsRegistryPrefix;
@@ -11,6 +14,9 @@ impl Lint for ContainsRegistryPrefix {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::ContainsRegistryPrefix
+    }
     fn short_description(&self) -> &str {
         "Check if the field contains the prefix for the REGISTRY ontology"
     }
@@ -18,25 +24,21 @@ impl Lint for ContainsRegistryPrefix {
     /// Check if ontology contains the prefix for the REGISTRY ontology.
     /// Currently required to ensure that editing operations work smoothly.
     fn run(&self, Linter { document, .. }: &Linter) -> LintResult {
-        let mut owl_prefixes = vec![("registry", "http://field33.com/ontologies/REGISTRY/")];
-
-        for statement in &document.statements {
-            if let Statement::Directive(Directive::Prefix(directive)) = statement {
-                if let Some(ref directive_prefix) = directive.prefix {
-                    owl_prefixes = owl_prefixes
-                        .into_iter()
-                        .filter(|(prefix, _)| directive_prefix != prefix)
-                        .collect();
-                }
-                continue;
-            }
-        }
+        let missing_prefixes =
+            missing_prefixes(document, &[(REGISTRY_PREFIX, REGISTRY_PREFIX_IRI)]);
 
-        if owl_prefixes.is_empty() {
+        if missing_prefixes.is_empty() {
             return lint_success!("The field contains the `registry:` prefix.");
         }
-        LintResult::Failure(owl_prefixes.iter().map(|(prefix, iri)| {
-            format!("The field is missing a prefix directive for {prefix}: `@prefix {prefix}: <{iri}> .`")
+        LintResult::Failure(missing_prefixes.iter().map(|(prefix, iri)| {
+            format!("The field is missing a prefix directive for {prefix}: `@prefix {prefix}: <{iri}> .`").into()
         }).collect())
     }
+
+    fn suggest_fix(&self, document: &TurtleDocument) -> Option<Vec<Fixes>> {
+        let missing_prefixes =
+            missing_prefixes(document, &[(REGISTRY_PREFIX, REGISTRY_PREFIX_IRI)]);
+        (!missing_prefixes.is_empty())
+            .then(|| vec![Fixes::AddPrefixes(AddPrefixes::for_missing_registry())])
+    }
 }
@@ -1,5 +1,6 @@
 //! This lint is not made to be used directly but directed to be used in `HasAtLeastOneValidLicenseAnnotation` lint.
 
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX, helpers::catch_single_annotations_which_must_exist,
@@ -23,6 +24,12 @@ impl Lint for ExistsRegistryLicenseSPDX {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::ExistsRegistryLicenseSPDX
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_LICENSE_SPDX)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:licenseSPDX`"
     }
@@ -1,5 +1,6 @@
 use std::any::Any;
 
+use crate::lints::LintName;
 use crate::{
     lint::{lint_failure, lint_success, Lint, LintResult},
     Linter,
@@ -22,6 +23,10 @@ impl Lint for HasAtLeastOneValidLicenseAnnotation {
         self
     }
 
+    fn name(&self) -> LintName {
+        LintName::HasAtLeastOneValidLicenseAnnotation
+    }
+
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:license` or `registry:licenseSPDX`, both missing are not allowed."
     }
@@ -1,6 +1,7 @@
 use crate::lint::common_error_literals::NO_ROOT_PREFIX;
 use crate::lint::helpers::catch_single_annotations_which_must_exist;
 use crate::lint::{lint_failure, lint_success, Lint, LintResult};
+use crate::lints::LintName;
 use crate::{Linter, MultiReaderRdfGraph};
 use field33_rdftk_iri_temporary_fork::IRI as RDFTK_IRI;
 use plow_ontology::constants::REGISTRY_CANONICAL_PREFIX;
@@ -17,6 +18,12 @@ impl Lint for HasCanonicalPrefix {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasCanonicalPrefix
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_CANONICAL_PREFIX)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:canonicalPrefix`"
     }
@@ -1,5 +1,6 @@
 use crate::lint::common_error_literals::NO_ROOT_PREFIX;
 use crate::lint::{lint_failure, lint_success, Lint, LintResult};
+use crate::lints::LintName;
 use crate::Linter;
 
 use plow_ontology::constants::{OWL_ONTOLOGY, RDF_TYPE};
@@ -19,6 +20,12 @@ impl Lint for HasOntologyDeclaration {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasOntologyDeclaration
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(RDF_TYPE)
+    }
     fn short_description(&self) -> &str {
         "Check that a field is declared in the file (matching the \":\" @prefix)"
     }
@@ -60,10 +67,10 @@ impl Lint for HasOntologyDeclaration {
                     })
                     .collect::<HashSet<_>>();
 
-                let mut error_messages = vec!["Unable to find ontology declaration.".to_owned()];
+                let mut error_messages = vec!["Unable to find ontology declaration.".into()];
                 for ontology_declaration in all_ontology_declarations {
                     let ontology_iri = ontology_declaration.subject().to_string();
-                    error_messages.push(format!("Found ontology declaration for `{ontology_iri}`. Maybe there is a typo, or no trailing slash?"));
+                    error_messages.push(format!("Found ontology declaration for `{ontology_iri}`. Maybe there is a typo, or no trailing slash?").into());
                 }
                 return LintResult::Failure(error_messages);
             }
@@ -1,9 +1,12 @@
+use crate::fixes::{ontology_annotation_value, SetOntologyAnnotation};
 use crate::lint::common_error_literals::NO_ROOT_PREFIX;
 use crate::lint::helpers::catch_single_annotations_which_must_exist;
-use crate::lint::{lint_failure, lint_success, Lint, LintResult};
+use crate::lint::{lint_failure, lint_success, Fixes, Lint, LintResult};
+use crate::lints::LintName;
 use crate::{Linter, MultiReaderRdfGraph};
 
 use field33_rdftk_iri_temporary_fork::IRI as RDFTK_IRI;
+use harriet::TurtleDocument;
 use plow_ontology::constants::REGISTRY_ONTOLOGY_FORMAT_VERSION;
 use plow_package_management::metadata::get_root_prefix;
 use std::any::Any;
@@ -24,6 +27,12 @@ impl Lint for HasOntologyFormatVersion {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasOntologyFormatVersion
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_ONTOLOGY_FORMAT_VERSION)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:ontologyFormatVersion`, and it is equal to an acceptable value (`v1`)."
     }
@@ -34,6 +43,7 @@ impl Lint for HasOntologyFormatVersion {
         Linter {
             document,
             graph: MultiReaderRdfGraph { inner: rdf_graph },
+            source_map,
             ..
         }: &Linter,
     ) -> LintResult {
@@ -81,8 +91,20 @@ impl Lint for HasOntologyFormatVersion {
                     )),
                 },
             }
+            .with_span(source_map.span_of(annotation))
         } else {
             lint_failure!(NO_ROOT_PREFIX)
         }
     }
+
+    fn suggest_fix(&self, document: &TurtleDocument) -> Option<Vec<Fixes>> {
+        get_root_prefix(document)?;
+        let version = ontology_annotation_value(document, REGISTRY_ONTOLOGY_FORMAT_VERSION);
+        (version.as_deref() != Some("v1")).then(|| {
+            vec![Fixes::SetOntologyAnnotation(SetOntologyAnnotation::new(
+                REGISTRY_ONTOLOGY_FORMAT_VERSION,
+                "v1",
+            ))]
+        })
+    }
 }
@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX, helpers::catch_single_annotations_which_must_exist,
@@ -23,6 +24,12 @@ impl Lint for HasRdfsLabelManifestContext {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRdfsLabelManifestContext
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(RDFS_LABEL)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `rdfs:label`"
     }
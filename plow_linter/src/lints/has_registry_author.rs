@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX,
@@ -27,6 +28,12 @@ impl Lint for HasRegistryAuthor {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRegistryAuthor
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_AUTHOR)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:author`"
     }
@@ -118,8 +125,13 @@ impl Lint for HasRegistryAuthor {
                 if let LintResult::Failure(messages) = result {
                     return lint_failure!(format!(
                         "Some {lint_prefix} annotations are invalid. More info: {}",
-                        messages.join(", ")
-                    ));
+                        messages
+                            .iter()
+                            .map(|message| message.text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                    .with_span(messages.first().and_then(|message| message.span));
                 }
             }
             lint_success!(format!("All {lint_prefix} annotations are valid."))
@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX,
@@ -65,6 +66,12 @@ impl Lint for HasRegistryCategory {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRegistryCategory
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_CATEGORY)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:category`"
     }
@@ -183,8 +190,13 @@ impl Lint for HasRegistryCategory {
                 if let LintResult::Failure(messages) = result {
                     return lint_failure!(format!(
                         "Some {RELATED_FIELD} annotations are invalid. More info: {}",
-                        messages.join(", ")
-                    ));
+                        messages
+                            .iter()
+                            .map(|message| message.text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                    .with_span(messages.first().and_then(|message| message.span));
                 }
             }
             lint_success!(format!("All {RELATED_FIELD} annotations are valid."))
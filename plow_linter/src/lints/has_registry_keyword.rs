@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX,
@@ -30,6 +31,12 @@ impl Lint for HasRegistryKeyword {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRegistryKeyword
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_KEYWORD)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:keyword`"
     }
@@ -112,8 +119,13 @@ impl Lint for HasRegistryKeyword {
                 if let LintResult::Failure(messages) = result {
                     return lint_failure!(format!(
                         "Some {RELATED_FIELD} annotations are invalid. More info: {}",
-                        messages.join(", ")
-                    ));
+                        messages
+                            .iter()
+                            .map(|message| message.text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                    .with_span(messages.first().and_then(|message| message.span));
                 }
             }
             lint_success!(format!("All {RELATED_FIELD} annotations are valid."))
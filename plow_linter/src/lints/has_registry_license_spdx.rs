@@ -1,14 +1,18 @@
+use crate::fixes::{ontology_annotation_value, SetOntologyAnnotation};
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX, helpers::catch_single_annotations_which_must_exist,
-        lint_failure, lint_success, Lint, LintResult,
+        lint_failure, lint_success, Fixes, Lint, LintResult,
     },
     Linter, MultiReaderRdfGraph,
 };
 
 use field33_rdftk_iri_temporary_fork::IRI as RDFTK_IRI;
+use harriet::TurtleDocument;
 use plow_ontology::constants::REGISTRY_LICENSE_SPDX;
 use plow_package_management::metadata::get_root_prefix;
+use spdx::lexer::{Lexer, ParseMode, Token};
 use std::str::FromStr;
 use std::{any::Any, collections::HashSet};
 
@@ -23,6 +27,12 @@ impl Lint for HasRegistryLicenseSPDX {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRegistryLicenseSPDX
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_LICENSE_SPDX)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:licenseSPDX`"
     }
@@ -95,4 +105,85 @@ impl Lint for HasRegistryLicenseSPDX {
             lint_failure!(NO_ROOT_PREFIX)
         }
     }
+
+    fn suggest_fix(&self, document: &TurtleDocument) -> Option<Vec<Fixes>> {
+        let license = ontology_annotation_value(document, REGISTRY_LICENSE_SPDX)?;
+        let normalized_license = normalize_spdx_expression(&license)?;
+        (normalized_license != license).then(|| {
+            vec![Fixes::SetOntologyAnnotation(SetOntologyAnnotation::new(
+                REGISTRY_LICENSE_SPDX,
+                &normalized_license,
+            ))]
+        })
+    }
+}
+
+/// Writes the expression as the SPDX specification does, if it is one once the case of its identifiers and operators is fixed.
+///
+/// Common mistakes like `mit`, `Apache 2.0` or `MIT/Apache-2.0` are accepted.
+fn normalize_spdx_expression(expression: &str) -> Option<String> {
+    let expression = expression
+        .trim()
+        .split_inclusive(|character: char| character.is_whitespace() || "()/".contains(character))
+        .map(|word| {
+            let separator_length = word
+                .chars()
+                .last()
+                .filter(|character| character.is_whitespace() || "()/".contains(*character))
+                .map_or(0, char::len_utf8);
+            let (word, separator) = word.split_at(word.len() - separator_length);
+            if ["AND", "OR", "WITH"]
+                .iter()
+                .any(|operator| operator.eq_ignore_ascii_case(word))
+            {
+                return word.to_uppercase() + separator;
+            }
+            let name = word.trim_end_matches('+');
+            spdx::identifiers::LICENSES
+                .iter()
+                .map(|(license, _, _)| *license)
+                .chain(
+                    spdx::identifiers::EXCEPTIONS
+                        .iter()
+                        .map(|(exception, _)| *exception),
+                )
+                .find(|identifier| identifier.eq_ignore_ascii_case(name))
+                .map_or_else(
+                    || word.to_owned(),
+                    |identifier| {
+                        format!("{identifier}{}", word.get(name.len()..).unwrap_or_default())
+                    },
+                )
+                + separator
+        })
+        .collect::<String>();
+
+    let mut normalized = String::new();
+    for token in Lexer::new_mode(&expression, ParseMode::LAX) {
+        let token = match token.ok()?.token {
+            Token::Spdx(license) => license.name.to_owned(),
+            Token::LicenseRef {
+                doc_ref: Some(doc_ref),
+                lic_ref,
+            } => format!("DocumentRef-{doc_ref}:LicenseRef-{lic_ref}"),
+            Token::LicenseRef {
+                doc_ref: None,
+                lic_ref,
+            } => format!("LicenseRef-{lic_ref}"),
+            Token::Exception(exception) => exception.name.to_owned(),
+            Token::Plus => "+".to_owned(),
+            Token::OpenParen => "(".to_owned(),
+            Token::CloseParen => ")".to_owned(),
+            Token::With => "WITH".to_owned(),
+            Token::And => "AND".to_owned(),
+            Token::Or => "OR".to_owned(),
+        };
+        let is_attached = matches!(token.as_str(), "+" | ")") || normalized.ends_with('(');
+        if !normalized.is_empty() && !is_attached {
+            normalized.push(' ');
+        }
+        normalized.push_str(&token);
+    }
+    spdx::Expression::parse(&normalized).ok()?;
+    Some(normalized)
 }
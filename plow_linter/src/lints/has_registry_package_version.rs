@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX,
@@ -25,17 +26,24 @@ impl Lint for HasRegistryPackageVersion {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::HasRegistryPackageVersion
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_PACKAGE_VERSION)
+    }
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:packageVersion`"
     }
 
     /// Lints for the existence of `registry:packageVersion` and its correct format
-    /// (Only simple and fully complete version strings are allowed with no prefix or suffixes. e.g. major.minor.patch)
+    /// (Only fully complete version strings are allowed with no prefix. Pre-release and build suffixes are allowed. e.g. major.minor.patch-pre+build)
     fn run(
         &self,
         Linter {
             document,
             graph: MultiReaderRdfGraph { inner: rdf_graph },
+            source_map,
             ..
         }: &Linter,
     ) -> LintResult {
@@ -66,6 +74,7 @@ impl Lint for HasRegistryPackageVersion {
             // We know that `annotations` has at least one member here.
             #[allow(clippy::unwrap_used)]
             let annotation = annotations.iter().next().unwrap();
+            let span = source_map.span_of(annotation);
             if let Some(literal) = annotation.object().as_literal() {
                 // Check if the version string is valid
                 let version = literal.lexical_form();
@@ -74,16 +83,18 @@ impl Lint for HasRegistryPackageVersion {
                     use VersionLiteralLintFailureOrWarning::*;
                     match failure_or_warning {
                         Warning(warning) => {
-                            return lint_warning!(format!("{lint_prefix} {warning}"));
+                            return lint_warning!(format!("{lint_prefix} {warning}"))
+                                .with_span(span);
                         }
                         Failure(failure) => {
-                            return lint_failure!(format!("{lint_prefix} {failure}"));
+                            return lint_failure!(format!("{lint_prefix} {failure}"))
+                                .with_span(span);
                         }
                     }
                 }
-                lint_success!(format!("{lint_prefix} is valid."))
+                lint_success!(format!("{lint_prefix} is valid.")).with_span(span)
             } else {
-                lint_failure!("{lint_prefix} is not a literal.")
+                lint_failure!("{lint_prefix} is not a literal.").with_span(span)
             }
         } else {
             lint_failure!(NO_ROOT_PREFIX)
@@ -1,5 +1,6 @@
 use std::any::Any;
 
+use crate::lints::LintName;
 use crate::{
     lint::{common_error_literals::NO_ROOT_PREFIX, lint_failure, lint_success, Lint, LintResult},
     Linter,
@@ -14,6 +15,9 @@ impl Lint for RootPrefixMatchesPattern {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::RootPrefixMatchesPattern
+    }
     fn short_description(&self) -> &str {
         "Check if the value for `@prefix :` matches the pattern `http://field33.com/ontologies/ONTOLOGY_NAME/`"
     }
@@ -1,6 +1,10 @@
+use crate::lints::LintName;
 use crate::lints::get_root_prefix;
 use crate::{
-    lint::{common_error_literals::NO_ROOT_PREFIX, lint_failure, lint_success, Lint, LintResult},
+    lint::{
+        common_error_literals::NO_ROOT_PREFIX, lint_failure, lint_success, Lint, LintMessage,
+        LintResult,
+    },
     Linter,
 };
 
@@ -23,6 +27,9 @@ impl Lint for ValidRdfsLabels {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::ValidRdfsLabels
+    }
     fn short_description(&self) -> &str {
         "Check that the related field is annotated with a value for `rdfs:label`"
     }
@@ -111,9 +118,12 @@ impl Lint for ValidRdfsLabels {
                                 //     ));
                                 // }
                             } else {
-                                failures.push(format!(
-                                    "{common_failure_prefix} is not a literal."
-                                ));
+                                failures.push(
+                                    LintMessage::from(format!(
+                                        "{common_failure_prefix} is not a literal."
+                                    ))
+                                    .with_span(linter.source_map.span_of(statement)),
+                                );
                             }
                             return true;
                         }
@@ -131,9 +141,12 @@ impl Lint for ValidRdfsLabels {
             {
                 // Statements which need an `rdfs:label` predicate
                 let iri = subject_iri.to_string();
-                warnings.push(format!(
+                warnings.push(
+                    LintMessage::from(format!(
                         "The subject with the IRI {iri} does not have an {RELATED_FIELD} associated with it."
-                    ));
+                    ))
+                    .with_span(linter.source_map.subject_span(&iri)),
+                );
             }
 
             if !warnings.is_empty() {
@@ -3,13 +3,18 @@ use crate::lint::helpers::{
     VersionLiteralLintFailureOrWarning,
 };
 use crate::lint::{
-    common_error_literals::NO_ROOT_PREFIX, lint_failure, lint_success, Lint, LintResult,
+    common_error_literals::NO_ROOT_PREFIX, lint_failure, lint_success, Lint, LintMessage,
+    LintResult,
 };
+use crate::lints::LintName;
 use crate::{Linter, MultiReaderRdfGraph};
 
 use field33_rdftk_iri_temporary_fork::IRI as RDFTK_IRI;
 use plow_ontology::constants::REGISTRY_DEPENDENCY;
 use plow_package_management::metadata::get_root_prefix;
+use plow_package_management::resolve::dependency::{
+    is_valid_registry_name, split_registry_qualifier,
+};
 
 use std::any::Any;
 use std::collections::HashSet;
@@ -24,14 +29,22 @@ impl Lint for ValidRegistryDependencies {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::ValidRegistryDependencies
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_DEPENDENCY)
+    }
     fn short_description(&self) -> &str {
         "Check the validity of the values associated to `registry:dependency` fields."
     }
+    #[allow(clippy::too_many_lines)]
     fn run(
         &self,
         Linter {
             document,
             graph: MultiReaderRdfGraph { inner: rdf_graph },
+            source_map,
             ..
         }: &Linter,
     ) -> LintResult {
@@ -60,6 +73,7 @@ impl Lint for ValidRegistryDependencies {
             let mut failures = vec![];
             let mut warnings = vec![];
             for annotation in &annotations {
+                let span = source_map.span_of(annotation);
                 if let Some(literal) = annotation.object().as_literal() {
                     let dependency_literal = literal.lexical_form();
                     let lint_prefix =
@@ -81,14 +95,26 @@ impl Lint for ValidRegistryDependencies {
                     version.remove(0);
 
                     if dependency_name.is_empty() || version.is_empty() {
-                        failures.push(format!(
+                        failures.push(LintMessage::from(format!(
                                 "{lint_prefix} should have both name and version separated by a single space."
-                            ));
+                            )).with_span(span));
                         continue;
                     }
 
-                    if let Err(failure) = validate_namespace_and_name(&dependency_name) {
-                        failures.push(format!("{lint_prefix} {failure}"));
+                    // Dependencies may be qualified with a registry, e.g. `internal:@namespace/name`.
+                    let (registry, dependency_name) = split_registry_qualifier(&dependency_name);
+                    if let Some(registry) = registry {
+                        if !is_valid_registry_name(registry) {
+                            failures.push(LintMessage::from(format!(
+                                "{lint_prefix} should be qualified with a registry name which only contains letters, digits, `-` or `_`."
+                            )).with_span(span));
+                        }
+                    }
+
+                    if let Err(failure) = validate_namespace_and_name(dependency_name) {
+                        failures.push(
+                            LintMessage::from(format!("{lint_prefix} {failure}")).with_span(span),
+                        );
                     }
 
                     if let Err(failures_or_warnings) =
@@ -98,18 +124,27 @@ impl Lint for ValidRegistryDependencies {
                         for failure_or_warning in failures_or_warnings {
                             match failure_or_warning {
                                 Warning(warning) => {
-                                    warnings.push(format!("{lint_prefix} {warning}"));
+                                    warnings.push(
+                                        LintMessage::from(format!("{lint_prefix} {warning}"))
+                                            .with_span(span),
+                                    );
                                 }
                                 Failure(failure) => {
-                                    failures.push(format!("{lint_prefix} {failure}"));
+                                    failures.push(
+                                        LintMessage::from(format!("{lint_prefix} {failure}"))
+                                            .with_span(span),
+                                    );
                                 }
                             }
                         }
                     }
                 } else {
-                    failures.push(format!(
+                    failures.push(
+                        LintMessage::from(format!(
                         "The value for {RELATED_FIELD} with stated dependency should be a literal."
-                    ));
+                    ))
+                        .with_span(span),
+                    );
                 }
             }
             if !failures.is_empty() {
@@ -1,3 +1,4 @@
+use crate::lints::LintName;
 use crate::{
     lint::{
         common_error_literals::NO_ROOT_PREFIX,
@@ -22,6 +23,12 @@ impl Lint for ValidRegistryHomepage {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn name(&self) -> LintName {
+        LintName::ValidRegistryHomepage
+    }
+    fn related_predicate(&self) -> Option<&str> {
+        Some(REGISTRY_HOMEPAGE)
+    }
 
     fn short_description(&self) -> &str {
         "Check that the field is annotated with a value for `registry:homepage`"
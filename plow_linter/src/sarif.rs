@@ -0,0 +1,73 @@
+//! Lint reports in the [SARIF](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) format,
+//! which code scanning tools and CI services understand.
+
+use crate::lint::{LintReport, Severity};
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// A SARIF log of a single run of the linter on the field at the path.
+///
+/// Successful lints are kept as results of the `pass` kind.
+pub fn sarif_log(reports: &[LintReport], field_path: &str) -> Value {
+    let mut rule_ids: Vec<&str> = vec![];
+    for report in reports {
+        if !rule_ids.contains(&report.lint.id()) {
+            rule_ids.push(report.lint.id());
+        }
+    }
+    let rules = rule_ids
+        .iter()
+        .map(|id| json!({ "id": id }))
+        .collect::<Vec<_>>();
+    let results = reports
+        .iter()
+        .map(|report| sarif_result(report, field_path))
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+            "columnKind": "unicodeCodePoints",
+        }]
+    })
+}
+
+fn sarif_result(report: &LintReport, field_path: &str) -> Value {
+    let (kind, level) = match report.severity {
+        Severity::Success => ("pass", "none"),
+        Severity::Warning => ("fail", "warning"),
+        Severity::Failure => ("fail", "error"),
+    };
+    let mut physical_location = json!({ "artifactLocation": { "uri": field_path } });
+    if let Some(span) = report.span {
+        physical_location["region"] = json!({
+            "startLine": span.line,
+            "startColumn": span.column,
+            "endLine": span.end_line,
+            "endColumn": span.end_column,
+        });
+    }
+    json!({
+        "ruleId": report.lint.id(),
+        "kind": kind,
+        "level": level,
+        "message": { "text": report.message },
+        "locations": [{ "physicalLocation": physical_location }],
+        "properties": {
+            "subject": report.subject,
+            "predicate": report.predicate,
+        },
+    })
+}
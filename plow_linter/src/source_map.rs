@@ -0,0 +1,292 @@
+//! Locates the annotations of a field in its source, the graph which lints run against does not know where they are written.
+
+use field33_rdftk_core_temporary_fork::model::statement::StatementRef;
+use harriet::{
+    Directive, Literal, Object, Statement, Subject, Triples, TurtleDocument, TurtleString, Verb,
+    IRI,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Where something is written in the source of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    /// Offset of the first byte in the source.
+    pub start: usize,
+    /// Offset of the byte after the last one in the source.
+    pub end: usize,
+    /// Line of the first character, starting from 1.
+    pub line: usize,
+    /// Column of the first character, counted in characters and starting from 1.
+    pub column: usize,
+    pub end_line: usize,
+    /// Column of the character after the last one.
+    pub end_column: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    annotations: Vec<AnnotationLocation>,
+}
+
+/// Where a triple is written in the source.
+#[derive(Debug)]
+struct AnnotationLocation {
+    subject: String,
+    predicate: String,
+    /// The IRI or the lexical form of the object, if it is one of those.
+    object: Option<String>,
+    subject_span: Span,
+    predicate_span: Span,
+    object_span: Option<Span>,
+}
+
+impl SourceMap {
+    /// Maps the statements of the document which was parsed from the source.
+    ///
+    /// Blank node subjects and `a` as predicate are not written out in the source so their annotations are not mapped.
+    pub fn new(source: &str, document: &TurtleDocument) -> Self {
+        let lines = Lines::new(source);
+        let mut base: Option<String> = None;
+        let mut prefixes = HashMap::new();
+        let mut annotations = vec![];
+
+        for statement in &document.statements {
+            match statement {
+                Statement::Directive(Directive::Base(directive)) => {
+                    base = Some(resolve(base.as_deref(), &directive.iri.iri));
+                }
+                Statement::Directive(Directive::SparqlBase(directive)) => {
+                    base = Some(resolve(base.as_deref(), &directive.iri.iri));
+                }
+                Statement::Directive(Directive::Prefix(directive)) => {
+                    prefixes.insert(
+                        directive.prefix.as_deref().unwrap_or_default().to_owned(),
+                        resolve(base.as_deref(), &directive.iri.iri),
+                    );
+                }
+                Statement::Directive(Directive::SparqlPrefix(directive)) => {
+                    prefixes.insert(
+                        directive.prefix.as_deref().unwrap_or_default().to_owned(),
+                        resolve(base.as_deref(), &directive.iri.iri),
+                    );
+                }
+                Statement::Triples(Triples::Labeled(_, Subject::IRI(subject), list)) => {
+                    let (subject, subject_span) = match (
+                        expand(subject, base.as_deref(), &prefixes),
+                        range_of_iri(source, subject),
+                    ) {
+                        (Some(iri), Some((start, end))) => (iri, lines.span(start, end)),
+                        _ => continue,
+                    };
+                    for (_, verb, objects, _) in &list.list {
+                        let predicate = match verb {
+                            Verb::IRI(predicate) => predicate,
+                            Verb::A => continue,
+                        };
+                        let (predicate_iri, predicate_span) = match (
+                            expand(predicate, base.as_deref(), &prefixes),
+                            range_of_iri(source, predicate),
+                        ) {
+                            (Some(iri), Some((start, end))) => (iri, lines.span(start, end)),
+                            _ => continue,
+                        };
+                        for (_, _, object) in &objects.list {
+                            let (object, object_range) = match object {
+                                Object::IRI(iri) => (
+                                    expand(iri, base.as_deref(), &prefixes),
+                                    range_of_iri(source, iri),
+                                ),
+                                Object::Literal(Literal::RDFLiteral(literal)) => {
+                                    let (string, quote_length) = match &literal.string {
+                                        TurtleString::StringLiteralQuote(quote) => {
+                                            (&quote.string, 1)
+                                        }
+                                        TurtleString::StringLiteralSingleQuote(quote) => {
+                                            (&quote.string, 1)
+                                        }
+                                        TurtleString::StringLiteralLongQuote(quote) => {
+                                            (&quote.string, 3)
+                                        }
+                                        TurtleString::StringLiteralLongSingleQuote(quote) => {
+                                            (&quote.string, 3)
+                                        }
+                                    };
+                                    // Including the quotes around it.
+                                    let range =
+                                        range_of(source, string).and_then(|(start, end)| {
+                                            Some((
+                                                start.checked_sub(quote_length)?,
+                                                end + quote_length,
+                                            ))
+                                        });
+                                    (Some(string.to_string()), range)
+                                }
+                                _ => (None, None),
+                            };
+                            annotations.push(AnnotationLocation {
+                                subject: subject.clone(),
+                                predicate: predicate_iri.clone(),
+                                object,
+                                subject_span,
+                                predicate_span,
+                                object_span: object_range
+                                    .map(|(start, end)| lines.span(start, end)),
+                            });
+                        }
+                    }
+                }
+                Statement::Triples(_) => {}
+            }
+        }
+
+        Self { annotations }
+    }
+
+    /// Where the predicate of the first annotation of the subject with the predicate is written.
+    pub fn annotation_span(&self, subject: &str, predicate: &str) -> Option<Span> {
+        self.annotations
+            .iter()
+            .find(|location| location.subject == subject && location.predicate == predicate)
+            .map(|location| location.predicate_span)
+    }
+
+    /// Where the subject is written first.
+    pub fn subject_span(&self, subject: &str) -> Option<Span> {
+        self.annotations
+            .iter()
+            .find(|location| location.subject == subject)
+            .map(|location| location.subject_span)
+    }
+
+    /// The subject of the annotation which the span is in.
+    pub fn subject_at(&self, span: Span) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|location| {
+                std::iter::once(location.subject_span)
+                    .chain(Some(location.predicate_span))
+                    .chain(location.object_span)
+                    .any(|location_span| {
+                        location_span.start <= span.start && span.end <= location_span.end
+                    })
+            })
+            .map(|location| location.subject.as_str())
+    }
+
+    /// Where the object of the statement is written.
+    ///
+    /// Falls back to the predicate for objects which can not be located, e.g. blank nodes or numbers.
+    pub fn span_of(&self, statement: &StatementRef) -> Option<Span> {
+        let subject = statement.subject().as_iri()?.to_string();
+        let predicate = statement.predicate().to_string();
+        let object = match (statement.object().as_iri(), statement.object().as_literal()) {
+            (Some(iri), _) => iri.to_string(),
+            (None, Some(literal)) => literal.lexical_form().clone(),
+            (None, None) => return self.annotation_span(&subject, &predicate),
+        };
+        self.annotations
+            .iter()
+            .find(|location| {
+                location.subject == subject
+                    && location.predicate == predicate
+                    && location.object.as_deref() == Some(object.as_str())
+            })
+            .map(|location| location.object_span.unwrap_or(location.predicate_span))
+            .or_else(|| self.annotation_span(&subject, &predicate))
+    }
+}
+
+/// Turns offsets in the source into lines and columns.
+struct Lines<'source> {
+    source: &'source str,
+    /// Offsets in the source which the lines start at.
+    starts: Vec<usize>,
+}
+
+impl<'source> Lines<'source> {
+    fn new(source: &'source str) -> Self {
+        Self {
+            source,
+            starts: std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+                .collect(),
+        }
+    }
+
+    fn line_and_column(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|start| *start <= offset);
+        let line_start = self
+            .starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
+        let column = self
+            .source
+            .get(line_start..offset)
+            .map_or(0, |text| text.chars().count());
+        (line, column + 1)
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        let (line, column) = self.line_and_column(start);
+        let (end_line, end_column) = self.line_and_column(end);
+        Span {
+            start,
+            end,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+/// Relative IRIs are appended to the base, which is enough for the IRIs used in fields.
+pub(crate) fn resolve(base: Option<&str>, iri: &str) -> String {
+    match base {
+        Some(base) if !iri.contains(':') => format!("{base}{iri}"),
+        _ => iri.to_owned(),
+    }
+}
+
+pub(crate) fn expand(
+    iri: &IRI,
+    base: Option<&str>,
+    prefixes: &HashMap<String, String>,
+) -> Option<String> {
+    match iri {
+        IRI::IRIReference(reference) => Some(resolve(base, &reference.iri)),
+        IRI::PrefixedName(name) => {
+            let namespace = prefixes.get(name.prefix.as_deref().unwrap_or_default())?;
+            Some(format!(
+                "{namespace}{}",
+                name.name.as_deref().unwrap_or_default()
+            ))
+        }
+    }
+}
+
+/// Where the IRI is written in the source, parts of it which were borrowed from the source while parsing point there.
+fn range_of_iri(source: &str, iri: &IRI) -> Option<(usize, usize)> {
+    match iri {
+        // Including the `<` and `>` around it.
+        IRI::IRIReference(reference) => {
+            let (start, end) = range_of(source, &reference.iri)?;
+            Some((start.checked_sub(1)?, end + 1))
+        }
+        // Including the prefix and the `:` in front of it.
+        IRI::PrefixedName(name) => {
+            let (start, end) = range_of(source, name.name.as_deref()?)?;
+            let prefix_length = name.prefix.as_deref().unwrap_or_default().len() + 1;
+            Some((start.checked_sub(prefix_length)?, end))
+        }
+    }
+}
+
+#[allow(clippy::as_conversions)]
+fn range_of(source: &str, text: &str) -> Option<(usize, usize)> {
+    let start = (text.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    let end = start + text.len();
+    (end <= source.len()).then_some((start, end))
+}
@@ -0,0 +1,203 @@
+use plow_linter::custom_lints::{CustomLint, Rule};
+use plow_linter::lint::{LintLevel, LintResult};
+use plow_linter::lints::{LintName, PlowLint};
+use plow_linter::Linter;
+
+const CUSTOM_LINTS_BASE: &str = concat!(
+    include_str!("data/default_ttl_header"),
+    r#"
+registry:packageName "@test/test" .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+
+:Person rdf:type owl:Class ;
+    skos:definition "A human being."@en .
+:lowercaseThing rdf:type owl:Class .
+:Employee rdfs:subClassOf :Person .
+"#
+);
+
+const OWL_CLASS: &str = "http://www.w3.org/2002/07/owl#Class";
+const SKOS_DEFINITION: &str = "http://www.w3.org/2004/02/skos/core#definition";
+
+fn definitions(rule: Rule) -> Rule {
+    Rule {
+        class: OWL_CLASS.to_owned(),
+        predicate: SKOS_DEFINITION.to_owned(),
+        ..rule
+    }
+}
+
+fn classes_have_definitions(message: Option<&str>) -> CustomLint {
+    CustomLint::new(
+        "classes_have_definitions",
+        "Every class has a `skos:definition`",
+        definitions(Rule {
+            min_count: Some(1),
+            ..Rule::default()
+        }),
+        message,
+    )
+    .unwrap()
+}
+
+fn run(document: &str, lint: CustomLint) -> LintResult {
+    let mut linter = Linter::try_from(document).unwrap();
+    let id = linter.add_lint_as_set(vec![Box::new(lint) as PlowLint], None);
+    let mut results = linter.run_lint_set(id);
+    assert!(results.len() <= 1);
+    results
+        .pop()
+        .unwrap_or_else(|| LintResult::Success("allowed".into()))
+}
+
+fn texts(result: &LintResult) -> Vec<&str> {
+    result
+        .messages()
+        .iter()
+        .map(|message| message.text.as_str())
+        .collect()
+}
+
+#[test]
+fn rules_fail_for_every_instance_which_breaks_them() {
+    let result = run(
+        CUSTOM_LINTS_BASE,
+        classes_have_definitions(Some("The class {subject} has no `skos:definition`.")),
+    );
+    assert!(result.is_failure());
+    assert_eq!(
+        texts(&result),
+        vec!["The class http://field33.com/ontologies/@test/test/lowercaseThing has no `skos:definition`."]
+    );
+    // Points at where the class is declared.
+    assert_eq!(result.messages()[0].span.map(|span| span.line), Some(16));
+
+    let result = run(CUSTOM_LINTS_BASE, classes_have_definitions(None));
+    assert_eq!(
+        texts(&result),
+        vec!["http://field33.com/ontologies/@test/test/lowercaseThing has 0 values for http://www.w3.org/2004/02/skos/core#definition, but at least 1 are required."]
+    );
+
+    // The rules of a class apply to the instances of its subclasses as well.
+    let document = format!("{CUSTOM_LINTS_BASE}:alice rdf:type :Employee .\n");
+    let lint = CustomLint::new(
+        "people_have_definitions",
+        "Every person has a `skos:definition`",
+        Rule {
+            class: "http://field33.com/ontologies/@test/test/Person".to_owned(),
+            ..definitions(Rule {
+                min_count: Some(1),
+                ..Rule::default()
+            })
+        },
+        Some("{subject} has no `skos:definition`."),
+    )
+    .unwrap();
+    assert_eq!(
+        texts(&run(&document, lint)),
+        vec!["http://field33.com/ontologies/@test/test/alice has no `skos:definition`."]
+    );
+}
+
+#[test]
+fn rules_check_the_datatypes_and_patterns_of_values() {
+    let lint = |rule: Rule| {
+        CustomLint::new("definitions", "Definitions", definitions(rule), None).unwrap()
+    };
+
+    let result = run(
+        CUSTOM_LINTS_BASE,
+        lint(Rule {
+            max_count: Some(1),
+            datatype: Some("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString".to_owned()),
+            pattern: Some("\\.$".to_owned()),
+            ..Rule::default()
+        }),
+    );
+    assert!(result.is_success());
+
+    let result = run(
+        CUSTOM_LINTS_BASE,
+        lint(Rule {
+            datatype: Some("http://www.w3.org/2001/XMLSchema#string".to_owned()),
+            pattern: Some("(?i)^an ".to_owned()),
+            ..Rule::default()
+        }),
+    );
+    assert!(result.is_failure());
+    assert_eq!(
+        texts(&result),
+        vec![
+            "The value A human being. of http://www.w3.org/2004/02/skos/core#definition of http://field33.com/ontologies/@test/test/Person does not have the datatype http://www.w3.org/2001/XMLSchema#string.",
+            "The value A human being. of http://www.w3.org/2004/02/skos/core#definition of http://field33.com/ontologies/@test/test/Person does not match the pattern `(?i)^an `.",
+        ]
+    );
+}
+
+#[test]
+fn custom_lints_are_allowed_and_leveled_by_their_names() {
+    let mut linter = Linter::try_from(CUSTOM_LINTS_BASE).unwrap();
+    linter.set_lint_level(
+        LintName::Custom("classes_have_definitions".to_owned()),
+        LintLevel::Warn,
+    );
+    let id = linter.add_lint_as_set(
+        vec![Box::new(classes_have_definitions(None)) as PlowLint],
+        None,
+    );
+    let results = linter.run_lint_set(id);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_warning());
+
+    let document = CUSTOM_LINTS_BASE.replace(
+        ":lowercaseThing rdf:type owl:Class .",
+        ":lowercaseThing rdf:type owl:Class ;\n    registry:allowLint \"classes_have_definitions\" .",
+    );
+    assert!(run(&document, classes_have_definitions(None)).is_success());
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    let lint = |name: &str, rule: Rule| CustomLint::new(name, "Custom", rule, None);
+    let checks_something = || Rule {
+        min_count: Some(1),
+        ..Rule::default()
+    };
+
+    assert!(lint("custom", definitions(checks_something())).is_ok());
+    assert!(lint("valid_rdfs_labels", definitions(checks_something())).is_err());
+    assert!(lint("two words", definitions(checks_something())).is_err());
+    // IRIs are written out in full, without the angle brackets of Turtle.
+    assert!(lint(
+        "custom",
+        Rule {
+            predicate: "<http://www.w3.org/2004/02/skos/core#definition>".to_owned(),
+            ..definitions(checks_something())
+        }
+    )
+    .is_err());
+    assert!(lint("custom", checks_something()).is_err());
+    assert!(lint("custom", definitions(Rule::default())).is_err());
+    assert!(lint(
+        "custom",
+        definitions(Rule {
+            min_count: Some(2),
+            max_count: Some(1),
+            ..Rule::default()
+        })
+    )
+    .is_err());
+
+    let err = lint(
+        "custom",
+        definitions(Rule {
+            pattern: Some("[A-Z".to_owned()),
+            ..Rule::default()
+        }),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("not a valid regular expression"),
+        "{err}"
+    );
+}
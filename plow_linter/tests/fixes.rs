@@ -0,0 +1,171 @@
+use harriet::TurtleDocument;
+use plow_linter::lint::{FixSuggestion, LintLevel};
+use plow_linter::lints::{
+    BaseMatchesRootPrefix, ContainsOWLPrefixes, ContainsRegistryPrefix, HasOntologyFormatVersion,
+    HasRegistryLicenseSPDX, LintName, PlowLint,
+};
+use plow_linter::Linter;
+
+fn fixed(document: &str, lints: fn() -> Vec<PlowLint>) -> String {
+    let mut linter = Linter::try_from(document).unwrap();
+    linter.add_lint_as_set(lints(), None);
+    let mut fixed_document = TurtleDocument::parse_full(document).unwrap();
+    for fix in linter.suggest_fixes() {
+        fix.apply(&mut fixed_document);
+    }
+    let fixed_document = fixed_document.to_string();
+
+    let mut linter = Linter::try_from(fixed_document.as_ref()).unwrap();
+    linter.add_lint_as_set(lints(), None);
+    assert!(linter
+        .run_all_lints()
+        .iter()
+        .all(|result| result.is_success()));
+    fixed_document
+}
+
+#[test]
+fn missing_prefixes_are_added_after_the_directives() {
+    let document = r#"@prefix : <http://field33.com/ontologies/@test/test/> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@base <http://field33.com/ontologies/@test/test/> .
+
+# The ontology itself.
+<http://field33.com/ontologies/@test/test/> rdf:type owl:Ontology .
+"#;
+    let lints = || {
+        vec![
+            Box::new(ContainsOWLPrefixes::default()) as PlowLint,
+            Box::new(ContainsRegistryPrefix::default()) as PlowLint,
+        ]
+    };
+    assert_eq!(
+        fixed(document, lints),
+        r#"@prefix : <http://field33.com/ontologies/@test/test/> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@base <http://field33.com/ontologies/@test/test/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xml: <http://www.w3.org/XML/1998/namespace> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix registry: <http://field33.com/ontologies/REGISTRY/> .
+
+# The ontology itself.
+<http://field33.com/ontologies/@test/test/> rdf:type owl:Ontology .
+"#
+    );
+}
+
+#[test]
+fn base_is_set_to_the_root_prefix() {
+    let lints = || vec![Box::new(BaseMatchesRootPrefix::default()) as PlowLint];
+    let document = r#"@prefix : <http://field33.com/ontologies/@test/test/> .
+@base <http://field33.com/ontologies/@test/tset> .
+@base <http://field33.com/ontologies/@test/test/> .
+"#;
+    assert_eq!(
+        fixed(document, lints),
+        r#"@prefix : <http://field33.com/ontologies/@test/test/> .
+@base <http://field33.com/ontologies/@test/test/> .
+"#
+    );
+
+    let document = "@prefix : <http://field33.com/ontologies/@test/test/> .\n";
+    assert_eq!(
+        fixed(document, lints),
+        r#"@prefix : <http://field33.com/ontologies/@test/test/> .
+@base <http://field33.com/ontologies/@test/test/> .
+"#
+    );
+}
+
+#[test]
+fn ontology_format_version_is_set_on_the_ontology() {
+    let lints = || vec![Box::new(HasOntologyFormatVersion::default()) as PlowLint];
+    let document = concat!(
+        include_str!("data/default_ttl_header"),
+        r#"
+    registry:packageName "@test/test" ;
+    # The version of the field.
+    registry:packageVersion "0.1.0" .
+"#
+    );
+    let expected_document = concat!(
+        include_str!("data/default_ttl_header"),
+        r#"
+    registry:packageName "@test/test" ;
+    # The version of the field.
+    registry:packageVersion "0.1.0" ;
+    registry:ontologyFormatVersion "v1" .
+"#
+    );
+    assert_eq!(fixed(document, lints), expected_document);
+
+    let document = concat!(
+        include_str!("data/default_ttl_header"),
+        r#"
+    registry:ontologyFormatVersion "v2" ;
+    registry:packageName "@test/test" .
+"#
+    );
+    let expected_document = concat!(
+        include_str!("data/default_ttl_header"),
+        r#"
+    registry:ontologyFormatVersion "v1" ;
+    registry:packageName "@test/test" .
+"#
+    );
+    assert_eq!(fixed(document, lints), expected_document);
+}
+
+#[test]
+fn spdx_ids_are_normalized() {
+    let lints = || vec![Box::new(HasRegistryLicenseSPDX::default()) as PlowLint];
+    for (license, normalized_license) in [
+        ("mit", "MIT"),
+        ("mit or apache-2.0", "MIT OR Apache-2.0"),
+        ("MIT/Apache-2.0", "MIT OR Apache-2.0"),
+        (
+            "( gpl-3.0-or-later with classpath-exception-2.0 )",
+            "(GPL-3.0-or-later WITH Classpath-exception-2.0)",
+        ),
+    ] {
+        let document = format!(
+            "{} registry:licenseSPDX \"{license}\" .",
+            include_str!("data/default_ttl_header")
+        );
+        let expected_document = format!(
+            "{} registry:licenseSPDX \"{normalized_license}\" .",
+            include_str!("data/default_ttl_header")
+        );
+        let mut linter = Linter::try_from(document.as_ref()).unwrap();
+        linter.add_lint_as_set(lints(), None);
+        let mut fixed_document = TurtleDocument::parse_full(&document).unwrap();
+        for fix in linter.suggest_fixes() {
+            fix.apply(&mut fixed_document);
+        }
+        assert_eq!(fixed_document.to_string(), expected_document);
+    }
+
+    let document = format!(
+        "{} registry:licenseSPDX \"not a license\" .",
+        include_str!("data/default_ttl_header")
+    );
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    linter.add_lint_as_set(lints(), None);
+    assert!(linter.suggest_fixes().is_empty());
+}
+
+#[test]
+fn allowed_lints_suggest_no_fixes() {
+    let document = "@prefix : <http://field33.com/ontologies/@test/test/> .\n";
+    let mut linter = Linter::try_from(document).unwrap();
+    linter.add_lint_as_set(
+        vec![Box::new(BaseMatchesRootPrefix::default()) as PlowLint],
+        None,
+    );
+    assert_eq!(linter.suggest_fixes().len(), 1);
+    linter.set_lint_level(LintName::BaseMatchesRootPrefix, LintLevel::Allow);
+    assert!(linter.suggest_fixes().is_empty());
+}
@@ -34,6 +34,10 @@ fn lint_registry_package_version_exists_and_valid() {
         format!("{REGISTRY_PACKAGE_VERSION_BASE} registry:packageVersion \"=1.0.0-beta.1\" .");
     let ttl_document_with_package_version_j =
         format!("{REGISTRY_PACKAGE_VERSION_BASE} registry:packageVersion \"=1.0.0-alpha.1+001\" .");
+    let ttl_document_with_package_version_k =
+        format!("{REGISTRY_PACKAGE_VERSION_BASE} registry:packageVersion \"1.0.0-beta.1\" .");
+    let ttl_document_with_package_version_l =
+        format!("{REGISTRY_PACKAGE_VERSION_BASE} registry:packageVersion \"1.0.0-alpha.1+001\" .");
     let mut linter_a = Linter::try_from(ttl_document_with_package_version_a.as_ref()).unwrap();
     linter_a.add_lint_as_set(
         vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
@@ -84,6 +88,16 @@ fn lint_registry_package_version_exists_and_valid() {
         vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
         None,
     );
+    let mut linter_k = Linter::try_from(ttl_document_with_package_version_k.as_ref()).unwrap();
+    linter_k.add_lint_as_set(
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+        None,
+    );
+    let mut linter_l = Linter::try_from(ttl_document_with_package_version_l.as_ref()).unwrap();
+    linter_l.add_lint_as_set(
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+        None,
+    );
 
     let result_a = linter_a.run_all_lints();
     let result_b = linter_b.run_all_lints();
@@ -95,6 +109,8 @@ fn lint_registry_package_version_exists_and_valid() {
     let result_h = linter_h.run_all_lints();
     let result_i = linter_i.run_all_lints();
     let result_j = linter_j.run_all_lints();
+    let result_k = linter_k.run_all_lints();
+    let result_l = linter_l.run_all_lints();
     assert!(result_a.first().unwrap().is_success());
     assert!(result_b.first().unwrap().is_failure());
     assert!(result_c.first().unwrap().is_failure());
@@ -105,6 +121,8 @@ fn lint_registry_package_version_exists_and_valid() {
     assert!(result_h.first().unwrap().is_failure());
     assert!(result_i.first().unwrap().is_failure());
     assert!(result_j.first().unwrap().is_failure());
+    assert!(result_k.first().unwrap().is_success());
+    assert!(result_l.first().unwrap().is_success());
 }
 
 #[test]
@@ -0,0 +1,103 @@
+use plow_linter::lint::{LintLevel, Severity};
+use plow_linter::lints::{HasRegistryPackageVersion, LintName, PlowLint, ValidRdfsLabels};
+use plow_linter::Linter;
+
+const LINT_LEVELS_BASE: &str = concat!(
+    include_str!("data/default_ttl_header"),
+    r#"
+registry:ontologyFormatVersion "v1" ;
+registry:packageName "@test/test" ;
+registry:canonicalPrefix "test" ;
+"#
+);
+
+const UNLABELED_SUBJECTS: &str = r#"
+<http://field33.com/ontologies/@test/test/some-iri>
+rdf:type owl:Class .
+<http://field33.com/ontologies/@test/test/other-iri>
+rdf:type owl:Class ."#;
+
+fn severities(linter: &mut Linter, lints: Vec<PlowLint>) -> Vec<Severity> {
+    let id = linter.add_lint_as_set(lints, None);
+    linter
+        .run_lint_set(id)
+        .iter()
+        .map(|result| result.severity())
+        .collect()
+}
+
+#[test]
+fn lint_names_are_parsed_from_their_ids() {
+    for name in LintName::ALL {
+        assert_eq!(name.id().parse::<LintName>().unwrap(), name);
+    }
+    assert!("valid_rdfs_label".parse::<LintName>().is_err());
+    assert_eq!("deny".parse::<LintLevel>().unwrap(), LintLevel::Deny);
+}
+
+#[test]
+fn lint_levels_change_how_results_are_reported() {
+    let document = format!("{LINT_LEVELS_BASE} registry:packageVersion \"2.x\" .");
+
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    linter.set_lint_level(LintName::HasRegistryPackageVersion, LintLevel::Warn);
+    let results = severities(
+        &mut linter,
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+    );
+    assert_eq!(results, vec![Severity::Warning]);
+
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    linter.set_lint_level(LintName::HasRegistryPackageVersion, LintLevel::Allow);
+    let results = severities(
+        &mut linter,
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+    );
+    assert!(results.is_empty());
+
+    let document =
+        format!("{LINT_LEVELS_BASE} registry:packageVersion \"2.3.4\" .{UNLABELED_SUBJECTS}");
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    linter.set_lint_level(LintName::ValidRdfsLabels, LintLevel::Deny);
+    let results = severities(
+        &mut linter,
+        vec![Box::new(ValidRdfsLabels::default()) as PlowLint],
+    );
+    assert_eq!(results, vec![Severity::Failure]);
+}
+
+#[test]
+fn lints_are_allowed_in_the_field() {
+    let document = format!(
+        "{LINT_LEVELS_BASE} registry:packageVersion \"2.x\" ;\n registry:allowLint \"has_registry_package_version\" ."
+    );
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    let results = severities(
+        &mut linter,
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+    );
+    assert!(results.is_empty());
+
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    linter.enforce_all_lints();
+    let results = severities(
+        &mut linter,
+        vec![Box::new(HasRegistryPackageVersion::default()) as PlowLint],
+    );
+    assert_eq!(results, vec![Severity::Failure]);
+}
+
+#[test]
+fn lints_are_allowed_on_a_subject() {
+    let document = format!(
+        "{LINT_LEVELS_BASE} registry:packageVersion \"2.3.4\" .{UNLABELED_SUBJECTS}
+<http://field33.com/ontologies/@test/test/other-iri>
+registry:allowLint \"valid_rdfs_labels\" ."
+    );
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    let id = linter.add_lint_as_set(vec![Box::new(ValidRdfsLabels::default()) as PlowLint], None);
+    let reports = linter.run_lint_set_with_reports(id);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].severity, Severity::Warning);
+    assert!(reports[0].message.contains("some-iri"));
+}
@@ -0,0 +1,128 @@
+use plow_linter::lint::Severity;
+use plow_linter::lints::{
+    HasRegistryPackageVersion, LintName, PlowLint, ValidRegistryDependencies,
+};
+use plow_linter::sarif::sarif_log;
+use plow_linter::Linter;
+
+const REGISTRY_REPORTS_BASE: &str = concat!(
+    include_str!("data/default_ttl_header"),
+    r#"
+registry:ontologyFormatVersion "v1" ;
+registry:packageName "@test/test" ;
+registry:canonicalPrefix "test" ;
+registry:dependency "@some/dependency =0.1.0" ;
+"#
+);
+
+fn line_containing(document: &str, text: &str) -> usize {
+    document
+        .lines()
+        .position(|line| line.contains(text))
+        .unwrap()
+        + 1
+}
+
+#[test]
+fn reports_refer_to_the_lint_and_the_annotation() {
+    let document = format!("{REGISTRY_REPORTS_BASE} registry:packageVersion \"2.x\" .");
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    let id = linter.add_lint_as_set(
+        vec![
+            Box::new(HasRegistryPackageVersion::default()) as PlowLint,
+            Box::new(ValidRegistryDependencies::default()) as PlowLint,
+        ],
+        None,
+    );
+
+    let reports = linter.run_lint_set_with_reports(id);
+    assert_eq!(reports.len(), 2);
+
+    let version = &reports[0];
+    assert_eq!(version.lint, LintName::HasRegistryPackageVersion);
+    assert_eq!(version.severity, Severity::Failure);
+    assert_eq!(
+        version.subject.as_deref(),
+        Some("http://field33.com/ontologies/@test/test/")
+    );
+    assert_eq!(
+        version.predicate.as_deref(),
+        Some("http://field33.com/ontologies/REGISTRY/packageVersion")
+    );
+    let span = version.span.unwrap();
+    assert_eq!(
+        span.line,
+        line_containing(&document, "registry:packageVersion")
+    );
+    assert_eq!(&document[span.start..span.end], "\"2.x\"");
+    assert_eq!(span.column, 26);
+    assert_eq!(span.end_column, 31);
+
+    let dependencies = &reports[1];
+    assert_eq!(dependencies.lint, LintName::ValidRegistryDependencies);
+    assert_eq!(dependencies.severity, Severity::Success);
+    let span = dependencies.span.unwrap();
+    assert_eq!(span.line, line_containing(&document, "registry:dependency"));
+    assert_eq!(&document[span.start..span.end], "registry:dependency");
+
+    let json = serde_json::to_value(&reports).unwrap();
+    assert_eq!(json[0]["lint"], "has_registry_package_version");
+    assert_eq!(json[0]["severity"], "failure");
+    assert_eq!(json[0]["span"]["column"], 26);
+}
+
+#[test]
+fn messages_point_at_the_invalid_value() {
+    let document = format!(
+        "{REGISTRY_REPORTS_BASE} registry:dependency \"some/other =1.0.0\" ;\n registry:packageVersion \"0.1.0\" ."
+    );
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    let id = linter.add_lint_as_set(
+        vec![Box::new(ValidRegistryDependencies::default()) as PlowLint],
+        None,
+    );
+
+    let reports = linter.run_lint_set_with_reports(id);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].severity, Severity::Failure);
+    let span = reports[0].span.unwrap();
+    assert_eq!(&document[span.start..span.end], "\"some/other =1.0.0\"");
+    assert_eq!(span.line, line_containing(&document, "some/other"));
+}
+
+#[test]
+fn reports_as_sarif() {
+    let document = format!("{REGISTRY_REPORTS_BASE} registry:packageVersion \"2.x\" .");
+    let mut linter = Linter::try_from(document.as_ref()).unwrap();
+    let id = linter.add_lint_as_set(
+        vec![
+            Box::new(HasRegistryPackageVersion::default()) as PlowLint,
+            Box::new(ValidRegistryDependencies::default()) as PlowLint,
+        ],
+        None,
+    );
+
+    let log = sarif_log(&linter.run_lint_set_with_reports(id), "test.ttl");
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(
+        run["tool"]["driver"]["rules"][0]["id"],
+        "has_registry_package_version"
+    );
+
+    let failure = &run["results"][0];
+    assert_eq!(failure["ruleId"], "has_registry_package_version");
+    assert_eq!(failure["level"], "error");
+    let location = &failure["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.ttl");
+    assert_eq!(
+        location["region"]["startLine"],
+        line_containing(&document, "registry:packageVersion")
+    );
+    assert_eq!(location["region"]["startColumn"], 26);
+    assert_eq!(location["region"]["endColumn"], 31);
+
+    let success = &run["results"][1];
+    assert_eq!(success["kind"], "pass");
+    assert_eq!(success["level"], "none");
+}
@@ -644,3 +644,40 @@ fn lint_registry_package_dependencies_with_version_pairs() {
     assert!(result_h_5.first().unwrap().is_failure());
     assert!(result_h_6.first().unwrap().is_failure());
 }
+
+#[test]
+fn lint_registry_package_dependencies_qualified_with_a_registry() {
+    let ttl_document_with_registry_dependency_a = format!(
+        "{REGISTRY_DEPENDENCY_BASE} registry:dependency \"internal:@some/dependency =0.1.0\", \"@another/dependency ^3\" ."
+    );
+    let ttl_document_with_registry_dependency_b = format!(
+        "{REGISTRY_DEPENDENCY_BASE} registry:dependency \"inter.nal:@some/dependency =0.1.0\" ."
+    );
+    let ttl_document_with_registry_dependency_c = format!(
+        "{REGISTRY_DEPENDENCY_BASE} registry:dependency \"internal:some/dependency =0.1.0\" ."
+    );
+
+    let mut linter_a = Linter::try_from(ttl_document_with_registry_dependency_a.as_ref()).unwrap();
+    linter_a.add_lint_as_set(
+        vec![Box::new(ValidRegistryDependencies::default()) as PlowLint],
+        None,
+    );
+    let mut linter_b = Linter::try_from(ttl_document_with_registry_dependency_b.as_ref()).unwrap();
+    linter_b.add_lint_as_set(
+        vec![Box::new(ValidRegistryDependencies::default()) as PlowLint],
+        None,
+    );
+    let mut linter_c = Linter::try_from(ttl_document_with_registry_dependency_c.as_ref()).unwrap();
+    linter_c.add_lint_as_set(
+        vec![Box::new(ValidRegistryDependencies::default()) as PlowLint],
+        None,
+    );
+
+    let result_a = linter_a.run_all_lints();
+    let result_b = linter_b.run_all_lints();
+    let result_c = linter_c.run_all_lints();
+
+    assert!(result_a.first().unwrap().is_success());
+    assert!(result_b.first().unwrap().is_failure());
+    assert!(result_c.first().unwrap().is_failure());
+}
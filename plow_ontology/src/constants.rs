@@ -18,6 +18,7 @@ pub const REGISTRY_DEPENDENCY: &str = "http://field33.com/ontologies/REGISTRY/de
 
 pub const REGISTRY_CANONICAL_PREFIX: &str =
     "http://field33.com/ontologies/REGISTRY/canonicalPrefix";
+pub const REGISTRY_ALLOW_LINT: &str = "http://field33.com/ontologies/REGISTRY/allowLint";
 
 pub const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
 pub const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
@@ -0,0 +1,98 @@
+//! Compares looking up the versions of a single package in a large on-disk registry,
+//! by reading the whole index like it used to be done and by going to the index file of the package.
+//!
+//! Run with `cargo bench -p plow_package_management`.
+#![allow(clippy::restriction)]
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use plow_package_management::registry::{
+    index::{IndexContents, IndexedPackageVersion},
+    on_disk::OnDiskRegistry,
+    Registry,
+};
+use tempdir::TempDir;
+
+const NAMESPACES: usize = 20;
+const PACKAGES_PER_NAMESPACE: usize = 100;
+const VERSIONS_PER_PACKAGE: usize = 3;
+
+/// Writes the index of a registry with `NAMESPACES * PACKAGES_PER_NAMESPACE` packages.
+fn generate_registry() -> TempDir {
+    let temp_dir = TempDir::new("on_disk_registry_bench").unwrap();
+    for namespace in 0..NAMESPACES {
+        let namespace_dir = temp_dir
+            .path()
+            .join("index")
+            .join(format!("@namespace{namespace}"));
+        std::fs::create_dir_all(&namespace_dir).unwrap();
+        for package in 0..PACKAGES_PER_NAMESPACE {
+            let contents = IndexContents {
+                versions: (0..VERSIONS_PER_PACKAGE)
+                    .map(|version| IndexedPackageVersion {
+                        name: format!("@namespace{namespace}/package{package}"),
+                        version: format!("1.{version}.0"),
+                        cksum: format!("{namespace:x}{package:x}{version:x}"),
+                        ontology_iri: None,
+                        deps: vec![],
+                        keywords: vec![],
+                        categories: vec![],
+                        yanked: false,
+                    })
+                    .collect(),
+            };
+            std::fs::write(
+                namespace_dir.join(format!("package{package}.json")),
+                serde_json::to_string_pretty(&contents).unwrap(),
+            )
+            .unwrap();
+        }
+    }
+    temp_dir
+}
+
+fn single_package_lookup(c: &mut Criterion) {
+    let temp_dir = generate_registry();
+    let package_name = "@namespace7/package42";
+
+    let mut group = c.benchmark_group("versions of a single package");
+    group.sample_size(10);
+    group.bench_function("reading the whole index", |b| {
+        b.iter_batched(
+            || OnDiskRegistry::new(temp_dir.path()).unwrap(),
+            |registry| {
+                registry
+                    .list_all_package_versions()
+                    .unwrap()
+                    .into_iter()
+                    .filter(|version| version.package_name == package_name)
+                    .count()
+            },
+            BatchSize::PerIteration,
+        );
+    });
+    group.bench_function("reading its index file", |b| {
+        b.iter_batched(
+            || OnDiskRegistry::new(temp_dir.path()).unwrap(),
+            |registry| {
+                registry
+                    .all_available_versions_of_a_package(package_name.to_owned())
+                    .unwrap()
+                    .len()
+            },
+            BatchSize::PerIteration,
+        );
+    });
+    group.bench_function("cached index file", |b| {
+        let registry = OnDiskRegistry::new(temp_dir.path()).unwrap();
+        b.iter(|| {
+            registry
+                .all_available_versions_of_a_package(package_name.to_owned())
+                .unwrap()
+                .len()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_package_lookup);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+use sha2::{Digest, Sha256};
+
+/// Computes the SHA-256 checksum of the contents of a field, lowercase hex encoded.
+///
+/// This is the `cksum` which indexes and lock files identify artifacts with.
+pub fn compute(contents: &[u8]) -> String {
+    let mut sha256 = Sha256::new();
+    sha256.update(contents);
+    format!("{:x}", sha256.finalize())
+}
+
+/// Checks whether the contents of a field match the expected checksum, ignoring the case of its hex encoding.
+pub fn matches(contents: &[u8], expected: &str) -> bool {
+    compute(contents).eq_ignore_ascii_case(expected)
+}
@@ -61,13 +61,14 @@
 
 pub(crate) const ORGANIZATION_NAME: &str = "@root/root";
 pub(crate) const ORGANIZATION_VERSION: &str = "0.0.0";
-pub(crate) const ORGANIZATION: &str = "@root/root 0.0.0";
 
+pub mod checksum;
 pub mod edit;
 pub mod lock;
 pub mod metadata;
 pub mod package;
 pub mod registry;
 pub mod resolve;
+pub mod source;
 pub mod version;
 pub mod workspace;
@@ -1,21 +1,57 @@
 #![allow(clippy::use_self)]
 
-use crate::package::OrganizationToResolveFor;
+use crate::package::{OrganizationToResolveFor, PackageToUpdate};
 use anyhow::bail;
 use camino::{Utf8Path, Utf8PathBuf};
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use thiserror::Error;
 
 use crate::{
-    package::{PackageSet, PackageVersion},
+    package::{PackageSet, PackageVersion, PackageVersionWithRegistryMetadata},
     registry::Registry,
-    resolve::Resolver,
+    resolve::{Dependency, Resolver, VersionRequestResolver},
+    source::PackageSource,
+    version::SemanticVersion,
 };
 
 /// Common name between all lock files for ontologies.
 pub const LOCK_FILE_NAME: &str = "Plow.lock";
 
+/// Errors related to lock files.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum LockFileError {
+    /// The lock file and the registry disagree on the checksum of a package.
+    #[error("The lock file has the checksum {locked} for {package_name} {version} but the registry has {registry}.")]
+    ChecksumMismatch {
+        package_name: String,
+        version: String,
+        locked: String,
+        registry: String,
+    },
+    /// A lock file is required but there is none.
+    #[error("A lock file is required but there is no {LOCK_FILE_NAME} in the workspace.")]
+    NotFound,
+    /// Resolving again would change what is locked.
+    #[error("The lock file needs to be updated but it is required to stay as it is. Packages which are not locked: {}", .0.join(", "))]
+    WouldChange(Vec<String>),
+}
+
+/// How an existing lock file is followed when locking.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LockOptions<'update> {
+    /// Whether the versions in an existing lock file stay pinned.
+    pub respect_existing_lock_file: bool,
+    /// A package which is resolved again together with its transitive dependencies.
+    pub package_to_update: Option<&'update PackageToUpdate>,
+    /// Whether incompatible major versions of a package may be locked side by side.
+    pub allow_multiple_major_versions: bool,
+    /// Whether the lock file is required to exist and to stay as it is.
+    pub locked: bool,
+}
+
 /// A runtime representation of a lock file.
 #[derive(Debug, Clone, Default)]
 pub struct LockFile {
@@ -34,8 +70,8 @@ impl LockFile {
     /// name = "@namespace/name"
     /// # A complete bare version.
     /// version = "0.2.15"
-    /// # Currently left empty
-    /// source = ""
+    /// # Where the package comes from, see `PackageSource`.
+    /// source = "git+https://github.com/field33/plow-registry-index.git"
     /// cksum = "24606928a235e73cdef55a0c909719cadd72fce573e5713d58cb2952d8f5794c"
     /// # Set of dependencies with corresponding versions.
     /// dependencies = [
@@ -72,6 +108,24 @@ impl LockFile {
         Ok(toml::from_str::<PackagesInLockFile>(&lock_file_contents)?)
     }
 
+    /// Whether writing the given packages would change the lock file in the workspace root.
+    ///
+    /// The order of the packages does not matter. Without a lock file, any package is a change.
+    pub fn would_change(
+        workspace_root: Option<Utf8PathBuf>,
+        package_set: &[PackageInLockFile],
+    ) -> Result<bool, anyhow::Error> {
+        let lock_file_path = match Self::previous_lock_file_exists(workspace_root) {
+            Some(lock_file_path) => lock_file_path,
+            None => return Ok(!package_set.is_empty()),
+        };
+        let mut previous = Self::deserialize_lock_file(&lock_file_path)?.packages;
+        let mut next = package_set.to_vec();
+        previous.sort();
+        next.sort();
+        Ok(previous != next)
+    }
+
     pub fn previous_lock_file_exists(workspace_root: Option<Utf8PathBuf>) -> Option<Utf8PathBuf> {
         workspace_root.and_then(|workspace_root| {
             let lock_file_path_in_workspace_root = workspace_root.join(LOCK_FILE_NAME);
@@ -83,48 +137,83 @@ impl LockFile {
     }
 
     /// Starts locking operation, resolves dependencies and write the lock file.
+    ///
+    /// When a package to update is given with an existing lock file respected,
+    /// only that package and its transitive dependencies are resolved again.
+    ///
+    /// Incompatible major versions of a package may be locked side by side if it is allowed.
+    ///
+    /// When locked, the existing lock file is always respected and it is an error
+    /// if it is missing or if the resolution needs a package which is not in it.
     pub fn lock_with_registry(
         package_to_resolve: OrganizationToResolveFor,
         registry: &dyn Registry,
         workspace_root: Option<Utf8PathBuf>,
-        respect_existing_lock_file: bool,
+        options: LockOptions,
     ) -> Result<Self, anyhow::Error> {
+        let LockOptions {
+            respect_existing_lock_file,
+            package_to_update,
+            allow_multiple_major_versions,
+            locked,
+        } = options;
+
         // TODO: Either this or another entry point will be expanded to support db based locks in the future.
 
-        let (resolved_dependencies, _previously_locked_path) =
-            if let Some(lock_file_path) = Self::previous_lock_file_exists(workspace_root) {
-                if respect_existing_lock_file {
-                    // With existing lock file input
-                    let packages = Self::deserialize_lock_file(&lock_file_path)?
-                        .packages
-                        .iter()
-                        .cloned()
-                        // TODO:
-                        // Currently filter the local resolutions out.
-                        // Will be addressed soon.
-                        .filter(|p| !p.root)
-                        .collect::<Vec<_>>();
-
-                    (
-                        Into::<crate::resolve::VersionRequestResolver>::into(registry)
-                            .resolve_dependencies(package_to_resolve, Some(&packages))?,
-                        Some(lock_file_path),
-                    )
-                } else {
-                    (
-                        Into::<crate::resolve::VersionRequestResolver>::into(registry)
-                            .resolve_dependencies(package_to_resolve, None)?,
-                        None,
-                    )
+        let resolver = VersionRequestResolver::from(registry)
+            .allow_multiple_major_versions(allow_multiple_major_versions);
+
+        let previous_lock_file_path = Self::previous_lock_file_exists(workspace_root);
+        if locked && previous_lock_file_path.is_none() {
+            return Err(LockFileError::NotFound.into());
+        }
+
+        let previously_locked = match previous_lock_file_path {
+            Some(ref lock_file_path) if respect_existing_lock_file || locked => {
+                // With existing lock file input
+                let packages = Self::deserialize_lock_file(lock_file_path)?
+                    .packages
+                    .iter()
+                    .cloned()
+                    // TODO:
+                    // Currently filter the local resolutions out.
+                    // Will be addressed soon.
+                    .filter(|p| !p.root)
+                    // Entries from another source than the configured registry are resolved again.
+                    .filter(|p| p.source_matches(registry))
+                    .collect::<Vec<_>>();
+
+                for package in &packages {
+                    package.verify_cksum(registry)?;
                 }
-            } else {
-                // No lockfile input
-                (
-                    Into::<crate::resolve::VersionRequestResolver>::into(registry)
-                        .resolve_dependencies(package_to_resolve, None)?,
-                    None,
-                )
-            };
+
+                Some(packages)
+            }
+            // No lockfile input
+            _ => None,
+        };
+
+        let resolved_dependencies = resolver.resolve_dependencies(
+            package_to_resolve,
+            previously_locked.as_deref(),
+            package_to_update,
+        )?;
+
+        if locked {
+            let not_locked = resolved_dependencies
+                .packages
+                .iter()
+                .filter(|package| {
+                    !previously_locked.iter().flatten().any(|locked| {
+                        locked.name == package.package_name && locked.version == package.version
+                    })
+                })
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            if !not_locked.is_empty() {
+                return Err(LockFileError::WouldChange(not_locked).into());
+            }
+        }
 
         // TODO: Do not write in this function!
         // let resolved_dependencies_with_metadata: Vec<PackageVersionWithRegistryMetadata> =
@@ -180,17 +269,146 @@ impl PackagesInLockFile {
 }
 
 /// A package in the form to be serialized to or to be deserialized to or from the lock file.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PackageInLockFile {
     pub root: bool,
     pub name: String,
     pub version: String,
-    pub source: Option<String>,
+    pub source: Option<PackageSource>,
+    /// The ontology IRI of the major version of the package, see [`crate::package::major_version_ontology_iri`].
     pub ontology_iri: Option<String>,
     pub cksum: Option<String>,
     pub dependencies: Vec<String>,
 }
 
+impl PackageInLockFile {
+    /// Checks that the registry has the same checksum for the locked package as the lock file.
+    ///
+    /// Packages which are not in the registry or have no checksum in one of them are skipped.
+    pub fn verify_cksum(&self, registry: &dyn Registry) -> Result<(), LockFileError> {
+        let registry_cksum = registry
+            .get_package_version_metadata(&self.into())
+            .ok()
+            .and_then(|metadata| metadata.cksum);
+        match (&self.cksum, registry_cksum) {
+            (Some(locked), Some(registry)) if !locked.eq_ignore_ascii_case(&registry) => {
+                Err(LockFileError::ChecksumMismatch {
+                    package_name: self.name.clone(),
+                    version: self.version.clone(),
+                    locked: locked.clone(),
+                    registry,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the locked package still comes from the same source in the given registry.
+    ///
+    /// Entries without a source, from lock files before sources were recorded, are trusted.
+    pub fn source_matches(&self, registry: &dyn Registry) -> bool {
+        let locked_source = match self.source {
+            Some(ref source) => source,
+            None => return true,
+        };
+        registry
+            .get_package_version_metadata(&self.into())
+            .map_or(false, |metadata| {
+                metadata
+                    .source
+                    .map_or(true, |registry_source| &registry_source == locked_source)
+            })
+    }
+
+    /// Describes the locked package as a registry would, with its dependencies pinned to the locked versions.
+    ///
+    /// This lets a registry serve what is locked without reaching the index, e.g. with `--frozen`.
+    pub fn to_registry_metadata(
+        &self,
+        lock_file: &[PackageInLockFile],
+    ) -> Result<PackageVersionWithRegistryMetadata, anyhow::Error> {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .map(|entry| {
+                let (name, version) = entry
+                    .split_once(' ')
+                    .map_or((entry.as_str(), None), |(name, version)| {
+                        (name, Some(version))
+                    });
+                let locked = lock_file
+                    .iter()
+                    .find(|package| {
+                        !package.root
+                            && package.name == name
+                            && version.map_or(true, |version| package.version == version)
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "The dependency {entry} of {} {} is not in the lock file.",
+                            self.name,
+                            self.version
+                        )
+                    })?;
+                Dependency::<SemanticVersion>::try_from(locked)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let version = SemanticVersion::try_from(self.version.as_str())?;
+        // The lock file records the ontology IRI of the major version, the registry the one of the package.
+        let major_version_suffix = format!("{}/", version.compatible_major_version());
+        let ontology_iri = self.ontology_iri.as_deref().map(|ontology_iri| {
+            ontology_iri
+                .strip_suffix(&major_version_suffix)
+                .unwrap_or(ontology_iri)
+                .to_owned()
+        });
+
+        Ok(PackageVersionWithRegistryMetadata {
+            package_name: self.name.clone(),
+            version,
+            ontology_iri,
+            dependencies,
+            cksum: self.cksum.clone(),
+            private: matches!(self.source, Some(PackageSource::PrivateIndex(_))),
+            source: self.source.clone(),
+            // The lock file does not record what a package is found by.
+            keywords: vec![],
+            categories: vec![],
+            yanked: false,
+        })
+    }
+
+    /// Lists dependencies of a package as they are written to the lock file.
+    ///
+    /// A dependency is written as `name version` if multiple versions of it are locked, otherwise just by its name.
+    pub fn dependency_entries(
+        dependencies: &[Dependency<SemanticVersion>],
+        locked: &PackageSet,
+    ) -> Vec<String> {
+        dependencies
+            .iter()
+            .map(|dependency| {
+                let versions = locked
+                    .packages
+                    .iter()
+                    .filter(|package| package.package_name == dependency.full_name)
+                    .collect::<Vec<_>>();
+                if versions.len() < 2 {
+                    return dependency.full_name.clone();
+                }
+                versions
+                    .into_iter()
+                    .find(|package| {
+                        SemanticVersion::try_from(package.version.as_str())
+                            .map_or(false, |version| dependency.version_range.contains(&version))
+                    })
+                    .map_or_else(|| dependency.full_name.clone(), ToString::to_string)
+            })
+            .collect()
+    }
+}
+
 // Only one way conversion is allowed, for convenience.
 #[allow(clippy::from_over_into)]
 impl Into<PackageVersion> for &PackageInLockFile {
@@ -201,21 +419,3 @@ impl Into<PackageVersion> for &PackageInLockFile {
         }
     }
 }
-
-// impl From<PackageVersionWithRegistryMetadata> for PackageInLockFile {
-//     fn from(package: PackageVersionWithRegistryMetadata) -> Self {
-//         Self {
-//             name: package.package_name,
-//             version: package.version.to_string(),
-//             // TODO: For example, source = "registry+https://github.com/rust-lang/crates.io-index" ??
-//             source: None,
-//             ontology_iri: package.ontology_iri,
-//             cksum: package.cksum,
-//             dependencies: package
-//                 .dependencies
-//                 .iter()
-//                 .map(std::string::ToString::to_string)
-//                 .collect_vec(),
-//         }
-//     }
-// }
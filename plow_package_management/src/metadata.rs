@@ -6,8 +6,8 @@ use field33_rdftk_iri_temporary_fork::IRI as RDFTK_IRI;
 use harriet::{Directive, Statement, TurtleDocument};
 use plow_graphify::document_to_graph;
 use plow_ontology::constants::{
-    REGISTRY_CANONICAL_PREFIX, REGISTRY_DEPENDENCY, REGISTRY_ONTOLOGY_FORMAT_VERSION,
-    REGISTRY_PACKAGE_NAME, REGISTRY_PACKAGE_VERSION,
+    REGISTRY_CANONICAL_PREFIX, REGISTRY_CATEGORY, REGISTRY_DEPENDENCY, REGISTRY_KEYWORD,
+    REGISTRY_ONTOLOGY_FORMAT_VERSION, REGISTRY_PACKAGE_NAME, REGISTRY_PACKAGE_VERSION,
 };
 use serde::Serialize;
 use std::borrow::Cow;
@@ -46,6 +46,10 @@ pub struct OntologyMetadata {
     pub dependencies: Vec<Dependency<SemanticVersion>>,
     pub package_name: String,
     pub package_version: SemanticVersion,
+    /// Values of `registry:keyword`, sorted.
+    pub keywords: Vec<String>,
+    /// Values of `registry:category`, sorted.
+    pub categories: Vec<String>,
 }
 
 impl OntologyMetadata {
@@ -83,9 +87,11 @@ impl OntologyMetadata {
         Ok(literal.lexical_form().as_str().to_owned())
     }
 
-    fn get_dependency_strings(
+    /// All values of an annotation which may be given more than once, sorted.
+    fn get_stringy_ontology_annotations(
         rdf_graph: &GraphRef,
         root_prefix: &str,
+        annotation_property_iri: &str,
     ) -> Result<Vec<String>, anyhow::Error> {
         let rdf_factory = field33_rdftk_core_temporary_fork::simple::statement::statement_factory();
         let rdf_graph_borrow = rdf_graph.borrow();
@@ -98,11 +104,11 @@ impl OntologyMetadata {
                 statement.subject()
                     == &rdf_factory.named_subject(RDFTK_IRI::from_str(root_prefix).unwrap().into())
                     && statement.predicate()
-                        == &RDFTK_IRI::from_str(REGISTRY_DEPENDENCY).unwrap().into()
+                        == &RDFTK_IRI::from_str(annotation_property_iri).unwrap().into()
             })
             .collect::<HashSet<_>>();
 
-        let dependency_literals = annotations
+        let mut literals = annotations
             .into_iter()
             .map(|annotation| {
                 let literal = annotation
@@ -113,8 +119,9 @@ impl OntologyMetadata {
                 Ok(literal.lexical_form().as_str().to_owned())
             })
             .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        literals.sort();
 
-        Ok(dependency_literals)
+        Ok(literals)
     }
 
     fn get_ontology_format_version(
@@ -157,19 +164,16 @@ impl OntologyMetadata {
             REGISTRY_PACKAGE_VERSION,
         )?;
 
-        // We require that the version predicates which are fed to the resolver are either bare or exact but always complete.
-        // This function ensures that this is the case.
-        if let Ok(semver) = SemanticVersion::try_from(&literal_value) {
-            let bare_and_complete = literal_value.replace('.', "").chars().all(char::is_numeric)
-                && (literal_value.matches('.').count() == 2
-                    && literal_value.split('.').count() == 3);
-
-            if bare_and_complete {
-                return Ok(semver);
+        // We require that the version predicates which are fed to the resolver are bare and complete.
+        // Pre-release identifiers and build metadata are allowed, e.g. `1.2.0-rc.1+001`.
+        // Partial versions are only parsed again to tell them apart in the error.
+        match semver::Version::parse(&literal_value) {
+            Ok(version) => Ok(version.into()),
+            Err(_) if SemanticVersion::try_from(&literal_value).is_ok() => {
+                bail!("Expected bare and complete version, got {literal_value}",)
             }
-            bail!("Expected bare and complete version, got {literal_value}",);
+            Err(_) => bail!("Invalid version predicate {literal_value}",),
         }
-        bail!("Invalid version predicate {literal_value}",);
     }
 }
 
@@ -199,10 +203,11 @@ impl TryFrom<&TurtleDocument<'_>> for OntologyMetadata {
 
         let ontology_format_version = Self::get_ontology_format_version(&rdf_graph, root_prefix)?;
 
-        let dependencies = Self::get_dependency_strings(&rdf_graph.clone(), root_prefix)?
-            .iter()
-            .map(|dep_string| Dependency::<SemanticVersion>::try_from(dep_string.as_str()))
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let dependencies =
+            Self::get_stringy_ontology_annotations(&rdf_graph, root_prefix, REGISTRY_DEPENDENCY)?
+                .iter()
+                .map(|dep_string| Dependency::<SemanticVersion>::try_from(dep_string.as_str()))
+                .collect::<Result<Vec<_>, anyhow::Error>>()?;
 
         Ok(Self {
             root_prefix: root_prefix.to_string(),
@@ -211,6 +216,16 @@ impl TryFrom<&TurtleDocument<'_>> for OntologyMetadata {
             dependencies,
             package_name: Self::get_package_name(&rdf_graph, root_prefix)?,
             package_version: Self::get_package_version(&rdf_graph, root_prefix)?,
+            keywords: Self::get_stringy_ontology_annotations(
+                &rdf_graph,
+                root_prefix,
+                REGISTRY_KEYWORD,
+            )?,
+            categories: Self::get_stringy_ontology_annotations(
+                &rdf_graph,
+                root_prefix,
+                REGISTRY_CATEGORY,
+            )?,
         })
     }
 }
@@ -1,4 +1,5 @@
 use crate::resolve::Dependency;
+use crate::source::PackageSource;
 use crate::version::SemanticVersion;
 use crate::{metadata::OntologyMetadata, ORGANIZATION_NAME};
 use camino::Utf8PathBuf;
@@ -67,6 +68,35 @@ pub struct PackageVersionWithRegistryMetadata {
     pub dependencies: Vec<Dependency<SemanticVersion>>,
     pub cksum: Option<String>,
     pub private: bool,
+    /// Where the package comes from, if the registry knows it.
+    pub source: Option<PackageSource>,
+    /// Values of `registry:keyword` of the package version.
+    pub keywords: Vec<String>,
+    /// Values of `registry:category` of the package version.
+    pub categories: Vec<String>,
+    /// Whether the version was yanked from the registry, registries which do not support yanking never yank.
+    pub yanked: bool,
+}
+
+/// The ontology IRI of a major version of a package, which is how it is imported and listed in the lock file and the Protege catalog.
+///
+/// Each major version has its own IRI so that multiple major versions of a package can be imported side by side,
+/// e.g. `http://field33.com/ontologies/@namespace/name/2/` for `2.1.0` of `http://field33.com/ontologies/@namespace/name/`.
+pub fn major_version_ontology_iri(ontology_iri: &str, version: &SemanticVersion) -> String {
+    format!(
+        "{}/{}/",
+        ontology_iri.trim_end_matches('/'),
+        version.compatible_major_version()
+    )
+}
+
+impl PackageVersionWithRegistryMetadata {
+    /// The ontology IRI of the major version of the package, see [`major_version_ontology_iri`].
+    pub fn major_version_ontology_iri(&self) -> Option<String> {
+        self.ontology_iri
+            .as_deref()
+            .map(|ontology_iri| major_version_ontology_iri(ontology_iri, &self.version))
+    }
 }
 
 impl PartialEq for PackageVersionWithRegistryMetadata {
@@ -174,3 +204,13 @@ impl From<Vec<Dependency<SemanticVersion>>> for OrganizationToResolveFor {
         }
     }
 }
+
+/// A package which is requested to be updated selectively, e.g. with `plow update @namespace/name`.
+///
+/// Every other package in the lock file stays pinned to its locked version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageToUpdate {
+    pub package_name: String,
+    /// Update to exactly this version instead of the highest valid one.
+    pub precise_version: Option<SemanticVersion>,
+}
@@ -1,27 +1,212 @@
+pub mod http;
 pub mod in_memory;
 pub mod index;
+pub mod merged;
 pub mod on_disk;
 pub mod on_disk_git;
+#[cfg(feature = "server")]
+pub mod server;
 
-use crate::package::{PackageVersion, PackageVersionWithRegistryMetadata};
+use crate::{
+    package::{PackageVersion, PackageVersionWithRegistryMetadata},
+    version::SemanticVersion,
+};
+
+/// Which packages to list from a registry, and which page of them.
+///
+/// Text, keywords and categories are compared case insensitively with the latest version of a package.
+#[derive(Debug, Clone, Default)]
+pub struct PackageFilter {
+    /// Text which is part of the name, a keyword or a category of the package.
+    pub text: Option<String>,
+    /// Keywords which all have to be given in `registry:keyword`.
+    pub keywords: Vec<String>,
+    /// Categories which all have to be given in `registry:category`.
+    pub categories: Vec<String>,
+    /// Whether packages of which all versions are yanked are listed.
+    pub include_yanked: bool,
+    /// Number of matching packages to skip.
+    pub offset: usize,
+    /// Maximum number of matching packages to list, all of them if there is none.
+    pub limit: Option<usize>,
+}
+
+impl PackageFilter {
+    /// Filters by text in the name, keywords or categories of packages.
+    pub fn search(text: &str) -> Self {
+        Self {
+            text: Some(text.to_owned()),
+            ..Self::default()
+        }
+    }
+
+    /// Lists the given page, pages are counted from zero.
+    #[must_use]
+    pub const fn page(mut self, page: usize, page_size: usize) -> Self {
+        self.offset = page.saturating_mul(page_size);
+        self.limit = Some(page_size);
+        self
+    }
+
+    /// Whether a package with the given latest version matches, regardless of the page.
+    pub fn matches(&self, listing: &PackageListing) -> bool {
+        let latest = &listing.latest;
+        if latest.yanked && !self.include_yanked {
+            return false;
+        }
+        let contains = |values: &[String], wanted: &str| {
+            values
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(wanted))
+        };
+        if !self
+            .keywords
+            .iter()
+            .all(|keyword| contains(&latest.keywords, keyword))
+            || !self
+                .categories
+                .iter()
+                .all(|category| contains(&latest.categories, category))
+        {
+            return false;
+        }
+        self.text.as_ref().map_or(true, |text| {
+            let text = text.to_lowercase();
+            std::iter::once(&latest.package_name)
+                .chain(&latest.keywords)
+                .chain(&latest.categories)
+                .any(|value| value.to_lowercase().contains(&text))
+        })
+    }
+}
+
+/// A package in a registry with all of its versions.
+#[derive(Debug, Clone)]
+pub struct PackageListing {
+    pub package_name: String,
+    /// The latest version which is not yanked, the latest yanked one if all of them are.
+    pub latest: PackageVersionWithRegistryMetadata,
+    /// All versions, the latest first.
+    pub versions: Vec<SemanticVersion>,
+}
+
+impl PackageListing {
+    /// Lists the given versions of a package, none if there are no versions.
+    pub fn new(mut versions: Vec<PackageVersionWithRegistryMetadata>) -> Option<Self> {
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+        let latest = versions
+            .iter()
+            .find(|version| !version.yanked)
+            .or_else(|| versions.first())?
+            .clone();
+        Some(Self {
+            package_name: latest.package_name.clone(),
+            latest,
+            versions: versions
+                .into_iter()
+                .map(|version| version.version)
+                .collect(),
+        })
+    }
+}
 
 /// A registry fulfills all the duties of an Index (= providing lightweight metadata about packages,
 /// like e.g. version, dependencies) and an Artifact store (= retrieval of packages).
 pub trait Registry {
-    // TODO: Write docs to trait methods
+    /// Names of all packages in the registry, e.g. `@namespace/name`.
+    fn package_names(&self) -> Result<Vec<String>, anyhow::Error>;
+
+    /// All versions of a package, none if the registry does not have the package.
+    ///
+    /// Fails if the registry has the package but can not tell its versions, e.g. because its index is corrupt.
     fn all_available_versions_of_a_package(
         &self,
         package_namespace_and_name: String,
-    ) -> Vec<PackageVersionWithRegistryMetadata>;
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, anyhow::Error>;
+
+    /// Metadata of a single version of a package, fails if the registry does not have it.
     fn get_package_version_metadata(
         &self,
         package_version: &PackageVersion,
     ) -> Result<PackageVersionWithRegistryMetadata, anyhow::Error>;
 
+    /// Contents of a single version of a package.
     fn retrieve_package(&self, package: &PackageVersion) -> Result<Vec<u8>, anyhow::Error>;
 
+    /// Adds a package version to the registry from the contents of its field.
     fn submit_package(
         &self,
         file_contents: &str,
     ) -> Result<PackageVersionWithRegistryMetadata, anyhow::Error>;
+
+    /// Yanks a package version, or takes a yank back.
+    ///
+    /// Yanked versions stay available for lock files which have them but are not resolved otherwise.
+    fn yank(&self, package_version: &PackageVersion, _yanked: bool) -> Result<(), anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "{package_version:?} can not be yanked, the registry does not support yanking."
+        ))
+    }
+
+    /// Whether a package version was yanked, fails if the registry does not have it.
+    fn is_yanked(&self, package_version: &PackageVersion) -> Result<bool, anyhow::Error> {
+        Ok(self.get_package_version_metadata(package_version)?.yanked)
+    }
+
+    /// Lists the packages which match the filter, ordered by name.
+    ///
+    /// The versions of packages are only looked up while iterating.
+    fn list_packages<'reg>(
+        &'reg self,
+        filter: &PackageFilter,
+    ) -> Result<Box<dyn Iterator<Item = Result<PackageListing, anyhow::Error>> + 'reg>, anyhow::Error>
+    {
+        let mut package_names = self.package_names()?;
+        package_names.sort();
+        package_names.dedup();
+        let (offset, limit) = (filter.offset, filter.limit.unwrap_or(usize::MAX));
+        let filter = filter.clone();
+        Ok(Box::new(
+            package_names
+                .into_iter()
+                .filter_map(move |package_name| {
+                    match self.all_available_versions_of_a_package(package_name) {
+                        Ok(versions) => PackageListing::new(versions)
+                            .filter(|listing| filter.matches(listing))
+                            .map(Ok),
+                        Err(err) => Some(Err(err)),
+                    }
+                })
+                .skip(offset)
+                .take(limit),
+        ))
+    }
+
+    /// Packages of which the name, a keyword or a category contains the text.
+    fn search(&self, text: &str) -> Result<Vec<PackageListing>, anyhow::Error> {
+        self.list_packages(&PackageFilter::search(text))?.collect()
+    }
+
+    /// All available versions of a package in one of the named registries which this registry is composed of.
+    ///
+    /// A registry which is not composed of named registries has none of them.
+    fn all_available_versions_of_a_package_in_registry(
+        &self,
+        registry_name: &str,
+        package_namespace_and_name: String,
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "There is no registry named {registry_name} to retrieve {package_namespace_and_name} from."
+        ))
+    }
+    /// Metadata of a package version in one of the named registries which this registry is composed of.
+    fn get_package_version_metadata_in_registry(
+        &self,
+        registry_name: &str,
+        package_version: &PackageVersion,
+    ) -> Result<PackageVersionWithRegistryMetadata, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "There is no registry named {registry_name} to retrieve {package_version:?} from."
+        ))
+    }
 }
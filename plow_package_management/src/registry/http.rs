@@ -0,0 +1,111 @@
+#[cfg(feature = "http-client")]
+mod client;
+
+#[cfg(feature = "http-client")]
+pub use client::{HttpRegistry, HttpRegistryError};
+
+use crate::{
+    package::PackageVersionWithRegistryMetadata, registry::index::IndexedPackageVersion,
+    resolve::Dependency, source::PackageSource, version::SemanticVersion,
+};
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{convert::TryFrom, str::FromStr};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DifferenceQuery {
+    pub existing_local_field_hashes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyRequirement {
+    pub name: String,
+    pub req: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateIndex {
+    pub name: String,
+    pub version: String,
+    pub cksum: String,
+    pub ontology_iri: Option<String>,
+    pub deps: Vec<DependencyRequirement>,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+impl PrivateIndex {
+    /// The metadata of the private package, which comes from the private index of the given registry.
+    pub fn to_registry_metadata(
+        &self,
+        registry_url: &str,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        let dependencies = self
+            .deps
+            .iter()
+            .map(|dep| {
+                Dependency::<SemanticVersion>::try_from(
+                    format!("{} {}", dep.name, dep.req).as_str(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PackageVersionWithRegistryMetadata {
+            package_name: self.name.clone(),
+            version: SemanticVersion::from_str(&self.version)?,
+            ontology_iri: self.ontology_iri.clone(),
+            dependencies,
+            cksum: Some(self.cksum.clone()),
+            private: true,
+            source: Some(PackageSource::PrivateIndex(registry_url.to_owned())),
+            // The private index does not have them.
+            keywords: vec![],
+            categories: vec![],
+            yanked: self.yanked,
+        })
+    }
+}
+
+impl TryFrom<PackageVersionWithRegistryMetadata> for PrivateIndex {
+    type Error = Error;
+
+    fn try_from(metadata: PackageVersionWithRegistryMetadata) -> Result<Self, Self::Error> {
+        let indexed = IndexedPackageVersion::try_from(metadata)?;
+        Ok(Self {
+            name: indexed.name,
+            version: indexed.version,
+            cksum: indexed.cksum,
+            ontology_iri: indexed.ontology_iri,
+            deps: indexed
+                .deps
+                .into_iter()
+                .map(|dep| DependencyRequirement {
+                    name: dep.name,
+                    req: dep.req,
+                })
+                .collect(),
+            yanked: indexed.yanked,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateIndexes {
+    /// Entries which are new or changed since the field hashes in the query.
+    pub indexes: Vec<PrivateIndex>,
+    /// Field hashes from the query of entries which were revoked or unpublished.
+    #[serde(default)]
+    pub removed_field_hashes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateIndexResponse {
+    pub status: String,
+    pub data: PrivateIndexes,
+}
+
+/// The hash which the registry identifies a field version with, the SHA256 hash of `<namespace>/<fieldname> <version>`.
+pub fn field_hash(name: &str, version: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{name} {version}")))
+}
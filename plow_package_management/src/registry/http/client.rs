@@ -0,0 +1,436 @@
+use super::{field_hash, DifferenceQuery, PrivateIndexResponse, PrivateIndexes};
+use crate::{
+    checksum,
+    metadata::OntologyMetadata,
+    package::{PackageVersion, PackageVersionWithRegistryMetadata},
+    registry::Registry,
+    source::PackageSource,
+};
+
+use anyhow::{anyhow, Error};
+use harriet::TurtleDocument;
+use reqwest::{
+    blocking::{multipart, Client, RequestBuilder, Response},
+    StatusCode,
+};
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom};
+use thiserror::Error;
+
+/// Errors of requests to the v1 HTTP API of a registry.
+#[derive(Error, Debug)]
+pub enum HttpRegistryError {
+    #[error("The request to {url} failed. Error: {reason}")]
+    RequestFailed { url: String, reason: String },
+    #[error("Unauthorized to request {url}, a valid API token is needed.")]
+    Unauthorized { url: String },
+    #[error("{0} was not found in the registry.")]
+    NotFound(String),
+    #[error("The request to {url} failed with status code: {status}")]
+    UnexpectedStatus { url: String, status: u16 },
+    #[error("The response to {url} is invalid. Error: {reason}")]
+    InvalidResponse { url: String, reason: String },
+    #[error("The registry rejected the request. {0}")]
+    Rejected(String),
+    #[error("The contents of {package} do not match the checksum {expected}, they have {actual}.")]
+    ChecksumMismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// A registry which is reached through the v1 HTTP API, like `https://api.plow.pm`.
+///
+/// The packages it provides are the ones in its private index, which is synced with [`HttpRegistry::sync_private_index`],
+/// and the ones which are inserted from other indexes, like the public index.
+#[derive(Debug)]
+pub struct HttpRegistry {
+    url: String,
+    token: Option<String>,
+    client: Client,
+    /// Map of package versions to their metadata (= index).
+    packages_metadata: RefCell<HashMap<PackageVersion, PackageVersionWithRegistryMetadata>>,
+    /// Whether submitted fields are public.
+    public: bool,
+    /// The organization which owns submitted fields, the user owns them otherwise.
+    owner_organization: Option<String>,
+    /// Whether submissions are only checked by the registry but not persisted.
+    dry_run: bool,
+}
+
+impl HttpRegistry {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_owned(),
+            token: None,
+            client: Client::new(),
+            packages_metadata: RefCell::new(HashMap::default()),
+            public: true,
+            owner_organization: None,
+            dry_run: false,
+        }
+    }
+
+    /// Authenticates requests with an API token.
+    #[must_use]
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// Submits fields privately instead of publicly.
+    #[must_use]
+    pub const fn submit_privately(mut self, private: bool) -> Self {
+        self.public = !private;
+        self
+    }
+
+    /// Submits fields under the ownership of an organization.
+    #[must_use]
+    pub fn submit_as_organization(mut self, organization_id: &str) -> Self {
+        self.owner_organization = Some(organization_id.to_owned());
+        self
+    }
+
+    /// Only lets the registry check submissions without persisting them.
+    #[must_use]
+    pub const fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Makes a package from another index available, e.g. from the public index of the registry.
+    pub fn insert(&self, metadata: PackageVersionWithRegistryMetadata) {
+        let package_version = PackageVersion {
+            package_name: metadata.package_name.clone(),
+            version: metadata.version.to_string(),
+        };
+        self.packages_metadata
+            .borrow_mut()
+            .insert(package_version, metadata);
+    }
+
+    /// Retrieves the changes of the private index since the state which the given field hashes describe.
+    pub fn private_index_changes(
+        &self,
+        existing_local_field_hashes: Vec<String>,
+    ) -> Result<PrivateIndexes, HttpRegistryError> {
+        let url = format!("{}/v1/index/private/sync", self.url);
+        let query = DifferenceQuery {
+            existing_local_field_hashes,
+        };
+        let response = Self::send(self.authorized(self.client.post(&url)).json(&query), &url)?;
+        response
+            .json::<PrivateIndexResponse>()
+            .map(|response| response.data)
+            .map_err(|err| HttpRegistryError::InvalidResponse {
+                url,
+                reason: err.to_string(),
+            })
+    }
+
+    /// Syncs the private index of the registry, only what changed since the last sync is retrieved.
+    pub fn sync_private_index(&self) -> Result<(), HttpRegistryError> {
+        let existing_local_field_hashes = self
+            .packages_metadata
+            .borrow()
+            .values()
+            .filter(|metadata| metadata.private)
+            .map(|metadata| field_hash(&metadata.package_name, &metadata.version.to_string()))
+            .collect();
+        let PrivateIndexes {
+            indexes,
+            removed_field_hashes,
+        } = self.private_index_changes(existing_local_field_hashes)?;
+
+        let mut packages_metadata = self.packages_metadata.borrow_mut();
+        packages_metadata.retain(|package_version, metadata| {
+            !metadata.private
+                || !removed_field_hashes.contains(&field_hash(
+                    &package_version.package_name,
+                    &package_version.version,
+                ))
+        });
+        for index in indexes {
+            let metadata = index.to_registry_metadata(&self.url).map_err(|err| {
+                HttpRegistryError::InvalidResponse {
+                    url: self.url.clone(),
+                    reason: err.to_string(),
+                }
+            })?;
+            packages_metadata.insert(
+                PackageVersion {
+                    package_name: index.name,
+                    version: index.version,
+                },
+                metadata,
+            );
+        }
+        Ok(())
+    }
+
+    /// Retrieves a short lived url to download the contents of a package from.
+    pub fn signed_url(
+        &self,
+        metadata: &PackageVersionWithRegistryMetadata,
+    ) -> Result<String, HttpRegistryError> {
+        let url = format!(
+            "{}/v1/artifact/signed-url-by-field-hash/{}",
+            self.url,
+            field_hash(&metadata.package_name, &metadata.version.to_string())
+        );
+        let mut request = self.client.get(&url);
+        // Only private fields need authentication.
+        if metadata.private {
+            request = self.authorized(request);
+        }
+        let response = Self::send(request, &url)
+            .map_err(|err| match err {
+                HttpRegistryError::NotFound(_) => HttpRegistryError::NotFound(metadata.to_string()),
+                err => err,
+            })?
+            .json::<serde_json::Value>()
+            .map_err(|err| HttpRegistryError::InvalidResponse {
+                url: url.clone(),
+                reason: err.to_string(),
+            })?;
+        response
+            .get("data")
+            .and_then(|data| data.get("url"))
+            .and_then(serde_json::Value::as_str)
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| HttpRegistryError::InvalidResponse {
+                url,
+                reason: "There is no download link in it.".to_owned(),
+            })
+    }
+
+    /// Downloads the contents of a package, without checking them against its checksum.
+    pub fn download(
+        &self,
+        metadata: &PackageVersionWithRegistryMetadata,
+    ) -> Result<Vec<u8>, HttpRegistryError> {
+        let signed_url = self.signed_url(metadata)?;
+        Self::send(self.client.get(&signed_url), &signed_url)?
+            .bytes()
+            .map(|bytes| bytes.to_vec())
+            .map_err(|err| HttpRegistryError::InvalidResponse {
+                url: signed_url,
+                reason: err.to_string(),
+            })
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.token {
+            Some(ref token) => {
+                request.header(reqwest::header::AUTHORIZATION, format!("Basic {token}"))
+            }
+            None => request,
+        }
+    }
+
+    /// Sends a request, responses which are not successful are errors.
+    fn send(request: RequestBuilder, url: &str) -> Result<Response, HttpRegistryError> {
+        let response = request
+            .send()
+            .map_err(|err| HttpRegistryError::RequestFailed {
+                url: url.to_owned(),
+                reason: err.to_string(),
+            })?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::UNAUTHORIZED => Err(HttpRegistryError::Unauthorized {
+                url: url.to_owned(),
+            }),
+            StatusCode::NOT_FOUND => Err(HttpRegistryError::NotFound(url.to_owned())),
+            status => Err(HttpRegistryError::UnexpectedStatus {
+                url: url.to_owned(),
+                status: status.as_u16(),
+            }),
+        }
+    }
+
+    /// Submits a field and reads the reason from a response which is not successful.
+    fn send_submission(&self, name: &str, file_contents: &str) -> Result<(), HttpRegistryError> {
+        let mut url = format!("{}/v1/field/submit", self.url);
+        if self.dry_run {
+            url.push_str("?dry-run=true");
+        }
+        let part = multipart::Part::text(file_contents.to_owned())
+            .file_name(format!("{name}.ttl"))
+            .mime_str("text/turtle")
+            .map_err(|err| HttpRegistryError::RequestFailed {
+                url: url.clone(),
+                reason: err.to_string(),
+            })?;
+        let mut form = multipart::Form::new().text("public", self.public.to_string());
+        if let Some(ref owner_organization) = self.owner_organization {
+            form = form.text("owner_plo_id", owner_organization.clone());
+        }
+        let form = form.part("field", part);
+
+        Self::send_jsend(self.authorized(self.client.post(&url)).multipart(form), url)
+    }
+
+    /// Sends a request and reads the reason from a response which is not successful.
+    ///
+    /// Responses follow the [`JSend`](https://github.com/omniti-labs/jsend) spec.
+    fn send_jsend(request: RequestBuilder, url: String) -> Result<(), HttpRegistryError> {
+        let response = request
+            .send()
+            .map_err(|err| HttpRegistryError::RequestFailed {
+                url: url.clone(),
+                reason: err.to_string(),
+            })?;
+        let status = response.status();
+        let body = response.json::<serde_json::Value>().map_err(|err| {
+            HttpRegistryError::InvalidResponse {
+                url: url.clone(),
+                reason: err.to_string(),
+            }
+        })?;
+
+        match body.get("status").and_then(serde_json::Value::as_str) {
+            Some("success") if status.is_success() => Ok(()),
+            Some("fail") => Err(HttpRegistryError::Rejected(match body.get("data") {
+                Some(serde_json::Value::String(reason)) => reason.clone(),
+                Some(data) => data
+                    .get("failures")
+                    .and_then(serde_json::Value::as_array)
+                    .map_or_else(
+                        || data.to_string(),
+                        |failures| {
+                            failures
+                                .iter()
+                                .filter_map(serde_json::Value::as_str)
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        },
+                    ),
+                None => String::new(),
+            })),
+            Some("error") => Err(HttpRegistryError::Rejected(
+                body.get("message")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned(),
+            )),
+            _ if status == StatusCode::UNAUTHORIZED => Err(HttpRegistryError::Unauthorized { url }),
+            _ => Err(HttpRegistryError::UnexpectedStatus {
+                url,
+                status: status.as_u16(),
+            }),
+        }
+    }
+}
+
+impl Registry for HttpRegistry {
+    fn package_names(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .packages_metadata
+            .borrow()
+            .keys()
+            .map(|package| package.package_name.clone())
+            .collect())
+    }
+
+    fn all_available_versions_of_a_package(
+        &self,
+        package_namespace_and_name: String,
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        Ok(self
+            .packages_metadata
+            .borrow()
+            .iter()
+            .filter(|(package, _)| package.package_name == package_namespace_and_name)
+            .map(|(_, metadata)| metadata.clone())
+            .collect())
+    }
+
+    fn get_package_version_metadata(
+        &self,
+        package_version: &PackageVersion,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        self.packages_metadata
+            .borrow()
+            .get(package_version)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unable to retrieve package registry metadata for package {:?}",
+                    &package_version
+                )
+            })
+    }
+
+    /// Yanks through `DELETE /v1/field/<field hash>/yank`, `PUT /v1/field/<field hash>/unyank` takes it back.
+    fn yank(&self, package_version: &PackageVersion, yanked: bool) -> Result<(), Error> {
+        let hash = field_hash(&package_version.package_name, &package_version.version);
+        let (request, url) = if yanked {
+            let url = format!("{}/v1/field/{hash}/yank", self.url);
+            (self.client.delete(&url), url)
+        } else {
+            let url = format!("{}/v1/field/{hash}/unyank", self.url);
+            (self.client.put(&url), url)
+        };
+        Self::send_jsend(self.authorized(request), url)?;
+
+        if let Some(metadata) = self.packages_metadata.borrow_mut().get_mut(package_version) {
+            metadata.yanked = yanked;
+        }
+        Ok(())
+    }
+
+    /// Downloads the contents of a package, which have to match its checksum.
+    fn retrieve_package(&self, package: &PackageVersion) -> Result<Vec<u8>, Error> {
+        let metadata = self.get_package_version_metadata(package)?;
+        let contents = self.download(&metadata)?;
+        if let Some(ref cksum) = metadata.cksum {
+            if !checksum::matches(&contents, cksum) {
+                return Err(HttpRegistryError::ChecksumMismatch {
+                    package: metadata.to_string(),
+                    expected: cksum.clone(),
+                    actual: checksum::compute(&contents),
+                }
+                .into());
+            }
+        }
+        Ok(contents)
+    }
+
+    /// Submits a field, unless it is a dry run it is available from the registry afterwards.
+    fn submit_package(
+        &self,
+        file_contents: &str,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        let document = TurtleDocument::parse_full(file_contents)
+            .map_err(|_| anyhow!("Couldn't parse turtle document."))?;
+        let metadata = OntologyMetadata::try_from(&document)
+            .map_err(|_| anyhow!("Couldn't get ontology metadata from turtle document."))?;
+
+        self.send_submission(&metadata.package_name, file_contents)?;
+
+        let private = !self.public;
+        let submitted = PackageVersionWithRegistryMetadata {
+            package_name: metadata.package_name,
+            version: metadata.package_version,
+            ontology_iri: Some(metadata.root_prefix),
+            dependencies: metadata.dependencies,
+            cksum: Some(checksum::compute(file_contents.as_bytes())),
+            private,
+            // Public fields are served from the public index, which the registry does not know about.
+            source: private.then(|| PackageSource::PrivateIndex(self.url.clone())),
+            keywords: metadata.keywords,
+            categories: metadata.categories,
+            yanked: false,
+        };
+        if !self.dry_run {
+            self.insert(submitted.clone());
+        }
+        Ok(submitted)
+    }
+}
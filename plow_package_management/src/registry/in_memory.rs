@@ -29,15 +29,24 @@ impl InMemoryRegistry {
 }
 
 impl Registry for InMemoryRegistry {
+    fn package_names(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .packages_metadata
+            .keys()
+            .map(|package| package.package_name.clone())
+            .collect())
+    }
+
     fn all_available_versions_of_a_package(
         &self,
         package_namespace_and_name: String,
-    ) -> Vec<PackageVersionWithRegistryMetadata> {
-        self.packages_metadata
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        Ok(self
+            .packages_metadata
             .iter()
             .filter(|(package, _)| package.package_name == package_namespace_and_name)
             .map(|(_, metadata)| metadata.clone())
-            .collect()
+            .collect())
     }
     fn get_package_version_metadata(
         &self,
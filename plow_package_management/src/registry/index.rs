@@ -18,6 +18,13 @@ pub struct IndexedPackageVersion {
     pub cksum: String,
     pub ontology_iri: Option<String>,
     pub deps: Vec<IndexedPackageDependency>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Yanked versions are only resolved if they are locked already.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub yanked: bool,
 }
 
 impl PartialEq for IndexedPackageVersion {
@@ -42,6 +49,11 @@ impl TryFrom<IndexedPackageVersion> for PackageVersionWithRegistryMetadata {
                     dependencies,
                     cksum: Some(indexed_package_version.cksum),
                     private: false,
+                    // Indexes do not record where they are served from.
+                    source: None,
+                    keywords: indexed_package_version.keywords,
+                    categories: indexed_package_version.categories,
+                    yanked: indexed_package_version.yanked,
                 })
             })
     }
@@ -63,12 +75,16 @@ impl TryFrom<PackageVersionWithRegistryMetadata> for IndexedPackageVersion {
             version: original.version.to_string(),
             ontology_iri: original.ontology_iri,
             deps: original.dependencies.into_iter().map(Into::into).collect(),
+            keywords: original.keywords,
+            categories: original.categories,
+            yanked: original.yanked,
         })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IndexedPackageDependency {
+    /// Qualified with a registry if the dependency has to come from a named one, e.g. `internal:@namespace/name`.
     pub name: String,
     pub req: String,
 }
@@ -76,7 +92,7 @@ pub struct IndexedPackageDependency {
 impl From<Dependency<SemanticVersion>> for IndexedPackageDependency {
     fn from(dependency: Dependency<SemanticVersion>) -> Self {
         Self {
-            name: dependency.full_name,
+            name: dependency.qualified_name(),
             req: dependency.version_requirement,
         }
     }
@@ -0,0 +1,131 @@
+use crate::{
+    package::{PackageVersion, PackageVersionWithRegistryMetadata},
+    registry::Registry,
+};
+
+use anyhow::{anyhow, Error};
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// A registry which merges a default registry with named ones, e.g. an internal registry next to the public one.
+///
+/// Packages are only looked up in the default registry, a package is never taken from a named registry
+/// unless the dependency on it is qualified with the name of that registry, like `internal:@namespace/name`.
+/// Otherwise a package which is published to a named registry under the name of a package in the default registry
+/// could take its place.
+pub struct MergedRegistry {
+    default: Box<dyn Registry>,
+    named: Vec<(String, Box<dyn Registry>)>,
+    /// The named registries which package versions were retrieved from explicitly.
+    origins: RefCell<BTreeMap<PackageVersion, String>>,
+}
+
+impl MergedRegistry {
+    pub fn new(default: Box<dyn Registry>) -> Self {
+        Self {
+            default,
+            named: vec![],
+            origins: RefCell::new(BTreeMap::default()),
+        }
+    }
+
+    /// Adds a named registry, a registry which is added again under the same name replaces the previous one.
+    #[must_use]
+    pub fn with_registry(mut self, name: &str, registry: Box<dyn Registry>) -> Self {
+        self.named.retain(|(existing, _)| existing != name);
+        self.named.push((name.to_owned(), registry));
+        self
+    }
+
+    pub fn registry_names(&self) -> impl Iterator<Item = &str> {
+        self.named.iter().map(|(name, _)| name.as_str())
+    }
+
+    fn named_registry(&self, name: &str) -> Option<&dyn Registry> {
+        self.named
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, registry)| registry.as_ref())
+    }
+
+    /// The name of the registry which a package version comes from, `None` if it comes from the default registry.
+    ///
+    /// Only package versions which were retrieved through a qualified dependency come from a named registry.
+    pub fn registry_of(&self, package_version: &PackageVersion) -> Option<String> {
+        self.origins.borrow().get(package_version).cloned()
+    }
+
+    /// The registry which a package version comes from.
+    fn registry_for(&self, package_version: &PackageVersion) -> &dyn Registry {
+        self.registry_of(package_version)
+            .and_then(|name| self.named_registry(&name))
+            .unwrap_or_else(|| self.default.as_ref())
+    }
+}
+
+impl Registry for MergedRegistry {
+    fn package_names(&self) -> Result<Vec<String>, Error> {
+        self.default.package_names()
+    }
+
+    fn all_available_versions_of_a_package(
+        &self,
+        package_namespace_and_name: String,
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        self.default
+            .all_available_versions_of_a_package(package_namespace_and_name)
+    }
+
+    fn get_package_version_metadata(
+        &self,
+        package_version: &PackageVersion,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        self.registry_for(package_version)
+            .get_package_version_metadata(package_version)
+    }
+
+    fn retrieve_package(&self, package: &PackageVersion) -> Result<Vec<u8>, Error> {
+        self.registry_for(package).retrieve_package(package)
+    }
+
+    fn yank(&self, package_version: &PackageVersion, yanked: bool) -> Result<(), Error> {
+        self.registry_for(package_version)
+            .yank(package_version, yanked)
+    }
+
+    fn submit_package(
+        &self,
+        file_contents: &str,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        self.default.submit_package(file_contents)
+    }
+
+    fn all_available_versions_of_a_package_in_registry(
+        &self,
+        registry_name: &str,
+        package_namespace_and_name: String,
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        self.named_registry(registry_name)
+            .ok_or_else(|| {
+                anyhow!("There is no registry named {registry_name} to retrieve {package_namespace_and_name} from.")
+            })?
+            .all_available_versions_of_a_package(package_namespace_and_name)
+    }
+
+    fn get_package_version_metadata_in_registry(
+        &self,
+        registry_name: &str,
+        package_version: &PackageVersion,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        let metadata = self
+            .named_registry(registry_name)
+            .ok_or_else(|| {
+                anyhow!("There is no registry named {registry_name} to retrieve {package_version:?} from.")
+            })?
+            .get_package_version_metadata(package_version)?;
+        // Keep track of it to retrieve the package from the same registry later.
+        self.origins
+            .borrow_mut()
+            .insert(package_version.clone(), registry_name.to_owned());
+        Ok(metadata)
+    }
+}
@@ -1,7 +1,9 @@
 use crate::{
+    checksum,
     metadata::OntologyMetadata,
     package::{PackageVersion, PackageVersionWithRegistryMetadata},
     registry::Registry,
+    source::PackageSource,
 };
 
 use anyhow::{anyhow, bail, Context, Error};
@@ -9,12 +11,14 @@ use glob::glob;
 use harriet::TurtleDocument;
 use plow_ontology::PackageName;
 
-use sha2::{Digest, Sha256};
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     fs,
     io::Write,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use super::index::{IndexContents, IndexedPackageVersion};
@@ -29,9 +33,20 @@ const ARTIFACTS_DIRECTORY: &str = "artifacts";
 ///                     |         |- @namespace2 -- bar.json
 ///                     |
 ///                     |- artifacts -- <`ARTIFACT_HASH`>
+///
+/// Index files are cached in memory after they were read, until their modification time or size changes.
 #[derive(Debug, Clone)]
 pub struct OnDiskRegistry {
     root_directory: PathBuf,
+    index_cache: RefCell<HashMap<PathBuf, CachedIndexFile>>,
+}
+
+/// The contents of an index file as of the time it was last modified.
+#[derive(Debug, Clone)]
+struct CachedIndexFile {
+    modified: SystemTime,
+    len: u64,
+    contents: IndexContents,
 }
 
 impl OnDiskRegistry {
@@ -45,13 +60,32 @@ impl OnDiskRegistry {
             );
         }
 
-        Ok(Self { root_directory })
+        Ok(Self {
+            root_directory,
+            index_cache: RefCell::new(HashMap::default()),
+        })
     }
 
     pub fn root_directory(&self) -> &Path {
         &self.root_directory
     }
 
+    /// The source which packages of this registry are locked with.
+    pub fn source(&self) -> PackageSource {
+        PackageSource::OnDiskRegistry(self.root_directory.to_string_lossy().into_owned())
+    }
+
+    /// Attaches the source of this registry to a package version from the index.
+    fn with_source(
+        &self,
+        indexed_package_version: IndexedPackageVersion,
+    ) -> Result<PackageVersionWithRegistryMetadata, Error> {
+        let mut package_version: PackageVersionWithRegistryMetadata =
+            indexed_package_version.try_into()?;
+        package_version.source = Some(self.source());
+        Ok(package_version)
+    }
+
     fn index_dir(&self) -> Result<PathBuf, Error> {
         let index_path = self.root_directory.join(INDEX_DIRECTORY);
         Ok(PathBuf::from(index_path.to_str().ok_or_else(|| {
@@ -62,27 +96,31 @@ impl OnDiskRegistry {
     /// Iterates through all packages in the registry and checks that they are well-formed.
     ///
     /// This currently checks whether the metadata for all packages can be parsed and their artifacts
-    /// are available under the listed cksum.
-    ///
-    /// Currently DOES NOT validate the cksum of the artifacts.
+    /// are available under the listed cksum with contents which hash to it.
     pub fn verify_integrity(&self) -> Result<(), Error> {
         let index_dir = self.index_dir()?;
 
         let glob_pattern = format!("{index}/**/*.json", index = index_dir.to_string_lossy());
         for metadata_file in glob(&glob_pattern)? {
-            let metadata_file = metadata_file?;
-
-            let file_contents = fs::read_to_string(metadata_file)?;
-            let package_contents: IndexContents = serde_json::from_str(&file_contents)?;
+            let package_contents = self.read_index_file(&metadata_file?)?;
 
             for version in package_contents.versions {
-                self.get_artifact_for_cksum(&version.cksum)?;
+                let artifact = self.get_artifact_for_cksum(&version.cksum)?;
+                if !checksum::matches(&artifact, &version.cksum) {
+                    bail!(
+                        "Artifact of `{name}` version `{version}` does not match its cksum `{cksum}`",
+                        name = version.name,
+                        version = version.version,
+                        cksum = version.cksum
+                    );
+                }
             }
         }
         Ok(())
     }
 
-    fn package_metadata_path(&self, package_name: &str) -> Result<PathBuf, Error> {
+    /// Path of the index file of a package, it does not need to exist.
+    pub(crate) fn package_metadata_path(&self, package_name: &str) -> Result<PathBuf, Error> {
         let package_name = PackageName::try_from(package_name.to_owned())?;
         let namespace = package_name.namespace();
         let name = package_name.package();
@@ -95,6 +133,39 @@ impl OnDiskRegistry {
         Ok(expected_package_path)
     }
 
+    /// Reads and parses an index file, unless it is cached and did not change since.
+    fn read_index_file(&self, path: &Path) -> Result<IndexContents, Error> {
+        let file_metadata = fs::metadata(path)?;
+        let modified = file_metadata.modified()?;
+        let len = file_metadata.len();
+        if let Some(cached) = self.index_cache.borrow().get(path) {
+            if cached.modified == modified && cached.len == len {
+                return Ok(cached.contents.clone());
+            }
+        }
+
+        let contents: IndexContents = serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Index file {} is corrupt.", path.display()))?;
+        self.index_cache.borrow_mut().insert(
+            path.to_owned(),
+            CachedIndexFile {
+                modified,
+                len,
+                contents: contents.clone(),
+            },
+        );
+        Ok(contents)
+    }
+
+    /// Paths of all index files, one for each package.
+    fn index_files(&self) -> Result<Vec<PathBuf>, Error> {
+        let glob_pattern = format!(
+            "{index}/**/*.json",
+            index = self.index_dir()?.to_string_lossy()
+        );
+        Ok(glob(&glob_pattern)?.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Tries to retrieve the the package metadata from the index.
     ///
     /// If no version of the package exists in the index it will return `None`.
@@ -108,8 +179,7 @@ impl OnDiskRegistry {
             return Ok(None);
         }
 
-        let file_contents = fs::read_to_string(expected_package_path)?;
-        Ok(Some(serde_json::from_str(&file_contents)?))
+        Ok(Some(self.read_index_file(&expected_package_path)?))
     }
 
     /// Tries to retrieve the the package version metadata from the index.
@@ -148,16 +218,9 @@ impl OnDiskRegistry {
         Ok(fs::read(expected_package_path)?)
     }
 
-    /// Computes the SHA256 cksum for the provided file contents.
-    fn compute_cksum(file_contents: &str) -> String {
-        let mut sha256 = Sha256::new();
-        sha256.update(file_contents.as_bytes());
-        format!("{:X}", sha256.finalize()).to_lowercase()
-    }
-
     /// Writes the provided ontology package to the artifact registry and returns its cksum.
     fn submit_package_artifact(&self, file_contents: &str) -> Result<String, Error> {
-        let cksum = Self::compute_cksum(file_contents);
+        let cksum = checksum::compute(file_contents.as_bytes());
 
         let artifact_path = self.root_directory.join(ARTIFACTS_DIRECTORY).join(&cksum);
 
@@ -196,12 +259,18 @@ impl OnDiskRegistry {
         }
 
         // Add new version to metadata
-        let mut metadata: IndexContents =
-            serde_json::from_str(&fs::read_to_string(&package_metadata_path)?)?;
+        let mut metadata = self.read_index_file(&package_metadata_path)?;
         metadata.versions.push(package_version.clone().try_into()?);
 
-        let mut file = fs::File::create(&package_metadata_path)?;
-        file.write_all(serde_json::to_string_pretty(&metadata)?.as_bytes())?;
+        self.write_index_file(&package_metadata_path, &metadata)
+    }
+
+    /// Writes an index file and forgets what was cached of it.
+    fn write_index_file(&self, path: &Path, contents: &IndexContents) -> Result<(), Error> {
+        let mut file = fs::File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(contents)?.as_bytes())?;
+        // The modification time might not change if the file is written again right away.
+        self.index_cache.borrow_mut().remove(path);
 
         Ok(())
     }
@@ -212,23 +281,44 @@ impl OnDiskRegistry {
             .is_some())
     }
 
+    /// Removes a package version from the index and its artifact, e.g. to take back a submission which could not be completed.
+    ///
+    /// The index file of the package is removed together with its last version.
+    pub fn remove_package_version(&self, package_version: &PackageVersion) -> Result<(), Error> {
+        let package_metadata_path = self.package_metadata_path(&package_version.package_name)?;
+        let mut metadata = self
+            .get_package_index_metadata(&package_version.package_name)?
+            .ok_or_else(|| anyhow!("Unable to find package version metadata"))?;
+        let position = metadata
+            .versions
+            .iter()
+            .position(|indexed| indexed.version == package_version.version)
+            .ok_or_else(|| anyhow!("Unable to find package version metadata"))?;
+        let removed = metadata.versions.remove(position);
+
+        if metadata.versions.is_empty() {
+            fs::remove_file(&package_metadata_path)?;
+            self.index_cache.borrow_mut().remove(&package_metadata_path);
+        } else {
+            self.write_index_file(&package_metadata_path, &metadata)?;
+        }
+        let artifact_path = self
+            .root_directory
+            .join(ARTIFACTS_DIRECTORY)
+            .join(removed.cksum);
+        if artifact_path.exists() {
+            fs::remove_file(artifact_path)?;
+        }
+        Ok(())
+    }
+
     pub fn list_all_package_versions(
         &self,
     ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
-        let index_dir = self.index_dir()?;
-
         let mut package_versions = vec![];
-        let glob_pattern = format!("{index}/**/*.json", index = index_dir.to_string_lossy());
-
-        for metadata_file in glob(&glob_pattern)? {
-            let metadata_file = metadata_file?;
-
-            let file_contents = fs::read_to_string(metadata_file)?;
-
-            let package_contents: IndexContents = serde_json::from_str(&file_contents)?;
-
-            for version in package_contents.versions {
-                package_versions.push(version.try_into()?);
+        for metadata_file in self.index_files()? {
+            for version in self.read_index_file(&metadata_file)?.versions {
+                package_versions.push(self.with_source(version)?);
             }
         }
 
@@ -237,14 +327,39 @@ impl OnDiskRegistry {
 }
 
 impl Registry for OnDiskRegistry {
+    /// Names of the packages are taken from the paths of their index files, which are not read.
+    fn package_names(&self) -> Result<Vec<String>, Error> {
+        self.index_files()?
+            .iter()
+            .map(|metadata_file| {
+                let name = metadata_file.file_stem().and_then(|name| name.to_str());
+                let namespace = metadata_file
+                    .parent()
+                    .and_then(Path::file_name)
+                    .and_then(|namespace| namespace.to_str());
+                match (namespace, name) {
+                    (Some(namespace), Some(name)) => Ok(format!("{namespace}/{name}")),
+                    _ => Err(anyhow!(
+                        "Index file {} is not named after a package.",
+                        metadata_file.display()
+                    )),
+                }
+            })
+            .collect()
+    }
+
     fn all_available_versions_of_a_package(
         &self,
         package_namespace_and_name: String,
-    ) -> Vec<PackageVersionWithRegistryMetadata> {
-        self.list_all_package_versions()
-            .unwrap_or_default()
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        // A name which is not a valid package name can not be in the index.
+        if PackageName::try_from(package_namespace_and_name.clone()).is_err() {
+            return Ok(vec![]);
+        }
+        self.get_package_index_metadata(&package_namespace_and_name)?
+            .map_or_else(Vec::new, |package_metadata| package_metadata.versions)
             .into_iter()
-            .filter(|package_version| package_version.package_name == package_namespace_and_name)
+            .map(|version| self.with_source(version))
             .collect()
     }
     fn get_package_version_metadata(
@@ -253,7 +368,7 @@ impl Registry for OnDiskRegistry {
     ) -> Result<PackageVersionWithRegistryMetadata, Error> {
         self.get_package_version_metadata_index(package_version)
             .map(|n| n.ok_or_else(|| anyhow!("Unable to find package version metadata")))?
-            .map(std::convert::TryInto::try_into)?
+            .map(|indexed_package_version| self.with_source(indexed_package_version))?
     }
 
     fn retrieve_package(&self, package: &PackageVersion) -> Result<Vec<u8>, Error> {
@@ -272,6 +387,21 @@ impl Registry for OnDiskRegistry {
             ))
     }
 
+    fn yank(&self, package_version: &PackageVersion, yanked: bool) -> Result<(), Error> {
+        let package_metadata_path = self.package_metadata_path(&package_version.package_name)?;
+        let mut metadata = self
+            .get_package_index_metadata(&package_version.package_name)?
+            .ok_or_else(|| anyhow!("Unable to find package version metadata"))?;
+        let indexed_package_version = metadata
+            .versions
+            .iter_mut()
+            .find(|indexed| indexed.version == package_version.version)
+            .ok_or_else(|| anyhow!("Unable to find package version metadata"))?;
+        indexed_package_version.yanked = yanked;
+
+        self.write_index_file(&package_metadata_path, &metadata)
+    }
+
     /// Submits an ontology package to the registry.
     fn submit_package(
         &self,
@@ -296,6 +426,10 @@ impl Registry for OnDiskRegistry {
                     dependencies: metadata.dependencies,
                     cksum: Some(cksum),
                     private: false,
+                    source: Some(self.source()),
+                    keywords: metadata.keywords,
+                    categories: metadata.categories,
+                    yanked: false,
                 };
 
                 self.submit_package_version_metadata(&new_package_version)?;
@@ -6,17 +6,35 @@ use crate::{
     registry::{on_disk::OnDiskRegistry, Registry},
 };
 use anyhow::{anyhow, bail, Error};
-use git2::{build::CheckoutBuilder, Cred, FetchOptions, RemoteCallbacks, Repository};
-use std::{fs, path::PathBuf};
+use git2::{
+    build::CheckoutBuilder, Commit, Cred, FetchOptions, PushOptions, RemoteCallbacks, Repository,
+    ResetType, Signature, StatusOptions,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The remote which the registry is pulled from and pushed to.
+const REMOTE_NAME: &str = "origin";
+/// The branch of the remote which the registry is on, unless another one is set with [`OnDiskGitRegistry::with_branch`].
+const DEFAULT_BRANCH_NAME: &str = "main";
+/// Who commits submitted packages, unless the repository is configured with a user.
+const COMMITTER_NAME: &str = "Plow Registry";
+const COMMITTER_EMAIL: &str = "registry@plow.pm";
 
 #[derive(Debug, Clone)]
 /// Wrapper for [`OnDiskRegistry`] for on-disk registries that are backed by a git repository.
 ///
 /// Provides additional functionality for pulling/pushing the latest state of the repository.
+/// Submitted packages are committed and pushed to the remote right away.
+///
+/// Only index files are committed, the artifacts of the packages are served by the registry which the index belongs to.
 
 pub struct OnDiskGitRegistry {
     on_disk_registry: OnDiskRegistry,
     ssh_priv_key: Option<String>,
+    branch: String,
 }
 
 impl OnDiskGitRegistry {
@@ -72,6 +90,7 @@ impl OnDiskGitRegistry {
             return Ok(Self {
                 on_disk_registry: OnDiskRegistry::new(root_directory)?,
                 ssh_priv_key,
+                branch: DEFAULT_BRANCH_NAME.to_owned(),
             });
         }
 
@@ -105,6 +124,7 @@ impl OnDiskGitRegistry {
         Ok(Self {
             on_disk_registry: OnDiskRegistry::new(root_directory)?,
             ssh_priv_key,
+            branch: DEFAULT_BRANCH_NAME.to_owned(),
         })
     }
 
@@ -123,13 +143,21 @@ impl OnDiskGitRegistry {
         Ok(Self {
             on_disk_registry: OnDiskRegistry::new(root_directory)?,
             ssh_priv_key,
+            branch: DEFAULT_BRANCH_NAME.to_owned(),
         })
     }
 
+    /// Sets the branch of the remote which the registry is on, `main` by default.
+    #[must_use]
+    pub fn with_branch<B: Into<String>>(mut self, branch: B) -> Self {
+        self.branch = branch.into();
+        self
+    }
+
     /// Updates the registry by pulling the latest changes from remote.
     pub fn update_from_remote(&mut self) -> Result<(), Error> {
-        let remote_name = "origin";
-        let remote_branch_name = "main";
+        let remote_name = REMOTE_NAME;
+        let remote_branch_name = self.branch.as_str();
         let repo = self.repo()?;
 
         let callbacks = Self::remote_callbacks_for_priv_key(self.ssh_priv_key.clone());
@@ -150,19 +178,134 @@ impl OnDiskGitRegistry {
 
         Ok(())
     }
+
+    /// Fetches the branch of the remote and resets to it, unless the remote does not have it yet.
+    ///
+    /// Returns the commit which the registry is at.
+    fn reset_to_remote<'repo>(
+        &self,
+        repo: &'repo Repository,
+    ) -> Result<Option<Commit<'repo>>, Error> {
+        let callbacks = Self::remote_callbacks_for_priv_key(self.ssh_priv_key.clone());
+        let remote_branch = format!("refs/remotes/{REMOTE_NAME}/{}", self.branch);
+        repo.find_remote(REMOTE_NAME)?.fetch(
+            &[format!("+refs/heads/{}:{remote_branch}", self.branch)],
+            Some(FetchOptions::default().remote_callbacks(callbacks)),
+            None,
+        )?;
+
+        match repo.find_reference(&remote_branch) {
+            Ok(reference) => {
+                let commit = reference.peel_to_commit()?;
+                self.reset_to(repo, Some(&commit))?;
+                Ok(Some(commit))
+            }
+            // The remote is empty.
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Resets the branch and the working directory to the commit, untracked files are removed.
+    ///
+    /// Without a commit, the branch and everything in the working directory are removed.
+    fn reset_to(&self, repo: &Repository, commit: Option<&Commit>) -> Result<(), Error> {
+        let branch = format!("refs/heads/{}", self.branch);
+        if let Some(commit) = commit {
+            repo.reference(&branch, commit.id(), true, "Reset to the remote")?;
+            repo.set_head(&branch)?;
+            repo.reset(commit.as_object(), ResetType::Hard, None)?;
+        } else {
+            if let Ok(mut reference) = repo.find_reference(&branch) {
+                reference.delete()?;
+            }
+            let mut index = repo.index()?;
+            index.clear()?;
+            index.write()?;
+        }
+
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("The registry repository has no working directory."))?;
+        let statuses = repo.statuses(Some(
+            StatusOptions::new()
+                .include_untracked(true)
+                .recurse_untracked_dirs(true),
+        ))?;
+        for status in statuses.iter().filter(|status| status.status().is_wt_new()) {
+            if let Some(path) = status.path() {
+                fs::remove_file(workdir.join(path))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resets the registry to the commit if a change of it failed, so that it stays the same as the remote.
+    fn reset_on_failure<T>(
+        &self,
+        repo: &Repository,
+        commit: Option<&Commit>,
+        result: Result<T, Error>,
+    ) -> Result<T, Error> {
+        result.or_else(|err| {
+            self.reset_to(repo, commit).map_err(|reset_err| {
+                anyhow!("{err} The registry could not be reset to the remote either. Error: {reset_err}")
+            })?;
+            Err(err)
+        })
+    }
+
+    /// Commits the changed index file of a package on top of the given commit and pushes it to the remote.
+    ///
+    /// Everything else in the working directory, e.g. the artifact of a submitted package, is removed afterwards.
+    fn commit_and_push(
+        &self,
+        repo: &Repository,
+        parent: Option<&Commit>,
+        index_file: &Path,
+        message: &str,
+    ) -> Result<(), Error> {
+        let mut index = repo.index()?;
+        index.add_path(index_file.strip_prefix(self.on_disk_registry.root_directory())?)?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = repo
+            .signature()
+            .or_else(|_| Signature::now(COMMITTER_NAME, COMMITTER_EMAIL))?;
+        let parents = parent.into_iter().collect::<Vec<_>>();
+        let commit = repo.commit(None, &signature, &signature, message, &tree, &parents)?;
+
+        let branch = format!("refs/heads/{}", self.branch);
+        repo.reference(&branch, commit, true, message)?;
+        repo.set_head(&branch)?;
+
+        let mut callbacks = Self::remote_callbacks_for_priv_key(self.ssh_priv_key.clone());
+        // Rejections of the remote, e.g. if it moved on in the meantime, are not errors of the push itself.
+        callbacks.push_update_reference(|reference, rejection| {
+            rejection.map_or(Ok(()), |rejection| {
+                Err(git2::Error::from_str(&format!(
+                    "The remote rejected {reference}: {rejection}"
+                )))
+            })
+        });
+        repo.find_remote(REMOTE_NAME)?.push(
+            &[format!("{branch}:{branch}")],
+            Some(PushOptions::new().remote_callbacks(callbacks)),
+        )?;
+        self.reset_to(repo, Some(&repo.find_commit(commit)?))
+    }
 }
 
 impl Registry for OnDiskGitRegistry {
+    fn package_names(&self) -> Result<Vec<String>, Error> {
+        self.on_disk_registry.package_names()
+    }
+
     fn all_available_versions_of_a_package(
         &self,
         package_namespace_and_name: String,
-    ) -> Vec<PackageVersionWithRegistryMetadata> {
+    ) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
         self.on_disk_registry
-            .list_all_package_versions()
-            .unwrap_or_default()
-            .into_iter()
-            .filter(|package_version| package_version.package_name == package_namespace_and_name)
-            .collect()
+            .all_available_versions_of_a_package(package_namespace_and_name)
     }
     fn get_package_version_metadata(
         &self,
@@ -176,10 +319,61 @@ impl Registry for OnDiskGitRegistry {
         self.on_disk_registry.retrieve_package(package)
     }
 
+    /// Submits the package on top of the latest state of the remote, commits it and pushes it.
+    ///
+    /// If it can not be pushed, the registry is reset to the state of the remote,
+    /// so that the index and the artifacts stay the same as the ones of the remote.
     fn submit_package(
         &self,
-        _file_contents: &str,
+        file_contents: &str,
     ) -> Result<PackageVersionWithRegistryMetadata, Error> {
-        unimplemented!()
+        let repo = self.repo()?;
+        let head = self.reset_to_remote(&repo)?;
+
+        let submitted = self
+            .on_disk_registry
+            .submit_package(file_contents)
+            .and_then(|package_version| {
+                self.commit_and_push(
+                    &repo,
+                    head.as_ref(),
+                    &self
+                        .on_disk_registry
+                        .package_metadata_path(&package_version.package_name)?,
+                    &format!(
+                        "Add {} {}",
+                        package_version.package_name, package_version.version
+                    ),
+                )?;
+                Ok(package_version)
+            });
+        self.reset_on_failure(&repo, head.as_ref(), submitted)
+    }
+
+    /// Yanks the package on top of the latest state of the remote, commits it and pushes it.
+    ///
+    /// If it can not be pushed, the registry is reset to the state of the remote.
+    fn yank(&self, package_version: &PackageVersion, yanked: bool) -> Result<(), Error> {
+        let repo = self.repo()?;
+        let head = self.reset_to_remote(&repo)?;
+
+        let action = if yanked { "Yank" } else { "Unyank" };
+        let result = self
+            .on_disk_registry
+            .yank(package_version, yanked)
+            .and_then(|()| {
+                self.commit_and_push(
+                    &repo,
+                    head.as_ref(),
+                    &self
+                        .on_disk_registry
+                        .package_metadata_path(&package_version.package_name)?,
+                    &format!(
+                        "{action} {} {}",
+                        package_version.package_name, package_version.version
+                    ),
+                )
+            });
+        self.reset_on_failure(&repo, head.as_ref(), result)
     }
 }
@@ -0,0 +1,418 @@
+use crate::{
+    metadata::OntologyMetadata,
+    package::{PackageVersion, PackageVersionWithRegistryMetadata},
+    registry::{
+        http::{field_hash, DifferenceQuery, PrivateIndex, PrivateIndexes},
+        Registry,
+    },
+};
+
+use anyhow::{anyhow, Error};
+use harriet::TurtleDocument;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+/// Path under which the contents of fields are served, the signed urls of the registry point there.
+const ARTIFACTS_PATH: &str = "artifacts";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Serves a registry through the v1 HTTP API which [`HttpRegistry`](super::http::HttpRegistry) speaks,
+/// e.g. an [`OnDiskRegistry`](super::on_disk::OnDiskRegistry) to self-host fields.
+///
+/// Every field of the registry is served through the private index, for whoever has the token of the server.
+/// Fields are not linted when they are submitted and their visibility and owner are not recorded.
+pub struct RegistryServer<R: Registry> {
+    registry: R,
+    server: Server,
+    token: Option<String>,
+    /// Package versions of the registry by their field hashes.
+    field_hashes: RefCell<HashMap<String, PackageVersion>>,
+}
+
+/// A response of the server before it is sent.
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    /// A [`JSend`](https://github.com/omniti-labs/jsend) response with data.
+    fn success(data: &Value) -> Self {
+        Self::json(200, &json!({ "status": "success", "data": data }))
+    }
+
+    /// A [`JSend`](https://github.com/omniti-labs/jsend) response for a request which can not be fulfilled.
+    fn fail(status: u16, failure: &str) -> Self {
+        Self::json(
+            status,
+            &json!({ "status": "fail", "data": { "failures": [failure] } }),
+        )
+    }
+
+    /// A [`JSend`](https://github.com/omniti-labs/jsend) response for a request which the server failed at.
+    ///
+    /// It has the same fields as the errors of the reference backend.
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(
+            status,
+            &json!({
+                "status": "error",
+                "code": status,
+                "error": StatusCode(status).default_reason_phrase(),
+                "message": message,
+                "data": null,
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            }),
+        )
+    }
+
+    fn json(status: u16, body: &Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn not_found(hash: &str) -> Self {
+        Self::error(404, &format!("There is no field with the hash {hash}."))
+    }
+}
+
+impl<R: Registry> RegistryServer<R> {
+    /// Listens on the address, e.g. `127.0.0.1:8080`, requests are only answered once [`RegistryServer::serve`] is called.
+    pub fn bind(registry: R, address: &str) -> Result<Self, Error> {
+        let server = Server::http(address)
+            .map_err(|err| anyhow!("Unable to listen on {address}. Error: {err}"))?;
+        let mut field_hashes = HashMap::new();
+        for package_name in registry.package_names()? {
+            for metadata in registry.all_available_versions_of_a_package(package_name)? {
+                let package_version = PackageVersion {
+                    package_name: metadata.package_name,
+                    version: metadata.version.to_string(),
+                };
+                field_hashes.insert(
+                    field_hash(&package_version.package_name, &package_version.version),
+                    package_version,
+                );
+            }
+        }
+        Ok(Self {
+            registry,
+            server,
+            token: None,
+            field_hashes: RefCell::new(field_hashes),
+        })
+    }
+
+    /// Only answers requests which are authenticated with the API token.
+    ///
+    /// Contents of fields are served through the signed urls of the registry instead,
+    /// which carry an HMAC of the field hash with the token.
+    #[must_use]
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// The url which the server is reached at, e.g. `http://127.0.0.1:8080`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.server.server_addr())
+    }
+
+    /// Answers requests until the server can not accept them anymore.
+    pub fn serve(&self) -> Result<(), Error> {
+        loop {
+            let mut request = self.server.recv()?;
+            let reply = self.handle(&mut request);
+            let response = Response::from_data(reply.body)
+                .with_status_code(reply.status)
+                .with_header(
+                    Header::from_bytes("Content-Type", reply.content_type)
+                        .map_err(|()| anyhow!("Invalid content type {}.", reply.content_type))?,
+                );
+            // A client which went away does not stop the server.
+            request.respond(response).ok();
+        }
+    }
+
+    fn handle(&self, request: &mut Request) -> Reply {
+        let mut body = vec![];
+        if let Err(err) = request.as_reader().read_to_end(&mut body) {
+            return Reply::error(400, &format!("Unable to read the request. Error: {err}"));
+        }
+        let header = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.as_str().to_owned())
+        };
+        let authorized = match self.token {
+            Some(ref token) => is_authorized(token, header("Authorization").as_deref()),
+            None => true,
+        };
+        // Signed urls are handed out for the host which the client reached the server at.
+        let base_url = header("Host").map_or_else(|| self.url(), |host| format!("http://{host}"));
+        let content_type = header("Content-Type").unwrap_or_default();
+
+        let url = request.url().to_owned();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match (request.method(), segments.as_slice()) {
+            (Method::Get, [ARTIFACTS_PATH, hash]) => self.artifact(hash, query),
+            _ if !authorized => Reply::error(401, "Unauthorized, a valid API token is needed."),
+            (Method::Post, ["v1", "field", "submit"]) => {
+                let dry_run = query.split('&').any(|pair| pair == "dry-run=true");
+                self.submit(&content_type, &body, dry_run)
+            }
+            (Method::Post, ["v1", "index", "private", "sync"]) => self.private_index(&body),
+            (Method::Get, ["v1", "artifact", "signed-url-by-field-hash", hash]) => {
+                self.signed_url(&base_url, hash)
+            }
+            (Method::Delete, ["v1", "field", hash, "yank"]) => self.yank(hash, true),
+            (Method::Put, ["v1", "field", hash, "unyank"]) => self.yank(hash, false),
+            _ => Reply::error(404, &format!("There is no route for {path}.")),
+        }
+    }
+
+    /// Submits the field in the `field` part of a multipart form, unless it is a dry run.
+    fn submit(&self, content_type: &str, body: &[u8], dry_run: bool) -> Reply {
+        let body = match std::str::from_utf8(body) {
+            Ok(body) => body,
+            Err(_) => return Reply::fail(400, "The submission is not UTF-8 encoded."),
+        };
+        let parts = match multipart_form(content_type, body) {
+            Some(parts) => parts,
+            None => return Reply::fail(400, "The submission is not a multipart form."),
+        };
+        let field = match parts.get("field") {
+            Some(field) => field,
+            None => return Reply::fail(400, "There is no field in the submission."),
+        };
+        let metadata = match TurtleDocument::parse_full(field)
+            .ok()
+            .and_then(|document| OntologyMetadata::try_from(&document).ok())
+        {
+            Some(metadata) => metadata,
+            None => {
+                return Reply::fail(
+                    400,
+                    "The field is not valid turtle or its name, version or dependencies can not be read.",
+                )
+            }
+        };
+        if self
+            .registry
+            .get_package_version_metadata(&metadata.clone().into())
+            .is_ok()
+        {
+            return Reply::fail(
+                400,
+                &format!(
+                    "Version {} of {} already exists in the registry.",
+                    metadata.package_version, metadata.package_name
+                ),
+            );
+        }
+        if dry_run {
+            return Reply::success(&Value::Null);
+        }
+        match self.registry.submit_package(field) {
+            Ok(submitted) => {
+                let package_version = PackageVersion {
+                    package_name: submitted.package_name,
+                    version: submitted.version.to_string(),
+                };
+                self.field_hashes.borrow_mut().insert(
+                    field_hash(&package_version.package_name, &package_version.version),
+                    package_version,
+                );
+                Reply::success(&Value::Null)
+            }
+            Err(err) => Reply::fail(400, &err.to_string()),
+        }
+    }
+
+    /// Sends every entry of the registry, the ones which the client has already are sent again so that it sees yanks.
+    fn private_index(&self, body: &[u8]) -> Reply {
+        let query = if body.is_empty() {
+            DifferenceQuery::default()
+        } else {
+            match serde_json::from_slice::<DifferenceQuery>(body) {
+                Ok(query) => query,
+                Err(err) => return Reply::fail(400, &format!("Invalid query. Error: {err}")),
+            }
+        };
+        let indexes = match self.package_versions().and_then(|package_versions| {
+            package_versions
+                .into_iter()
+                .map(PrivateIndex::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        }) {
+            Ok(indexes) => indexes,
+            Err(err) => return Reply::error(500, &err.to_string()),
+        };
+        let removed_field_hashes = query
+            .existing_local_field_hashes
+            .into_iter()
+            .filter(|hash| {
+                !indexes
+                    .iter()
+                    .any(|index| &field_hash(&index.name, &index.version) == hash)
+            })
+            .collect();
+
+        match serde_json::to_value(PrivateIndexes {
+            indexes,
+            removed_field_hashes,
+        }) {
+            Ok(data) => Reply::success(&data),
+            Err(err) => Reply::error(500, &err.to_string()),
+        }
+    }
+
+    /// The url of the contents of a field, signed with the token of the server if it has one.
+    fn signed_url(&self, base_url: &str, hash: &str) -> Reply {
+        if self.find_by_field_hash(hash).is_none() {
+            return Reply::not_found(hash);
+        }
+        let url = format!("{base_url}/{ARTIFACTS_PATH}/{hash}");
+        match self.token {
+            Some(ref token) => Reply::success(&json!({
+                "url": format!("{url}?signature={:x}", signature(token, hash).finalize().into_bytes())
+            })),
+            None => Reply::success(&json!({ "url": url })),
+        }
+    }
+
+    /// Serves the contents of a field, if the server has a token only through a signed url.
+    fn artifact(&self, hash: &str, query: &str) -> Reply {
+        if let Some(ref token) = self.token {
+            let valid = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("signature="))
+                .and_then(decode_hex)
+                .map_or(false, |given| {
+                    signature(token, hash).verify_slice(&given).is_ok()
+                });
+            if !valid {
+                return Reply::error(403, "The url of the field is not signed by the registry.");
+            }
+        }
+        let package_version = match self.find_by_field_hash(hash) {
+            Some(package_version) => package_version,
+            None => return Reply::not_found(hash),
+        };
+        match self.registry.retrieve_package(&package_version) {
+            Ok(contents) => Reply {
+                status: 200,
+                content_type: "text/turtle",
+                body: contents,
+            },
+            Err(err) => Reply::error(500, &err.to_string()),
+        }
+    }
+
+    fn yank(&self, hash: &str, yanked: bool) -> Reply {
+        let package_version = match self.find_by_field_hash(hash) {
+            Some(package_version) => package_version,
+            None => return Reply::not_found(hash),
+        };
+        match self.registry.yank(&package_version, yanked) {
+            Ok(()) => Reply::success(&Value::Null),
+            Err(err) => Reply::fail(400, &err.to_string()),
+        }
+    }
+
+    fn package_versions(&self) -> Result<Vec<PackageVersionWithRegistryMetadata>, Error> {
+        let mut package_versions = vec![];
+        for package_name in self.registry.package_names()? {
+            package_versions.extend(
+                self.registry
+                    .all_available_versions_of_a_package(package_name)?,
+            );
+        }
+        Ok(package_versions)
+    }
+
+    fn find_by_field_hash(&self, hash: &str) -> Option<PackageVersion> {
+        self.field_hashes.borrow().get(hash).cloned()
+    }
+}
+
+/// The HMAC of a message with the token of the server, of a field hash it signs the url of the contents of the field.
+fn signature(token: &str, hash: &str) -> HmacSha256 {
+    let mut signature =
+        HmacSha256::new_from_slice(token.as_bytes()).expect("HMAC takes keys of any length.");
+    signature.update(hash.as_bytes());
+    signature
+}
+
+/// Whether the `Authorization` header carries the token of the server.
+///
+/// The HMACs of the header and of the expected one are compared in constant time,
+/// so the time of a comparison does not tell how much of the token was guessed right.
+fn is_authorized(token: &str, authorization: Option<&str>) -> bool {
+    let expected = signature(token, &format!("Basic {token}"))
+        .finalize()
+        .into_bytes();
+    authorization.map_or(false, |authorization| {
+        signature(token, authorization)
+            .verify_slice(&expected)
+            .is_ok()
+    })
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            hex.get(index..index + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
+
+/// The text parts of a `multipart/form-data` body by their names.
+fn multipart_form(content_type: &str, body: &str) -> Option<HashMap<String, String>> {
+    let boundary = content_type
+        .strip_prefix("multipart/form-data")?
+        .split(';')
+        .find_map(|parameter| parameter.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{boundary}");
+
+    let mut parts = HashMap::new();
+    // What comes before the first delimiter is a preamble and after the last one `--`.
+    for part in body.split(delimiter.as_str()).skip(1) {
+        let (headers, contents) = match part.split_once("\r\n\r\n") {
+            Some(headers_and_contents) => headers_and_contents,
+            None => continue,
+        };
+        let name = headers.lines().find_map(|header| {
+            let (field, value) = header.split_once(':')?;
+            if !field.trim().eq_ignore_ascii_case("content-disposition") {
+                return None;
+            }
+            value
+                .split(';')
+                .find_map(|parameter| parameter.trim().strip_prefix("name="))
+                .map(|name| name.trim_matches('"').to_owned())
+        });
+        if let Some(name) = name {
+            let contents = contents.strip_suffix("\r\n").unwrap_or(contents);
+            parts.insert(name, contents.to_owned());
+        }
+    }
+    Some(parts)
+}
@@ -1,28 +1,31 @@
 pub mod dependency;
 pub mod error;
+pub mod report;
 
 pub use crate::version::semver;
 pub use dependency::Dependency;
 
+use dependency::split_registry_qualifier;
+
 use crate::{
     lock::PackageInLockFile,
     package::{
-        OrganizationToResolveFor, PackageSet, PackageVersion, PackageVersionWithRegistryMetadata,
+        OrganizationToResolveFor, PackageSet, PackageToUpdate, PackageVersion,
+        PackageVersionWithRegistryMetadata,
     },
     registry::Registry,
     version::SemanticVersion,
-    ORGANIZATION, ORGANIZATION_NAME, ORGANIZATION_VERSION,
+    ORGANIZATION_NAME, ORGANIZATION_VERSION,
 };
 
 use pubgrub::{
     range::Range,
-    report::Reporter,
     solver::{DependencyConstraints, DependencyProvider},
 };
 
 use std::{
     borrow::Borrow,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     convert::{From, Into},
 };
 
@@ -31,15 +34,19 @@ use fallible_iterator::FallibleIterator;
 use itertools::Itertools;
 
 use self::error::ResolverError;
-use std::str::FromStr;
+use self::report::{ConflictReport, RequirementsCache};
 
 /// A trait for different dependency resolver implementations.
 pub trait Resolver {
     /// Resolves a set of requested dependencies to a set of package versions.
+    ///
+    /// If a package to update is given, only that package and the packages it transitively
+    /// depends on in the lock file are unlocked, every other locked package stays pinned.
     fn resolve_dependencies(
         &self,
         organization_to_resolve_for: OrganizationToResolveFor,
         locked_dependencies: Option<&[PackageInLockFile]>,
+        package_to_update: Option<&PackageToUpdate>,
     ) -> Result<PackageSet, ResolverError>;
 }
 
@@ -55,6 +62,51 @@ pub type ValidPackagesIntermediateCache = BTreeMap<String, Vec<SemanticVersion>>
 
 /// A cache to use across resolver iterations.
 pub type AvailablePackagesCache = BTreeMap<String, Vec<PackageVersionWithRegistryMetadata>>;
+
+/// Versions of packages which are pinned by a lock file, indexed by package name.
+pub type LockedVersions = BTreeMap<String, Vec<SemanticVersion>>;
+
+/// Separates the package name from its major version in the names which the solver works with.
+///
+/// Only used when multiple major versions of a package are allowed, e.g. `@namespace/name#2`.
+const MAJOR_VERSION_SEPARATOR: char = '#';
+
+/// The name of a package without the major version which the solver may have attached to it.
+pub(crate) fn package_name_without_major_version(name: &str) -> &str {
+    name.split_once(MAJOR_VERSION_SEPARATOR)
+        .map_or(name, |(package_name, _)| package_name)
+}
+
+/// The name of a package without the registry qualifier and the major version which the solver may have attached to it.
+fn plain_package_name(name: &str) -> &str {
+    split_registry_qualifier(package_name_without_major_version(name)).1
+}
+
+/// The major version which a version is compatible within and the range of versions in it.
+///
+/// See [`SemanticVersion::compatible_major_version`] for what the major version is.
+#[allow(clippy::unwrap_used)]
+fn compatible_versions(version: &SemanticVersion) -> (String, Range<SemanticVersion>) {
+    let (major, minor, patch) = version.major_minor_patch();
+    let (lower, upper) = if major > 0 {
+        ((major, 0, 0), (major + 1, 0, 0))
+    } else if minor > 0 {
+        ((0, minor, 0), (0, minor + 1, 0))
+    } else {
+        ((0, 0, patch), (0, 0, patch + 1))
+    };
+    let (lower_major, lower_minor, lower_patch) = lower;
+    let (upper_major, upper_minor, upper_patch) = upper;
+    (
+        version.compatible_major_version(),
+        Range::between(
+            // A single numeric zero is the smallest pre-release identifier, so pre-releases are covered.
+            SemanticVersion::new_pre_release(lower_major, lower_minor, lower_patch, "0").unwrap(),
+            // Requirements exclude the next major version as a release, like `^2.0.0` does with `3.0.0`.
+            SemanticVersion::new(upper_major, upper_minor, upper_patch),
+        ),
+    )
+}
 /// A resolver which uses pubgrub algorithm to resolve dependencies with semantic version requests.
 pub struct VersionRequestResolver<'req_resolver> {
     // Access to registry.
@@ -63,7 +115,13 @@ pub struct VersionRequestResolver<'req_resolver> {
     valid_versions_intermediate_cache: std::cell::RefCell<ValidPackagesIntermediateCache>,
     // A cache to use across resolver iterations.
     available_packages_cache: std::cell::RefCell<AvailablePackagesCache>,
-    locked_dependencies: std::cell::RefCell<Option<BTreeMap<String, SemanticVersion>>>,
+    locked_dependencies: std::cell::RefCell<Option<LockedVersions>>,
+    // A package which should be updated to an exact version.
+    precise_version: std::cell::RefCell<Option<(String, SemanticVersion)>>,
+    // Version requirements as they were written, to explain conflicts with them.
+    requirements: std::cell::RefCell<RequirementsCache>,
+    // Whether incompatible major versions of a package may be resolved side by side.
+    allow_multiple_major_versions: bool,
 }
 
 impl<'req_resolver> From<&'req_resolver dyn Registry> for VersionRequestResolver<'req_resolver> {
@@ -75,13 +133,56 @@ impl<'req_resolver> From<&'req_resolver dyn Registry> for VersionRequestResolver
             ),
             available_packages_cache: std::cell::RefCell::new(AvailablePackagesCache::default()),
             locked_dependencies: std::cell::RefCell::new(None),
+            precise_version: std::cell::RefCell::new(None),
+            requirements: std::cell::RefCell::new(RequirementsCache::default()),
+            allow_multiple_major_versions: false,
         }
     }
 }
 
 impl<'req_resolver> VersionRequestResolver<'req_resolver> {
+    /// Allows incompatible major versions of a package to be resolved side by side.
+    ///
+    /// Requirements which stay within a single major version are then resolved separately per major version.
+    #[must_use]
+    pub fn allow_multiple_major_versions(mut self, allow: bool) -> Self {
+        self.allow_multiple_major_versions = allow;
+        self
+    }
+
+    /// The name which the solver uses for a dependency.
+    ///
+    /// Dependencies which are qualified with a registry keep the qualifier, e.g. `internal:@namespace/name`.
+    /// When multiple major versions are allowed, the major version is attached to the package name
+    /// if the requirement stays within a single major version.
+    fn solver_name(&self, dependency: &Dependency<SemanticVersion>) -> String {
+        if !self.allow_multiple_major_versions {
+            return dependency.qualified_name();
+        }
+        dependency
+            .version_range
+            .lowest_version()
+            .map(|lowest| compatible_versions(&lowest))
+            .filter(|(_, compatible_range)| {
+                dependency
+                    .version_range
+                    .intersection(&compatible_range.negate())
+                    == Range::none()
+            })
+            .map_or_else(
+                || dependency.qualified_name(),
+                |(major, _)| {
+                    format!(
+                        "{}{MAJOR_VERSION_SEPARATOR}{major}",
+                        dependency.qualified_name()
+                    )
+                },
+            )
+    }
+
     /// Handles the errors originate directly from dependency resolution.
     fn handle_resolution_errors(
+        &self,
         error: pubgrub::error::PubGrubError<String, SemanticVersion>,
     ) -> ResolverError {
         // Error in resolution
@@ -89,10 +190,9 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
         match error {
             NoSolution(mut tree) => {
                 tree.collapse_no_versions();
-                let report = pubgrub::report::DefaultStringReporter::report(&tree);
-                ResolverError::SolutionError(report.replace(ORGANIZATION, "organization").replace(
-                    "Organization is forbidden",
-                    "Dependencies of the organization could not be resolved",
+                ResolverError::Conflict(ConflictReport::from_derivation_tree(
+                    &tree,
+                    &self.requirements.borrow(),
                 ))
             }
             ErrorRetrievingDependencies {
@@ -127,9 +227,15 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
                 );
                 ResolverError::SolutionError(message)
             }
-            ErrorChoosingPackageVersion(err) => {
-                ResolverError::SolutionError(format!("Error choosing package version.\n{err}"))
-            }
+            ErrorChoosingPackageVersion(err) => match err.downcast::<ResolverError>() {
+                Ok(err) if matches!(*err, ResolverError::LockFileOutOfDate { .. }) => *err,
+                Ok(err) => {
+                    ResolverError::SolutionError(format!("Error choosing package version.\n{err}"))
+                }
+                Err(err) => {
+                    ResolverError::SolutionError(format!("Error choosing package version.\n{err}"))
+                }
+            },
             ErrorInShouldCancel(err) => {
                 ResolverError::SolutionError(format!("Error in should_cancel.\n{err}"))
             }
@@ -140,7 +246,7 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
     /// Post processes the resolved dependencies.
     ///
     /// This includes removing duplicates, sorting and validating if there are multiple
-    /// versions of the same package.
+    /// versions of the same package, or of the same major version if multiple major versions are allowed.
     fn post_process_and_deliver_resolved_dependencies(
         &self,
         resolved_dependencies: Vec<PackageVersion>,
@@ -148,6 +254,11 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
         // Here we apply any post processing to resolved dependencies
         let packages: Vec<PackageVersion> = resolved_dependencies
             .into_iter()
+            // Restore the package names which the solver has attached registries or major versions to.
+            .map(|mut package| {
+                package.package_name = plain_package_name(&package.package_name).to_owned();
+                package
+            })
             // Filter the root package which represents the organization which we resolve for.
             .filter(|package| package.package_name != ORGANIZATION_NAME)
             // Sort
@@ -169,6 +280,9 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
                                 && package_checking.version != package_comparing.version
                             // Other than the package we're currently checking.
                                 && package_checking != &package_comparing
+                            // Within the same major version if multiple major versions are allowed.
+                                && (!self.allow_multiple_major_versions
+                                    || Self::have_same_major_version(package_checking, package_comparing))
                 })
             })
             .collect();
@@ -193,6 +307,173 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
         Err(ResolverError::SolutionError(error_message))
     }
 
+    /// Whether two resolved versions of a package fall into the same major version.
+    fn have_same_major_version(first: &PackageVersion, second: &PackageVersion) -> bool {
+        match (
+            SemanticVersion::try_from(first.version.as_str()),
+            SemanticVersion::try_from(second.version.as_str()),
+        ) {
+            (Ok(first), Ok(second)) => {
+                compatible_versions(&first).0 == compatible_versions(&second).0
+            }
+            _ => true,
+        }
+    }
+
+    /// Collects the versions of packages in a lock file which should stay pinned during resolution.
+    ///
+    /// Only packages which another package in the lock file or the organization depends on are pinned,
+    /// the organization stands in for the root entries which are not passed to the resolver.
+    /// The package to update and everything it transitively depends on in the lock file is left out.
+    fn derive_locked_versions(
+        packages: &[PackageInLockFile],
+        organization_dependencies: &[Dependency<SemanticVersion>],
+        package_to_update: Option<&PackageToUpdate>,
+    ) -> Result<LockedVersions, ResolverError> {
+        // Dependencies are written as `name version` if multiple versions of them are locked.
+        let dependency_name = |dependency: &String| {
+            dependency
+                .split_once(' ')
+                .map_or(dependency.as_str(), |(name, _)| name)
+                .to_owned()
+        };
+
+        let mut unlocked = BTreeSet::new();
+        if let Some(package_to_update) = package_to_update {
+            if !packages
+                .iter()
+                .any(|package| package.name == package_to_update.package_name)
+            {
+                return Err(ResolverError::NotFoundInLockFile(
+                    package_to_update.package_name.clone(),
+                ));
+            }
+            let mut to_visit = vec![package_to_update.package_name.clone()];
+            while let Some(name) = to_visit.pop() {
+                if !unlocked.insert(name.clone()) {
+                    continue;
+                }
+                for package in packages.iter().filter(|package| package.name == name) {
+                    to_visit.extend(package.dependencies.iter().map(dependency_name));
+                }
+            }
+        }
+
+        let depended_on = packages
+            .iter()
+            .flat_map(|package| package.dependencies.iter().map(dependency_name))
+            .chain(
+                organization_dependencies
+                    .iter()
+                    .map(|dependency| plain_package_name(&dependency.full_name).to_owned()),
+            )
+            .collect::<BTreeSet<_>>();
+
+        packages
+            .iter()
+            .filter(|package| {
+                depended_on.contains(&package.name) && !unlocked.contains(&package.name)
+            })
+            .try_fold(LockedVersions::default(), |mut locked, package| {
+                let version = SemanticVersion::try_from(&package.version).map_err(|err| {
+                    ResolverError::InvalidLockFile(format!(
+                        "{} has an invalid version {}, {err}",
+                        package.name, package.version
+                    ))
+                })?;
+                locked
+                    .entry(package.name.clone())
+                    .or_default()
+                    .push(version);
+                Ok(locked)
+            })
+    }
+
+    /// Records a version requirement of a package as it was written.
+    ///
+    /// Packages are named as the solver names them.
+    fn record_requirement(
+        &self,
+        dependent: &str,
+        version: &SemanticVersion,
+        dependency: &Dependency<SemanticVersion>,
+    ) {
+        self.requirements
+            .borrow_mut()
+            .entry((dependent.to_owned(), self.solver_name(dependency)))
+            .or_default()
+            .insert(version.clone(), dependency.version_requirement.clone());
+    }
+
+    /// The range of versions which a requirement allows, without the pre-releases which it does not opt into.
+    ///
+    /// Like in Cargo a pre-release is only allowed by a requirement which names a pre-release of the same
+    /// `major.minor.patch`, so the other requirements on the package do not make it eligible.
+    fn requirement_range(
+        &self,
+        dependency: &Dependency<SemanticVersion>,
+    ) -> Result<Range<SemanticVersion>, ResolverError> {
+        let solver_name = self.solver_name(dependency);
+        let cached_package_name = package_name_without_major_version(&solver_name);
+        self.cache_available_packages(cached_package_name)?;
+        let available_packages_cache = self.available_packages_cache.borrow();
+        Ok(available_packages_cache
+            .get(cached_package_name)
+            .into_iter()
+            .flatten()
+            .map(|package| &package.version)
+            .filter(|version| {
+                version.is_pre_release()
+                    && !dependency
+                        .pre_release_opt_ins
+                        .contains(&version.major_minor_patch())
+            })
+            .fold(dependency.version_range.clone(), |range, pre_release| {
+                range.intersection(&Range::exact(pre_release.clone()).negate())
+            }))
+    }
+
+    /// Retrieves the available versions of a package from the registry unless they are cached already.
+    ///
+    /// Packages which are qualified with a registry are cached apart from the unqualified ones,
+    /// all major versions of a package share the same entry.
+    fn cache_available_packages(&self, cached_package_name: &str) -> Result<(), ResolverError> {
+        if self
+            .available_packages_cache
+            .borrow()
+            .contains_key(cached_package_name)
+        {
+            return Ok(());
+        }
+        let (registry_name, package_name) = split_registry_qualifier(cached_package_name);
+        let mut available_packages = match registry_name {
+            Some(registry_name) => self
+                .registry
+                .all_available_versions_of_a_package_in_registry(
+                    registry_name,
+                    package_name.to_owned(),
+                ),
+            None => self
+                .registry
+                .all_available_versions_of_a_package(package_name.to_owned()),
+        }
+        .map_err(|err| ResolverError::RegistryLookupFailed {
+            package: cached_package_name.to_owned(),
+            reason: err.to_string(),
+        })?;
+
+        // Yanked versions are only resolved if they are locked, which is handled in `choose_package_version`.
+        available_packages.retain(|package| !package.yanked);
+
+        // Sort by version, descending.
+        available_packages.sort_by(|a, b| b.version.cmp(&a.version));
+
+        self.available_packages_cache
+            .borrow_mut()
+            .insert(cached_package_name.to_owned(), available_packages);
+        Ok(())
+    }
+
     fn get_valid_packages<U: std::borrow::Borrow<Range<SemanticVersion>>>(
         available_packages: &[PackageVersionWithRegistryMetadata],
         requested_range: &U,
@@ -221,7 +502,7 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
                     Ok(dependencies) => {
                         // Collect all available packages.
                         available_versions_of_package_and_their_dependency_constraints
-                            .insert(metadata.version, dependencies);
+                            .insert(metadata.version.clone(), dependencies);
                         Ok(available_versions_of_package_and_their_dependency_constraints)
                     }
                     Err(err) => Err(err),
@@ -233,9 +514,9 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
         .keys()
         .sorted()
         .into_iter()
-        .copied().map(Ok))
+        .cloned().map(Ok))
         // We give the constraint here.
-        .filter(|v| Ok(requested_range.borrow().contains(v.borrow())))
+        .filter(|v| Ok(requested_range.borrow().contains(v)))
         .collect()
     }
 
@@ -252,7 +533,19 @@ impl<'req_resolver> VersionRequestResolver<'req_resolver> {
         requested_package: &T,
         requested_range: &U,
     ) -> Result<usize, ResolverError> {
-        let valid_packages = Self::get_valid_packages(available_packages, requested_range)?;
+        let requested_package_name = plain_package_name(requested_package.borrow());
+        let mut valid_packages = Self::get_valid_packages(available_packages, requested_range)?;
+
+        // A package which is updated precisely may only be picked in that version.
+        // Other major versions of it are left as they are if multiple major versions are allowed.
+        if let Some((ref package_name, ref precise_version)) = *self.precise_version.borrow() {
+            if package_name == requested_package_name
+                && (!self.allow_multiple_major_versions
+                    || requested_range.borrow().contains(precise_version))
+            {
+                valid_packages.retain(|version| version == precise_version);
+            }
+        }
 
         // Quantity of valid versions of a package
         let count = valid_packages.len();
@@ -273,7 +566,7 @@ impl<'req_resolver> Resolver for VersionRequestResolver<'req_resolver> {
         &self,
         organization_to_resolve_for: OrganizationToResolveFor,
         locked_dependencies: Option<&[PackageInLockFile]>,
-        // TODO: A user requested update will be added here.
+        package_to_update: Option<&PackageToUpdate>,
     ) -> Result<PackageSet, ResolverError> {
         // In our use case we solve for organizations but not packages.
         // An organization has dependencies but itself is not a package but more of an entity.
@@ -286,39 +579,39 @@ impl<'req_resolver> Resolver for VersionRequestResolver<'req_resolver> {
             organization_to_resolve_for.package_name.clone(),
             vec![PackageVersionWithRegistryMetadata {
                 package_name: organization_to_resolve_for.package_name.clone(),
-                version: organization_to_resolve_for.package_version,
+                version: organization_to_resolve_for.package_version.clone(),
                 ontology_iri: None,
-                dependencies: organization_to_resolve_for.dependencies,
+                dependencies: organization_to_resolve_for.dependencies.clone(),
                 cksum: None,
                 private: false,
+                source: None,
+                keywords: vec![],
+                categories: vec![],
+                yanked: false,
             }],
         );
 
+        // Requirements of a previous resolution must not leak into this one.
+        self.requirements.borrow_mut().clear();
+
         // Collect locked dependencies if there are some.
-        // Derives versions assuming there can only be one version of a dependency in a lock file.
-        if let Some(packages) = locked_dependencies {
-            *self.locked_dependencies.borrow_mut() = Some(packages.iter().fold(
-                BTreeMap::new(),
-                |mut locked_dependencies: BTreeMap<String, SemanticVersion>,
-                 package: &PackageInLockFile|
-                 -> BTreeMap<String, SemanticVersion> {
-                    // Dependencies of a single package in a lock file.
-                    let deps = &package.dependencies;
-                    // Find the right versions in the lock file.
-                    for dep_name in deps {
-                        for package in packages {
-                            if &package.name == dep_name
-                                && locked_dependencies.get(&package.name).is_none()
-                            {
-                                locked_dependencies
-                                    .insert(package.name.clone(), semver!(&package.version));
-                            }
-                        }
-                    }
-                    locked_dependencies
-                },
-            ));
-        }
+        // There may be multiple versions of a dependency in a lock file if multiple major versions are allowed.
+        *self.locked_dependencies.borrow_mut() = locked_dependencies
+            .map(|packages| {
+                Self::derive_locked_versions(
+                    packages,
+                    &organization_to_resolve_for.dependencies,
+                    package_to_update,
+                )
+            })
+            .transpose()?;
+
+        *self.precise_version.borrow_mut() = package_to_update.and_then(|package_to_update| {
+            package_to_update
+                .precise_version
+                .clone()
+                .map(|version| (package_to_update.package_name.clone(), version))
+        });
 
         // Now we are ready to start the resolution.
         pubgrub::solver::resolve(
@@ -332,7 +625,7 @@ impl<'req_resolver> Resolver for VersionRequestResolver<'req_resolver> {
             Ok(solved.iter().map_into().collect::<Vec<PackageVersion>>())
         })
         .map_or_else(
-            |err| Err(Self::handle_resolution_errors(err)),
+            |err| Err(self.handle_resolution_errors(err)),
             |resolved_dependencies| {
                 // Post process and deliver
                 self.post_process_and_deliver_resolved_dependencies(resolved_dependencies)
@@ -363,63 +656,47 @@ impl<'req_resolver> DependencyProvider<String, SemanticVersion>
         // You may check the original at (https://github.com/pubgrub-rs/pubgrub/blob/717289be5722dd5caaa0d1f4ed13047d11a7f7fd/src/solver.rs#L279-L300)
         let count_valid = |(package, range): &(T, U)| -> Result<usize, ResolverError> {
             // Early return if a package in potential_packages hits the lock file,
+            // packages which are requested to be updated are not in there.
 
-            // TODO: A way to update individual packages in the lock file.
-            // Ignore this for the package if it has an update flag.
-            // Change package type to include update flags.
+            // Packages which are qualified with a registry are cached apart from the unqualified ones.
+            let cached_package_name = package_name_without_major_version(package.borrow());
+            let package_name = plain_package_name(cached_package_name);
 
             if let Some(ref locked_dependencies) = *self.locked_dependencies.borrow() {
-                if let Some(locked_dependency_version) = locked_dependencies.get(package.borrow()) {
+                let locked_versions = locked_dependencies.get(package_name);
+                if let Some(locked_dependency_version) = locked_versions.and_then(|versions| {
+                    versions
+                        .iter()
+                        .find(|version| range.borrow().contains(version))
+                }) {
                     self.valid_versions_intermediate_cache.borrow_mut().insert(
                         package.borrow().clone(),
                         // We know that this is always a valid, single, complete and exact version.
-                        vec![*locked_dependency_version],
+                        vec![locked_dependency_version.clone()],
                     );
                     // Pick only one valid version since we exactly want that version.
                     return Ok(1);
                 }
+                // A pinned version which does not satisfy the requirement anymore is never resolved again,
+                // only the package to update and its dependencies are left out of the pins.
+                if let Some(versions) = locked_versions {
+                    return Err(ResolverError::LockFileOutOfDate {
+                        package: package_name.to_owned(),
+                        locked: versions.iter().map(ToString::to_string).join(", "),
+                        requirement: range.borrow().to_string(),
+                    });
+                }
             }
-            // if let Some(ref locked_dependencies) = *self.locked_dependencies.borrow() {
-            //     if let Some((locked_dependency_version, locked_dependency_range)) =
-            //         locked_dependencies.get(package.borrow())
-            //     {
-            //         if locked_dependency_range.intersection(range.borrow()) != Range::none() {
-            //             self.valid_versions_intermediate_cache.borrow_mut().insert(
-            //                 package.borrow().clone(),
-            //                 // We know that this is always a valid, single, complete and exact version.
-            //                 vec![*locked_dependency_version],
-            //             );
-
-            //             // Pick only one valid version since we exactly want that version.
-            //             return Ok(1);
-            //         }
-            //     }
-            // }
 
             // TODO: In future iterations we'll add other resources for checking for dependencies such as the local file system.
             // Currently we only check registry.
 
             // Check if we already retrieved this information and stored it in cache.
-            let mut available_package_cache = self.available_packages_cache.borrow_mut();
-            let available_packages = match available_package_cache.get(package.borrow()) {
-                Some(packages) => packages,
-                None => {
-                    // Cache miss, retrieve from registry.
-                    let mut available_packages = self
-                        .registry
-                        .all_available_versions_of_a_package(package.borrow().clone());
-
-                    // Sort by version, descending.
-                    available_packages.sort_by(|a, b| b.version.cmp(&a.version));
-
-                    // Cache available packages for the next run.
-                    available_package_cache.insert(package.borrow().clone(), available_packages);
-
-                    #[allow(clippy::unwrap_used)]
-                    // This is fine, we have just inserted it.
-                    available_package_cache.get(package.borrow()).unwrap()
-                }
-            };
+            self.cache_available_packages(cached_package_name)?;
+            let available_package_cache = self.available_packages_cache.borrow();
+            #[allow(clippy::unwrap_used)]
+            // This is fine, we have just cached it.
+            let available_packages = available_package_cache.get(cached_package_name).unwrap();
 
             // Pick valid versions of available packages regarding the requested version range.
             // Cache them to share the state out of this closure.
@@ -446,9 +723,9 @@ impl<'req_resolver> DependencyProvider<String, SemanticVersion>
         // Order by descending versions.
         valid_versions_for_package.sort_by(|a, b| b.cmp(a));
 
-        let version = valid_versions_for_package.iter().copied().find(|v| {
+        let version = valid_versions_for_package.into_iter().find(|v| {
             // Pick the first valid version, which will always be the highest version because we've sorted it.
-            range.borrow().contains(v.borrow())
+            range.borrow().contains(v)
         });
 
         // We need to clear our intermediate cache in the end of this iteration to have it ready and empty for the next run.
@@ -466,16 +743,20 @@ impl<'req_resolver> DependencyProvider<String, SemanticVersion>
     ) -> Result<pubgrub::solver::Dependencies<String, SemanticVersion>, Box<dyn std::error::Error>>
     {
         // We need to work with our own type to query the registry.
-        let package_version = (package, version).into();
+        let (registry_name, _) =
+            split_registry_qualifier(package_name_without_major_version(package));
+        let package_version = (&plain_package_name(package).to_owned(), version).into();
 
         if package == ORGANIZATION_NAME {
-            return Ok(pubgrub::solver::Dependencies::Known(
-                // We explicitly insert and always know that we'll have the organization package in cache.
-                #[allow(clippy::indexing_slicing)]
-                self.available_packages_cache.borrow()[package][0]
-                    .dependencies
-                    .iter()
-                    .try_fold(
+            // We explicitly insert and always know that we'll have the organization package in cache.
+            // The dependencies are cloned since the cache is filled while their ranges are derived.
+            #[allow(clippy::indexing_slicing)]
+            let organization_dependencies = self.available_packages_cache.borrow()[package][0]
+                .dependencies
+                .clone();
+            return Ok(
+                pubgrub::solver::Dependencies::Known(
+                    organization_dependencies.iter().try_fold(
                         DependencyConstraints::<String, SemanticVersion>::default(),
                         |mut dependencies,
                          dep|
@@ -484,22 +765,31 @@ impl<'req_resolver> DependencyProvider<String, SemanticVersion>
                             ResolverError,
                         > {
                             // Insert dependencies of the organization.
-                            dependencies.insert(dep.full_name.clone(), dep.version_range.clone());
+                            self.record_requirement(package, version, dep);
+                            dependencies
+                                .insert(self.solver_name(dep), self.requirement_range(dep)?);
                             Ok(dependencies)
                         },
                     )?,
-            ));
+                ),
+            );
         }
 
         Ok(
             // TODO: In future iterations we'll add other resources for checking for dependencies such as the local file system.
             // Currently we only check registry.
-            match self.registry.get_package_version_metadata(&package_version) {
+            match registry_name.map_or_else(
+                || self.registry.get_package_version_metadata(&package_version),
+                |registry_name| {
+                    self.registry
+                        .get_package_version_metadata_in_registry(registry_name, &package_version)
+                },
+            ) {
                 // Something went wrong and we couldn't retrieve the picked package from registry.
                 Err(_) => pubgrub::solver::Dependencies::Unknown,
                 // Deliver the dependencies of the picked package for this run.
-                Ok(package) => {
-                    let dependencies = package.dependencies.iter().try_fold(
+                Ok(metadata) => {
+                    let dependencies = metadata.dependencies.iter().try_fold(
                         DependencyConstraints::<String, SemanticVersion>::default(),
                         |mut dependencies,
                          dep|
@@ -507,7 +797,9 @@ impl<'req_resolver> DependencyProvider<String, SemanticVersion>
                             DependencyConstraints<String, SemanticVersion>,
                             ResolverError,
                         > {
-                            dependencies.insert(dep.full_name.clone(), dep.version_range.clone());
+                            self.record_requirement(package, version, dep);
+                            dependencies
+                                .insert(self.solver_name(dep), self.requirement_range(dep)?);
                             Ok(dependencies)
                         },
                     );
@@ -9,7 +9,28 @@ use itertools::Itertools;
 use pubgrub::{range::Range, version::Version};
 use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
 use serde::Serialize;
-use std::{convert::From, fmt};
+use std::{collections::BTreeSet, convert::From, fmt};
+
+/// Separates the name of a registry from the package name in a qualified dependency, e.g. `internal:@namespace/name`.
+pub const REGISTRY_QUALIFIER_SEPARATOR: char = ':';
+
+/// Splits a dependency name into the registry it is qualified with, if there is one, and the package name.
+///
+/// Package names start with `@`, so a separator in them does not qualify the name.
+pub fn split_registry_qualifier(dependency_name: &str) -> (Option<&str>, &str) {
+    match dependency_name.split_once(REGISTRY_QUALIFIER_SEPARATOR) {
+        Some((registry, name)) if !registry.starts_with('@') => (Some(registry), name),
+        _ => (None, dependency_name),
+    }
+}
+
+/// Whether a name is usable as the name of a registry, like the ones in `[registries]` of a config file.
+pub fn is_valid_registry_name(registry: &str) -> bool {
+    !registry.is_empty()
+        && registry
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
 
 /// A dependency type to use in [`VersionRequestResolver`](crate::resolve::VersionRequestResolver).
 #[derive(Debug, Clone, Serialize)]
@@ -27,6 +48,15 @@ where
     pub name: String,
     #[serde(skip)]
     pub version_range: Range<V>,
+    /// `(major, minor, patch)` triples which this requirement opts into pre-releases for.
+    ///
+    /// Like in Cargo, a pre-release is only eligible if a requirement names
+    /// a pre-release of the same `major.minor.patch`, e.g. `^2.0.0-beta`.
+    #[serde(skip)]
+    pub pre_release_opt_ins: BTreeSet<(u64, u64, u64)>,
+    /// The named registry which the dependency has to come from, if it is qualified with one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
 }
 
 impl<V> Dependency<V>
@@ -40,6 +70,14 @@ where
         self.version_range == Range::any()
     }
 
+    /// The name of the dependency with the registry it is qualified with, e.g. `internal:@namespace/name`.
+    pub fn qualified_name(&self) -> String {
+        self.registry.as_ref().map_or_else(
+            || self.full_name.clone(),
+            |registry| format!("{registry}{REGISTRY_QUALIFIER_SEPARATOR}{}", self.full_name),
+        )
+    }
+
     /// Parse a version request pair and return a [`Range`](pubgrub::range::Range) which
     /// will be used later in [`VersionRequestResolver`](crate::resolve::VersionRequestResolver)
     #[allow(clippy::indexing_slicing)]
@@ -74,33 +112,33 @@ where
             return match first_operator {
                 Exact => Ok(Self::exact_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
 
                 Greater => Ok(Self::greater_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
-                GreaterEq => Ok(Self::greater_eq_version_to_range(semantic_version)),
-                Less => Ok(Self::less_version_to_range(semantic_version)),
+                GreaterEq => Ok(Self::greater_eq_version_to_range(&semantic_version)),
+                Less => Ok(Self::less_version_to_range(&semantic_version)),
                 LessEq => Ok(Self::less_eq_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
                 Caret => Ok(Self::caret_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
                 Tilde => Ok(Self::tilde_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
                 // Catches only bare versions with wildcards.
                 // Do not catch a single wildcard.
                 // Do not catch wildcards following other operators.
                 Wildcard => Ok(Self::wildcard_version_to_range(
                     first_comparator,
-                    semantic_version,
+                    &semantic_version,
                 )),
                 // It would be a miracle to reach this path with linted documents.
                 _ => Err(anyhow!("Unsupported operator.")),
@@ -117,15 +155,18 @@ where
     /// =I.J — equivalent to >=I.J.0, <I.(J+1).0
     /// =I — equivalent to >=I.0.0, <(I+1).0.0
     /// ```
-    fn exact_version_to_range(comparator: &semver::Comparator, version: SemanticVersion) -> Range<V>
+    fn exact_version_to_range(
+        comparator: &semver::Comparator,
+        version: &SemanticVersion,
+    ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
         use SemanticVersionCompleteness::*;
         match semver_completeness(comparator) {
-            Complete => Range::exact(version),
-            OnlyMinorAndMajor => Range::between(version, version.bump_minor()),
-            OnlyMajor => Range::between(version, version.bump_major()),
+            Complete => Range::exact(version.clone()),
+            OnlyMinorAndMajor => Range::between(version.clone(), version.bump_minor()),
+            OnlyMajor => Range::between(version.clone(), version.bump_major()),
         }
     }
 
@@ -139,13 +180,14 @@ where
     /// ```
     fn greater_version_to_range(
         comparator: &semver::Comparator,
-        version: SemanticVersion,
+        version: &SemanticVersion,
     ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
         use SemanticVersionCompleteness::*;
         match semver_completeness(comparator) {
+            Complete if version.is_pre_release() => Range::higher_than(version.bump()),
             Complete => Range::higher_than(version.bump_patch()),
             OnlyMinorAndMajor => Range::higher_than(version.bump_minor()),
             OnlyMajor => Range::higher_than(version.bump_major()),
@@ -160,11 +202,11 @@ where
     /// >=I.J — equivalent to >=I.J.0
     /// >=I — equivalent to >=I.0.0
     /// ```
-    fn greater_eq_version_to_range(version: SemanticVersion) -> Range<V>
+    fn greater_eq_version_to_range(version: &SemanticVersion) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
-        Range::higher_than(version)
+        Range::higher_than(version.clone())
     }
 
     /// Convert a less than version to a [`Range`](pubgrub::range::Range)
@@ -175,11 +217,11 @@ where
     /// <I.J — equivalent to <I.J.0
     /// <I — equivalent to <I.0.0
     /// ```
-    fn less_version_to_range(version: SemanticVersion) -> Range<V>
+    fn less_version_to_range(version: &SemanticVersion) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
-        Range::strictly_lower_than(version)
+        Range::strictly_lower_than(version.clone())
     }
 
     /// Convert a less than or equals version to a [`Range`](pubgrub::range::Range)
@@ -192,13 +234,14 @@ where
     /// ```
     fn less_eq_version_to_range(
         comparator: &semver::Comparator,
-        version: SemanticVersion,
+        version: &SemanticVersion,
     ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
         use SemanticVersionCompleteness::*;
         match semver_completeness(comparator) {
+            Complete if version.is_pre_release() => Range::strictly_lower_than(version.bump()),
             Complete => Range::strictly_lower_than(version.bump_patch()),
             OnlyMinorAndMajor => Range::strictly_lower_than(version.bump_minor()),
             OnlyMajor => Range::strictly_lower_than(version.bump_major()),
@@ -215,7 +258,10 @@ where
     /// ^I.J (for I>0 or J>0) — equivalent to ^I.J.0
     /// ^I — equivalent to =I
     /// ```
-    fn caret_version_to_range(comparator: &semver::Comparator, version: SemanticVersion) -> Range<V>
+    fn caret_version_to_range(
+        comparator: &semver::Comparator,
+        version: &SemanticVersion,
+    ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
@@ -224,9 +270,18 @@ where
         use SemanticVersionCompleteness::*;
         match semver_completeness(comparator) {
             Complete if version.is_major_zero() && !version.is_minor_zero() => {
-                Range::between(version, version.bump_minor())
+                Range::between(version.clone(), version.bump_minor())
+            }
+            Complete
+                if version.is_major_zero()
+                    && version.is_minor_zero()
+                    && version.is_pre_release() =>
+            {
+                Range::between(version.clone(), version.bump_patch())
+            }
+            Complete if version.is_major_zero() && version.is_minor_zero() => {
+                Range::exact(version.clone())
             }
-            Complete if version.is_major_zero() && version.is_minor_zero() => Range::exact(version),
             OnlyMinorAndMajor if version.is_major_zero() && version.is_minor_zero() => {
                 Range::between(
                     SemanticVersion::zero(),
@@ -234,7 +289,7 @@ where
                 )
             }
             Complete | OnlyMinorAndMajor | OnlyMajor => {
-                Range::between(version, version.bump_major())
+                Range::between(version.clone(), version.bump_major())
             }
         }
     }
@@ -246,7 +301,10 @@ where
     /// ~I.J — equivalent to =I.J
     /// ~I — equivalent to =I
     /// ```
-    fn tilde_version_to_range(comparator: &semver::Comparator, version: SemanticVersion) -> Range<V>
+    fn tilde_version_to_range(
+        comparator: &semver::Comparator,
+        version: &SemanticVersion,
+    ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
     {
@@ -254,8 +312,8 @@ where
         // =I — equivalent to >=I.0.0, <(I+1).0.0
         use SemanticVersionCompleteness::*;
         match semver_completeness(comparator) {
-            Complete | OnlyMinorAndMajor => Range::between(version, version.bump_minor()),
-            OnlyMajor => Range::between(version, version.bump_major()),
+            Complete | OnlyMinorAndMajor => Range::between(version.clone(), version.bump_minor()),
+            OnlyMajor => Range::between(version.clone(), version.bump_major()),
         }
     }
 
@@ -268,7 +326,7 @@ where
     /// ```
     fn wildcard_version_to_range(
         comparator: &semver::Comparator,
-        version: SemanticVersion,
+        version: &SemanticVersion,
     ) -> Range<V>
     where
         V: Version + From<SemanticVersion>,
@@ -277,11 +335,28 @@ where
         match semver_completeness(comparator) {
             #[allow(clippy::unreachable)]
             Complete => unreachable!(),
-            OnlyMinorAndMajor => Range::between(version, version.bump_minor()),
-            OnlyMajor => Range::between(version, version.bump_major()),
+            OnlyMinorAndMajor => Range::between(version.clone(), version.bump_minor()),
+            OnlyMajor => Range::between(version.clone(), version.bump_major()),
         }
     }
 
+    /// Collects the `(major, minor, patch)` triples of the pre-releases which are explicitly named in version requests.
+    pub fn derive_pre_release_opt_ins(predicates: &[String]) -> BTreeSet<(u64, u64, u64)> {
+        predicates
+            .iter()
+            .filter_map(|predicate| semver::VersionReq::parse(predicate).ok())
+            .flat_map(|requirement| requirement.comparators)
+            .filter(|comparator| !comparator.pre.is_empty())
+            .map(|comparator| {
+                (
+                    comparator.major,
+                    comparator.minor.unwrap_or(0),
+                    comparator.patch.unwrap_or(0),
+                )
+            })
+            .collect()
+    }
+
     pub fn split_string_dependency_spec(input: &str) -> Result<(String, String)> {
         let (dependency_name, mut version_requirement) = input.split(' ').enumerate().fold(
             (String::default(), String::default()),
@@ -309,6 +384,13 @@ where
     }
 
     pub fn try_new(dependency_name: &str, version_requirement: &str) -> Result<Self> {
+        let (registry, dependency_name) = split_registry_qualifier(dependency_name);
+        if let Some(registry) = registry {
+            if !is_valid_registry_name(registry) {
+                bail!("Not a valid registry name: {}", registry);
+            }
+        }
+        let registry = registry.map(ToOwned::to_owned);
         let (namespace, name) = dependency_name
             .split_once('/')
             .ok_or_else(|| anyhow!("Not a valid dependency name."))?;
@@ -339,6 +421,8 @@ where
             version_requirement.split(' ').map_into().collect()
         };
 
+        let pre_release_opt_ins = Self::derive_pre_release_opt_ins(&version_predicates);
+
         match version_predicates.len() {
             2 => Ok(Self {
                 full_name: dependency_name.to_owned(),
@@ -346,6 +430,8 @@ where
                 namespace: namespace.to_owned(),
                 name: name.to_owned(),
                 version_range: Self::derive_range_for_version_request_pair(&version_predicates)?,
+                pre_release_opt_ins,
+                registry,
             }),
             1 => Ok(Self {
                 full_name: dependency_name.to_owned(),
@@ -353,6 +439,8 @@ where
                 namespace: namespace.to_owned(),
                 name: name.to_owned(),
                 version_range: Self::derive_range_for_single_version_request(version_requirement)?,
+                pre_release_opt_ins,
+                registry,
             }),
             _ => Err(anyhow!(
                 "Invalid version requirement: {}",
@@ -373,6 +461,7 @@ where
         enum Field {
             DependencyName,
             VersionRequirement,
+            Registry,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -386,7 +475,8 @@ where
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`dependency_name` or `version_requirement`")
+                        formatter
+                            .write_str("`dependency_name`, `version_requirement` or `registry`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -396,6 +486,7 @@ where
                         match value {
                             "dependency_name" => Ok(Field::DependencyName),
                             "version_requirement" => Ok(Field::VersionRequirement),
+                            "registry" => Ok(Field::Registry),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -428,6 +519,7 @@ where
             {
                 let mut dependency_name = None;
                 let mut version_requirement = None;
+                let mut registry: Option<&str> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::DependencyName => {
@@ -442,18 +534,29 @@ where
                             }
                             version_requirement = Some(map.next_value()?);
                         }
+                        Field::Registry => {
+                            if registry.is_some() {
+                                return Err(de::Error::duplicate_field("registry"));
+                            }
+                            registry = Some(map.next_value()?);
+                        }
                     }
                 }
                 let dependency_name =
                     dependency_name.ok_or_else(|| de::Error::missing_field("dependency_name"))?;
                 let version_requirement = version_requirement
                     .ok_or_else(|| de::Error::missing_field("version_requirement"))?;
-                Dependency::try_new(dependency_name, version_requirement)
+                let dependency_name: &str = dependency_name;
+                let qualified_name = registry.map_or_else(
+                    || dependency_name.to_owned(),
+                    |registry| format!("{registry}{REGISTRY_QUALIFIER_SEPARATOR}{dependency_name}"),
+                );
+                Dependency::try_new(&qualified_name, version_requirement)
                     .map_or_else(|err| Err(de::Error::custom(format!("{}", err))), Ok)
             }
         }
 
-        const FIELDS: &[&str] = &["dependency_name", "version_requirement"];
+        const FIELDS: &[&str] = &["dependency_name", "version_requirement", "registry"];
         deserializer.deserialize_struct(
             "Dependency",
             FIELDS,
@@ -469,7 +572,7 @@ where
     V: Version + From<SemanticVersion>,
 {
     fn to_string(&self) -> String {
-        format!("{} {}", self.full_name, self.version_requirement)
+        format!("{} {}", self.qualified_name(), self.version_requirement)
     }
 }
 
@@ -1,3 +1,4 @@
+use super::report::ConflictReport;
 use thiserror::Error;
 
 /// Errors related to dependency resolver.
@@ -10,10 +11,26 @@ pub enum ResolverError {
     /// An invalid version lock file has fed to resolver.
     #[error("Invalid lock file: {0}.")]
     InvalidLockFile(String),
+    /// A package which was requested to be updated is not in the lock file.
+    #[error("The package {0} which was requested to be updated is not in the lock file.")]
+    NotFoundInLockFile(String),
+    /// A locked version does not satisfy the requirement on it anymore.
+    #[error("The lock file is out of date, {package} is locked at {locked} which does not satisfy {requirement}.")]
+    LockFileOutOfDate {
+        package: String,
+        locked: String,
+        requirement: String,
+    },
     /// Error related to retrieving a packages metadata from the registry.
     #[error("The package {0} was not found in registry.")]
     NotFoundInRegistry(String),
+    /// The registry could not tell which versions of a package it has.
+    #[error("The versions of {package} could not be retrieved from the registry. Error: {reason}")]
+    RegistryLookupFailed { package: String, reason: String },
     /// Resolution error.
     #[error("{0}")]
     SolutionError(String),
+    /// The requirements of packages conflict with each other.
+    #[error("{0}")]
+    Conflict(ConflictReport),
 }
@@ -0,0 +1,329 @@
+use super::package_name_without_major_version;
+use crate::{version::SemanticVersion, ORGANIZATION_NAME};
+use pubgrub::{
+    range::Range,
+    report::{DerivationTree, Derived, External},
+    term::Term,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
+
+/// Version requirements which were seen during resolution.
+///
+/// Indexed by the dependent and the dependency package names, then by the version of the dependent.
+pub type RequirementsCache = BTreeMap<(String, String), BTreeMap<SemanticVersion, String>>;
+
+/// How the organization which we resolve for is called in reports.
+const ORGANIZATION_IN_REPORT: &str = "organization";
+
+/// A requirement of a package on another package which took part in a conflict.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ConflictingRequirement {
+    /// Name of the package which has the requirement, `organization` for the root.
+    pub dependent: String,
+    /// Versions of the dependent which have the requirement, empty for the root.
+    pub dependent_versions: String,
+    /// Name of the package which is required.
+    pub dependency: String,
+    /// The version requirement as it was written, e.g. `^2.0.0`.
+    pub requirement: String,
+}
+
+impl Display for ConflictingRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.dependent_versions.is_empty() {
+            write!(
+                f,
+                "{} requires {} {}",
+                self.dependent, self.dependency, self.requirement
+            )
+        } else {
+            write!(
+                f,
+                "{} {} requires {} {}",
+                self.dependent, self.dependent_versions, self.dependency, self.requirement
+            )
+        }
+    }
+}
+
+/// A single step in the chain of incompatibilities which made a resolution fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConflictCause {
+    /// A package requires another package in some versions.
+    Requirement(ConflictingRequirement),
+    /// There are no versions of a package in the requested range.
+    NoVersions { package: String, versions: String },
+    /// The dependencies of a package could not be retrieved.
+    UnavailableDependencies { package: String, versions: String },
+    /// A package other than the root was tried to be picked first.
+    NotRoot { package: String, version: String },
+    /// A conclusion which was derived from other causes.
+    ///
+    /// Causes are left empty if the same conclusion was already explained earlier in the report.
+    Derived {
+        conclusion: String,
+        causes: Vec<ConflictCause>,
+    },
+}
+
+impl ConflictCause {
+    fn render(&self, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth);
+        match self {
+            Self::Requirement(requirement) => {
+                output.push_str(&format!("{indent}- {requirement}\n"));
+            }
+            Self::NoVersions { package, versions } => {
+                output.push_str(&format!(
+                    "{indent}- there is no version of {package} in {versions}\n"
+                ));
+            }
+            Self::UnavailableDependencies { package, versions } => {
+                output.push_str(&format!(
+                    "{indent}- dependencies of {package} {versions} could not be retrieved\n"
+                ));
+            }
+            Self::NotRoot { package, version } => {
+                output.push_str(&format!("{indent}- {package} {version} is not the root\n"));
+            }
+            Self::Derived { conclusion, causes } if causes.is_empty() => {
+                output.push_str(&format!("{indent}- {conclusion} (explained above)\n"));
+            }
+            Self::Derived { conclusion, causes } => {
+                output.push_str(&format!("{indent}- {conclusion}, because\n"));
+                for cause in causes {
+                    cause.render(depth + 1, output);
+                }
+            }
+        }
+    }
+
+    fn collect_requirements(&self, requirements: &mut BTreeSet<ConflictingRequirement>) {
+        match self {
+            Self::Requirement(requirement) => {
+                requirements.insert(requirement.clone());
+            }
+            Self::Derived { causes, .. } => {
+                for cause in causes {
+                    cause.collect_requirements(requirements);
+                }
+            }
+            Self::NoVersions { .. }
+            | Self::UnavailableDependencies { .. }
+            | Self::NotRoot { .. } => {}
+        }
+    }
+}
+
+/// A structured explanation of why dependencies could not be resolved.
+///
+/// Renders as an indented tree with [`Display`] and serializes to data for other front ends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConflictReport {
+    /// Every requirement which takes part in the conflict.
+    pub requirements: Vec<ConflictingRequirement>,
+    /// The chain of incompatibilities which made the resolution fail.
+    pub cause: ConflictCause,
+}
+
+impl ConflictReport {
+    /// Builds a report from the derivation tree of a failed pubgrub resolution.
+    ///
+    /// Requirements are named in the form they were written in if they are found in the cache.
+    pub fn from_derivation_tree(
+        tree: &DerivationTree<String, SemanticVersion>,
+        requirements_cache: &RequirementsCache,
+    ) -> Self {
+        let mut builder = ReportBuilder {
+            requirements_cache,
+            explained: BTreeSet::new(),
+        };
+        let cause = builder.cause(tree);
+        let mut requirements = BTreeSet::new();
+        cause.collect_requirements(&mut requirements);
+        Self {
+            requirements: requirements.into_iter().collect(),
+            cause,
+        }
+    }
+}
+
+impl Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        self.cause.render(0, &mut output);
+        write!(f, "{}", output.trim_end())
+    }
+}
+
+struct ReportBuilder<'cache> {
+    requirements_cache: &'cache RequirementsCache,
+    // Shared ids of derived incompatibilities which were already explained.
+    explained: BTreeSet<usize>,
+}
+
+impl<'cache> ReportBuilder<'cache> {
+    fn cause(&mut self, tree: &DerivationTree<String, SemanticVersion>) -> ConflictCause {
+        match tree {
+            DerivationTree::External(external) => self.external(external),
+            DerivationTree::Derived(derived) => self.derived(derived),
+        }
+    }
+
+    fn derived(&mut self, derived: &Derived<String, SemanticVersion>) -> ConflictCause {
+        let conclusion = Self::conclusion(derived);
+        if let Some(shared_id) = derived.shared_id {
+            if !self.explained.insert(shared_id) {
+                return ConflictCause::Derived {
+                    conclusion,
+                    causes: vec![],
+                };
+            }
+        }
+        ConflictCause::Derived {
+            conclusion,
+            causes: vec![self.cause(&derived.cause1), self.cause(&derived.cause2)],
+        }
+    }
+
+    fn external(&self, external: &External<String, SemanticVersion>) -> ConflictCause {
+        match external {
+            External::NotRoot(package, version) => ConflictCause::NotRoot {
+                package: package_name(package),
+                version: version.to_string(),
+            },
+            External::NoVersions(package, range) => ConflictCause::NoVersions {
+                package: package_name(package),
+                versions: range.to_string(),
+            },
+            External::UnavailableDependencies(package, range) => {
+                ConflictCause::UnavailableDependencies {
+                    package: package_name(package),
+                    versions: range.to_string(),
+                }
+            }
+            External::FromDependencyOf(
+                dependent,
+                dependent_range,
+                dependency,
+                dependency_range,
+            ) => ConflictCause::Requirement(self.requirement(
+                dependent,
+                dependent_range,
+                dependency,
+                dependency_range,
+            )),
+        }
+    }
+
+    fn requirement(
+        &self,
+        dependent: &str,
+        dependent_range: &Range<SemanticVersion>,
+        dependency: &str,
+        dependency_range: &Range<SemanticVersion>,
+    ) -> ConflictingRequirement {
+        let seen: Vec<(&SemanticVersion, &String)> = self
+            .requirements_cache
+            .get(&(dependent.to_owned(), dependency.to_owned()))
+            .map(|versions| {
+                versions
+                    .iter()
+                    .filter(|(version, _)| dependent_range.contains(version))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let dependent_versions = if dependent == ORGANIZATION_NAME {
+            String::new()
+        } else if let [(version, _)] = seen.as_slice() {
+            version.to_string()
+        } else {
+            dependent_range.to_string()
+        };
+
+        let written_requirements: BTreeSet<&String> =
+            seen.iter().map(|(_, requirement)| *requirement).collect();
+        let requirement = if written_requirements.len() == 1 {
+            written_requirements
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect()
+        } else {
+            dependency_range.to_string()
+        };
+
+        ConflictingRequirement {
+            dependent: package_name(dependent),
+            dependent_versions,
+            dependency: package_name(dependency),
+            requirement,
+        }
+    }
+
+    fn conclusion(derived: &Derived<String, SemanticVersion>) -> String {
+        let mut terms: Vec<(&String, &Term<SemanticVersion>)> = derived.terms.iter().collect();
+        // Positive terms first, then by name for a stable output.
+        terms.sort_by_key(|(package, term)| (matches!(term, Term::Negative(_)), *package));
+
+        match terms.as_slice() {
+            [] => "dependencies could not be resolved".to_owned(),
+            [(package, Term::Positive(_))] if package.as_str() == ORGANIZATION_NAME => {
+                "dependencies of the organization could not be resolved".to_owned()
+            }
+            [(package, Term::Positive(range))] => {
+                format!("{} can not be used", subject(package, range))
+            }
+            [(package, Term::Negative(range))] => {
+                format!("{} is required", subject(package, range))
+            }
+            [(dependent, Term::Positive(dependent_range)), (dependency, Term::Negative(dependency_range))] =>
+            {
+                format!(
+                    "{} requires {}",
+                    subject(dependent, dependent_range),
+                    subject(dependency, dependency_range),
+                )
+            }
+            [(first, Term::Positive(first_range)), (second, Term::Positive(second_range))] => {
+                format!(
+                    "{} and {} are incompatible",
+                    subject(first, first_range),
+                    subject(second, second_range),
+                )
+            }
+            more => {
+                let terms = more
+                    .iter()
+                    .map(|(package, term)| match term {
+                        Term::Positive(range) => subject(package, range),
+                        Term::Negative(range) => format!("not {}", subject(package, range)),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{terms} are incompatible")
+            }
+        }
+    }
+}
+
+/// The name of a package as it is shown in reports, without a major version the solver may have attached.
+fn package_name(package: &str) -> String {
+    if package == ORGANIZATION_NAME {
+        return ORGANIZATION_IN_REPORT.to_owned();
+    }
+    package_name_without_major_version(package).to_owned()
+}
+
+/// A package and its versions as they are shown in reports, versions are left out for the organization.
+fn subject(package: &str, range: &Range<SemanticVersion>) -> String {
+    if package == ORGANIZATION_NAME {
+        return ORGANIZATION_IN_REPORT.to_owned();
+    }
+    format!("{} {range}", package_name(package))
+}
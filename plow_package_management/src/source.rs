@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Where a package comes from.
+///
+/// Written to the lock file in a similar form to Cargo.lock, e.g.
+/// `git+https://github.com/field33/plow-registry-index.git` for the public index.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PackageSource {
+    /// The public git index, identified by the url of its repository.
+    PublicIndex(String),
+    /// The private index of a registry, identified by the url of the registry.
+    PrivateIndex(String),
+    /// A field on the local file system, identified by its path.
+    Path(String),
+    /// An on-disk registry, identified by its root directory.
+    OnDiskRegistry(String),
+}
+
+impl Display for PackageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PublicIndex(url) => write!(f, "git+{url}"),
+            Self::PrivateIndex(url) => write!(f, "private+{url}"),
+            Self::Path(path) => write!(f, "path+{path}"),
+            Self::OnDiskRegistry(path) => write!(f, "disk+{path}"),
+        }
+    }
+}
+
+impl FromStr for PackageSource {
+    type Err = Error;
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let (kind, location) = source
+            .split_once('+')
+            .filter(|(_, location)| !location.is_empty())
+            .ok_or_else(|| anyhow!("Invalid package source `{source}`."))?;
+        let location = location.to_owned();
+        match kind {
+            "git" => Ok(Self::PublicIndex(location)),
+            "private" => Ok(Self::PrivateIndex(location)),
+            "path" => Ok(Self::Path(location)),
+            "disk" => Ok(Self::OnDiskRegistry(location)),
+            _ => Err(anyhow!("Unknown kind of package source `{kind}`.")),
+        }
+    }
+}
+
+impl TryFrom<String> for PackageSource {
+    type Error = Error;
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::from_str(&source)
+    }
+}
+
+impl From<PackageSource> for String {
+    fn from(source: PackageSource) -> Self {
+        source.to_string()
+    }
+}
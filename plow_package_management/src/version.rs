@@ -1,7 +1,9 @@
 use anyhow::{Error, Result};
 use pubgrub::range::Range;
 use semver::VersionReq;
+use std::cmp::Ordering;
 use std::convert::From;
+use std::hash::{Hash, Hasher};
 use std::{fmt::Display, str::FromStr};
 
 /// Macro to quickly generate a [`SemanticVersion`] from a string.
@@ -76,11 +78,51 @@ pub const fn semver_completeness(comparator: &semver::Comparator) -> SemanticVer
 }
 
 /// Our own semantic version type to use in pubgrub based resolver.
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+///
+/// Follows [SemVer 2.0](https://semver.org/) including pre-release identifiers and build metadata.
+/// Build metadata is carried along but ignored for equality, ordering and hashing as the spec requires.
+#[derive(Debug, Clone, Default)]
 pub struct SemanticVersion {
     major: u64,
     minor: u64,
     patch: u64,
+    pre: semver::Prerelease,
+    build: semver::BuildMetadata,
+}
+
+impl PartialEq for SemanticVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemanticVersion {}
+
+impl PartialOrd for SemanticVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemanticVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `Prerelease` already orders an empty pre-release above any non empty one.
+        (self.major, self.minor, self.patch, &self.pre).cmp(&(
+            other.major,
+            other.minor,
+            other.patch,
+            &other.pre,
+        ))
+    }
+}
+
+impl Hash for SemanticVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.pre.hash(state);
+    }
 }
 
 impl serde::Serialize for SemanticVersion {
@@ -110,9 +152,23 @@ impl SemanticVersion {
             major,
             minor,
             patch,
+            pre: semver::Prerelease::EMPTY,
+            build: semver::BuildMetadata::EMPTY,
         }
     }
 
+    /// Create a version with "major", "minor" and "patch" values and a pre-release identifier.
+    /// `version = major.minor.patch-pre`
+    ///
+    /// # Errors
+    /// Fails if the pre-release identifier is not valid in terms of [SemVer 2.0](https://semver.org/#spec-item-9).
+    pub fn new_pre_release(major: u64, minor: u64, patch: u64, pre: &str) -> Result<Self> {
+        Ok(Self {
+            pre: semver::Prerelease::new(pre)?,
+            ..Self::new(major, minor, patch)
+        })
+    }
+
     pub const fn as_sum(&self) -> u64 {
         self.major + self.minor + self.patch
     }
@@ -124,22 +180,64 @@ impl SemanticVersion {
 
     #[must_use]
     /// Bump the patch number of a version.
-    pub const fn bump_patch(self) -> Self {
+    ///
+    /// Pre-release and build metadata are dropped.
+    pub const fn bump_patch(&self) -> Self {
         Self::new(self.major, self.minor, self.patch + 1)
     }
 
     #[must_use]
     /// Bump the minor number of a version.
-    pub const fn bump_minor(self) -> Self {
+    ///
+    /// Pre-release and build metadata are dropped.
+    pub const fn bump_minor(&self) -> Self {
         Self::new(self.major, self.minor + 1, 0)
     }
 
     #[must_use]
     /// Bump the major number of a version.
-    pub const fn bump_major(self) -> Self {
+    ///
+    /// Pre-release and build metadata are dropped.
+    pub const fn bump_major(&self) -> Self {
         Self::new(self.major + 1, 0, 0)
     }
 
+    #[must_use]
+    /// The `(major, minor, patch)` triple of a version without pre-release or build metadata.
+    pub const fn major_minor_patch(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    #[must_use]
+    /// The major version which the version is compatible within.
+    ///
+    /// Like in Cargo, the left most non zero number is the major version, `0.3` for `0.3.1` or `0.0.4` for `0.0.4`.
+    pub fn compatible_major_version(&self) -> String {
+        match (self.major, self.minor) {
+            (0, 0) => format!("0.0.{}", self.patch),
+            (0, minor) => format!("0.{minor}"),
+            (major, _) => major.to_string(),
+        }
+    }
+
+    #[must_use]
+    /// Returns `true` if the version has a pre-release identifier, e.g. `1.0.0-rc.1`.
+    pub fn is_pre_release(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    #[must_use]
+    /// The pre-release identifier of a version, empty for releases.
+    pub const fn pre_release(&self) -> &semver::Prerelease {
+        &self.pre
+    }
+
+    #[must_use]
+    /// The build metadata of a version, empty if there is none.
+    pub const fn build_metadata(&self) -> &semver::BuildMetadata {
+        &self.build
+    }
+
     #[must_use]
     /// Returns `true` if the patch field of the version is 0.
     pub const fn is_patch_zero(&self) -> bool {
@@ -161,7 +259,14 @@ impl SemanticVersion {
 
 impl Display for SemanticVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
     }
 }
 
@@ -176,7 +281,31 @@ impl From<(u64, u64, u64)> for SemanticVersion {
 // Convert a version into a tuple (major, minor, patch).
 impl From<SemanticVersion> for (u64, u64, u64) {
     fn from(v: SemanticVersion) -> Self {
-        (v.major, v.minor, v.patch)
+        v.major_minor_patch()
+    }
+}
+
+impl From<semver::Version> for SemanticVersion {
+    fn from(version: semver::Version) -> Self {
+        Self {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            pre: version.pre,
+            build: version.build,
+        }
+    }
+}
+
+impl From<SemanticVersion> for semver::Version {
+    fn from(version: SemanticVersion) -> Self {
+        Self {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            pre: version.pre,
+            build: version.build,
+        }
     }
 }
 
@@ -185,6 +314,11 @@ impl TryFrom<&str> for SemanticVersion {
     type Error = Error;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
+        // A complete bare version, the only form which may carry build metadata.
+        if let Ok(version) = semver::Version::parse(s) {
+            return Ok(version.into());
+        }
+
         let req = VersionReq::parse(s)?;
 
         Ok(Self {
@@ -192,6 +326,8 @@ impl TryFrom<&str> for SemanticVersion {
             major: req.comparators[0].major,
             minor: req.comparators[0].minor.unwrap_or(0),
             patch: req.comparators[0].patch.unwrap_or(0),
+            pre: req.comparators[0].pre.clone(),
+            build: semver::BuildMetadata::EMPTY,
         })
     }
 }
@@ -225,11 +361,29 @@ impl FromStr for SemanticVersion {
 
 // Implement the trait `Version` for us to use this type with pubgrub's DependencyProvider trait.
 impl pubgrub::version::Version for SemanticVersion {
+    /// `0.0.0-0` is the smallest possible version since pre-releases precede their release.
     fn lowest() -> Self {
-        Self::zero()
+        // A single numeric zero is the smallest pre-release identifier possible.
+        #[allow(clippy::unwrap_used)]
+        Self::new_pre_release(0, 0, 0, "0").unwrap()
     }
+    /// The smallest version which is strictly greater than this one.
+    ///
+    /// For `1.2.3` this is `1.2.4-0` and for `1.2.3-rc.1` this is `1.2.3-rc.1.0`.
     fn bump(&self) -> Self {
-        self.bump_patch()
+        if self.is_pre_release() {
+            #[allow(clippy::unwrap_used)]
+            // Appending a numeric identifier to a valid pre-release keeps it valid.
+            return Self::new_pre_release(
+                self.major,
+                self.minor,
+                self.patch,
+                &format!("{}.0", self.pre),
+            )
+            .unwrap();
+        }
+        #[allow(clippy::unwrap_used)]
+        Self::new_pre_release(self.major, self.minor, self.patch + 1, "0").unwrap()
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::{
-    lock::LockFile,
+    lock::{LockFile, LockOptions},
     metadata::OntologyMetadata,
-    package::{RetrievedPackageSet, RetrievedPackageVersion},
+    package::{major_version_ontology_iri, RetrievedPackageSet, RetrievedPackageVersion},
     registry::Registry,
 };
 use anyhow::anyhow;
@@ -90,7 +90,10 @@ impl OntologyWorkspace {
                 ontology_metadata.into(),
                 registry,
                 workspace_root,
-                true,
+                LockOptions {
+                    respect_existing_lock_file: true,
+                    ..LockOptions::default()
+                },
             )?,
         })
     }
@@ -126,14 +129,29 @@ impl OntologyWorkspaceLocked {
                 .map_err(|_| anyhow!("Unable to parse dependency contents as ontology"))?;
             let metadata = OntologyMetadata::try_from(&document)?;
 
-            let dependency_file_path = dependency_dir
-                .clone()
-                .join(format!("{}.ttl", metadata.canonical_prefix));
+            // Multiple major versions of a dependency need a file each.
+            let has_multiple_versions =
+                self.lockfile
+                    .locked_dependencies
+                    .packages
+                    .iter()
+                    .any(|other| {
+                        other.package_name == dep.package_name && other.version != dep.version
+                    });
+            let dependency_file_name = if has_multiple_versions {
+                format!("{}-{}.ttl", metadata.canonical_prefix, dep.version)
+            } else {
+                format!("{}.ttl", metadata.canonical_prefix)
+            };
+            let dependency_file_path = dependency_dir.clone().join(dependency_file_name);
             let mut file = File::create(&dependency_file_path)?;
             file.write_all(contents_str.as_bytes())?;
 
             retrieved_deps.push(RetrievedPackageVersion {
-                ontology_iri: metadata.root_prefix,
+                ontology_iri: major_version_ontology_iri(
+                    &metadata.root_prefix,
+                    &metadata.package_version,
+                ),
                 package: dep.clone(),
                 file_path: dependency_file_path,
             });
@@ -0,0 +1,228 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::tests_filepath;
+use git2::Repository;
+use plow_package_management::{
+    package::PackageVersion,
+    registry::{on_disk_git::OnDiskGitRegistry, Registry},
+};
+use tempdir::TempDir;
+
+const TOP_LEVEL_ARTIFACT: &str = "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9";
+
+/// The contents of a version of `@namespace/top_level`.
+fn artifact(version: &str) -> String {
+    std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT))
+        .unwrap()
+        .replace("\"0.0.1\"", &format!("\"{version}\""))
+}
+
+fn package_version(version: &str) -> PackageVersion {
+    PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: version.to_owned(),
+    }
+}
+
+/// An empty bare repository which clones check out `main` of, like the public index.
+fn remote(name: &str) -> TempDir {
+    let remote = TempDir::new(name).unwrap();
+    Repository::init_bare(remote.path())
+        .unwrap()
+        .set_head("refs/heads/main")
+        .unwrap();
+    remote
+}
+
+/// Message of the last commit on the main branch of the repository.
+fn last_commit_message(repository_path: &std::path::Path) -> String {
+    last_commit_message_on(repository_path, "main")
+}
+
+fn last_commit_message_on(repository_path: &std::path::Path, branch: &str) -> String {
+    Repository::open(repository_path)
+        .unwrap()
+        .find_reference(&format!("refs/heads/{branch}"))
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .message()
+        .unwrap()
+        .to_owned()
+}
+
+/// Paths of the files in the last commit on the main branch of the repository.
+fn committed_files(repository_path: &std::path::Path) -> Vec<String> {
+    let repository = Repository::open(repository_path).unwrap();
+    let tree = repository
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .peel_to_tree()
+        .unwrap();
+    let mut files = vec![];
+    tree.walk(git2::TreeWalkMode::PreOrder, |directory, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            files.push(format!("{directory}{}", entry.name().unwrap()));
+        }
+        git2::TreeWalkResult::Ok
+    })
+    .unwrap();
+    files
+}
+
+#[test]
+fn submissions_are_committed_and_pushed() {
+    let remote = remote("submissions_are_committed_and_pushed_remote");
+    let remote_url = remote.path().to_str().unwrap();
+    let clones = TempDir::new("submissions_are_committed_and_pushed").unwrap();
+
+    // The remote is empty before the first submission.
+    let first =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("first"), None)
+            .unwrap();
+    first.submit_package(&artifact("0.0.1")).unwrap();
+    assert_eq!(
+        last_commit_message(remote.path()),
+        "Add @namespace/top_level 0.0.1"
+    );
+
+    // Only the index file is committed, artifacts are not part of the index.
+    assert_eq!(
+        committed_files(remote.path()),
+        vec!["index/@namespace/top_level.json"]
+    );
+    assert!(Repository::open(clones.path().join("first"))
+        .unwrap()
+        .statuses(None)
+        .unwrap()
+        .is_empty());
+
+    let second =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("second"), None)
+            .unwrap();
+    assert!(second
+        .get_package_version_metadata(&package_version("0.0.1"))
+        .is_ok());
+
+    // Submissions go on top of what others pushed in the meantime.
+    second.submit_package(&artifact("0.0.2")).unwrap();
+    assert!(first
+        .get_package_version_metadata(&package_version("0.0.2"))
+        .is_err());
+    let duplicate = first.submit_package(&artifact("0.0.2"));
+    assert!(duplicate.is_err());
+    assert!(first
+        .get_package_version_metadata(&package_version("0.0.2"))
+        .is_ok());
+    assert_eq!(
+        last_commit_message(remote.path()),
+        "Add @namespace/top_level 0.0.2"
+    );
+}
+
+#[test]
+fn submissions_which_can_not_be_pushed_are_rolled_back() {
+    let remote = remote("submissions_which_can_not_be_pushed_are_rolled_back_remote");
+    let remote_url = remote.path().to_str().unwrap();
+    let clones = TempDir::new("submissions_which_can_not_be_pushed_are_rolled_back").unwrap();
+    let clone_path = clones.path().join("clone");
+
+    let registry =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, &clone_path, None).unwrap();
+    registry.submit_package(&artifact("0.0.1")).unwrap();
+
+    // Fetching still works, only pushing fails.
+    let repository = Repository::open(&clone_path).unwrap();
+    repository
+        .remote_set_pushurl(
+            "origin",
+            Some(clones.path().join("missing").to_str().unwrap()),
+        )
+        .unwrap();
+    assert!(registry.submit_package(&artifact("0.0.2")).is_err());
+
+    assert!(registry
+        .get_package_version_metadata(&package_version("0.0.2"))
+        .is_err());
+    assert!(registry
+        .get_package_version_metadata(&package_version("0.0.1"))
+        .is_ok());
+    assert_eq!(
+        last_commit_message(&clone_path),
+        "Add @namespace/top_level 0.0.1"
+    );
+    assert!(repository.statuses(None).unwrap().is_empty());
+    assert_eq!(
+        std::fs::read_dir(clone_path.join("artifacts"))
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn registries_can_be_on_another_branch() {
+    let remote = remote("registries_can_be_on_another_branch_remote");
+    let remote_url = remote.path().to_str().unwrap();
+    let clones = TempDir::new("registries_can_be_on_another_branch").unwrap();
+
+    let first =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("first"), None)
+            .unwrap()
+            .with_branch("index");
+    first.submit_package(&artifact("0.0.1")).unwrap();
+    assert_eq!(
+        last_commit_message_on(remote.path(), "index"),
+        "Add @namespace/top_level 0.0.1"
+    );
+    assert!(Repository::open(remote.path())
+        .unwrap()
+        .find_reference("refs/heads/main")
+        .is_err());
+
+    let second =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("second"), None)
+            .unwrap()
+            .with_branch("index");
+    second.yank(&package_version("0.0.1"), true).unwrap();
+    assert_eq!(
+        last_commit_message_on(remote.path(), "index"),
+        "Yank @namespace/top_level 0.0.1"
+    );
+}
+
+#[test]
+fn yanks_are_committed_and_pushed() {
+    let remote = remote("yanks_are_committed_and_pushed_remote");
+    let remote_url = remote.path().to_str().unwrap();
+    let clones = TempDir::new("yanks_are_committed_and_pushed").unwrap();
+
+    let first =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("first"), None)
+            .unwrap();
+    first.submit_package(&artifact("0.0.1")).unwrap();
+    let second =
+        OnDiskGitRegistry::initialize_from_remote(remote_url, clones.path().join("second"), None)
+            .unwrap();
+
+    second.yank(&package_version("0.0.1"), true).unwrap();
+    assert_eq!(
+        last_commit_message(remote.path()),
+        "Yank @namespace/top_level 0.0.1"
+    );
+    // The yank goes on top of the latest state of the remote.
+    first.yank(&package_version("0.0.1"), false).unwrap();
+    assert!(!first.is_yanked(&package_version("0.0.1")).unwrap());
+    assert_eq!(
+        last_commit_message(remote.path()),
+        "Unyank @namespace/top_level 0.0.1"
+    );
+
+    assert!(first.yank(&package_version("0.0.2"), true).is_err());
+    assert!(Repository::open(clones.path().join("first"))
+        .unwrap()
+        .statuses(None)
+        .unwrap()
+        .is_empty());
+}
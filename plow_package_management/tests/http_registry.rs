@@ -0,0 +1,314 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::tests_filepath;
+use plow_package_management::{
+    checksum,
+    package::PackageVersion,
+    registry::{
+        http::{field_hash, HttpRegistry, HttpRegistryError},
+        Registry,
+    },
+};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+const TOP_LEVEL_ARTIFACT: &str = "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9";
+
+/// A request which the mock registry received.
+struct ReceivedRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl ReceivedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response of the mock registry to requests of which the method and path start with the route.
+///
+/// `{url}` in the body is replaced with the url of the mock registry.
+struct Route {
+    route: String,
+    status: u16,
+    body: String,
+}
+
+fn route(route: &str, status: u16, body: &str) -> Route {
+    Route {
+        route: route.to_owned(),
+        status,
+        body: body.to_owned(),
+    }
+}
+
+fn read_request(stream: &mut impl Read) -> ReceivedRequest {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let path = parts.next().unwrap_or_default().to_owned();
+
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+    }
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .map_or(0, |(_, value)| value.parse::<usize>().unwrap());
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    ReceivedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
+
+/// Serves the routes on a local port, returns the url of the mock registry and the requests it receives.
+fn serve(routes: Vec<Route>) -> (String, Arc<Mutex<Vec<ReceivedRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(vec![]));
+
+    let server_url = url.clone();
+    let server_received = Arc::clone(&received);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let request = read_request(&mut stream);
+            let method_and_path = format!("{} {}", request.method, request.path);
+            let (status, body) = routes
+                .iter()
+                .find(|route| method_and_path.starts_with(&route.route))
+                .map_or((404, String::new()), |route| {
+                    (route.status, route.body.replace("{url}", &server_url))
+                });
+            // Recorded before responding so that it is there once the client has the response.
+            server_received.lock().unwrap().push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+
+    (url, received)
+}
+
+#[test]
+fn sync_private_index_and_retrieve_packages() {
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+    let cksum = checksum::compute(artifact.as_bytes());
+    let hash = field_hash("@namespace/top_level", "0.0.1");
+    let (url, received) = serve(vec![
+        route(
+            "POST /v1/index/private/sync",
+            200,
+            &format!(
+                r#"{{"status":"success","data":{{"indexes":[{{"name":"@namespace/top_level","version":"0.0.1","cksum":"{cksum}","ontology_iri":null,"deps":[]}}]}}}}"#
+            ),
+        ),
+        route(
+            &format!("GET /v1/artifact/signed-url-by-field-hash/{hash}"),
+            200,
+            r#"{"status":"success","data":{"url":"{url}/artifacts/top_level"}}"#,
+        ),
+        route("GET /artifacts/top_level", 200, &artifact),
+    ]);
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.sync_private_index().unwrap();
+
+    let versions = registry
+        .all_available_versions_of_a_package("@namespace/top_level".to_owned())
+        .unwrap();
+    assert_eq!(versions.len(), 1);
+    assert!(versions[0].private);
+    assert_eq!(versions[0].cksum.as_deref(), Some(cksum.as_str()));
+
+    let package_version = PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: "0.0.1".to_owned(),
+    };
+    let contents = registry.retrieve_package(&package_version).unwrap();
+    assert_eq!(contents, artifact.as_bytes());
+
+    let received = received.lock().unwrap();
+    assert_eq!(received[0].header("authorization"), Some("Basic secret"));
+    assert!(received[0].body.contains("existing_local_field_hashes"));
+    // Private fields need authentication to be downloaded.
+    assert_eq!(received[1].header("authorization"), Some("Basic secret"));
+}
+
+#[test]
+fn retrieving_a_package_which_does_not_match_its_checksum_fails() {
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+    let (url, _) = serve(vec![
+        route(
+            "POST /v1/index/private/sync",
+            200,
+            r#"{"status":"success","data":{"indexes":[{"name":"@namespace/top_level","version":"0.0.1","cksum":"0000","ontology_iri":null,"deps":[]}]}}"#,
+        ),
+        route(
+            "GET /v1/artifact/signed-url-by-field-hash/",
+            200,
+            r#"{"status":"success","data":{"url":"{url}/artifacts/top_level"}}"#,
+        ),
+        route("GET /artifacts/top_level", 200, &artifact),
+    ]);
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.sync_private_index().unwrap();
+
+    let error = registry
+        .retrieve_package(&PackageVersion {
+            package_name: "@namespace/top_level".to_owned(),
+            version: "0.0.1".to_owned(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HttpRegistryError>(),
+        Some(HttpRegistryError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn syncing_without_authorization_fails() {
+    let (url, _) = serve(vec![route(
+        "POST /v1/index/private/sync",
+        401,
+        r#"{"status":"error","message":"Unauthorized"}"#,
+    )]);
+
+    let registry = HttpRegistry::new(&url);
+    assert!(matches!(
+        registry.sync_private_index(),
+        Err(HttpRegistryError::Unauthorized { .. })
+    ));
+}
+
+#[test]
+fn submit_package() {
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+    let (url, received) = serve(vec![route(
+        "POST /v1/field/submit",
+        200,
+        r#"{"status":"success","data":null}"#,
+    )]);
+
+    let registry = HttpRegistry::new(&url)
+        .with_token("secret")
+        .submit_privately(true);
+    let submitted = registry.submit_package(&artifact).unwrap();
+    assert_eq!(submitted.package_name, "@namespace/top_level");
+    assert!(submitted.private);
+    assert_eq!(
+        submitted.cksum.as_deref(),
+        Some(checksum::compute(artifact.as_bytes()).as_str())
+    );
+
+    // A submitted package is available from the registry.
+    let package_version = PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: "0.0.1".to_owned(),
+    };
+    assert!(registry
+        .get_package_version_metadata(&package_version)
+        .is_ok());
+
+    let received = received.lock().unwrap();
+    assert_eq!(received[0].path, "/v1/field/submit");
+    assert_eq!(received[0].header("authorization"), Some("Basic secret"));
+    assert!(received[0].body.contains("name=\"public\"\r\n\r\nfalse"));
+    assert!(received[0].body.contains("name=\"field\""));
+}
+
+#[test]
+fn rejected_submissions_fail_and_dry_runs_are_not_kept() {
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+    let (url, received) = serve(vec![
+        route(
+            "POST /v1/field/submit?dry-run=true",
+            200,
+            r#"{"status":"success","data":null}"#,
+        ),
+        route(
+            "POST /v1/field/submit",
+            400,
+            r#"{"status":"fail","data":{"failures":["The field already exists."]}}"#,
+        ),
+    ]);
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    let error = registry.submit_package(&artifact).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HttpRegistryError>(),
+        Some(HttpRegistryError::Rejected(reason)) if reason == "The field already exists."
+    ));
+
+    let registry = HttpRegistry::new(&url).with_token("secret").dry_run(true);
+    registry.submit_package(&artifact).unwrap();
+    assert!(registry
+        .all_available_versions_of_a_package("@namespace/top_level".to_owned())
+        .unwrap()
+        .is_empty());
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn yank_and_unyank() {
+    let hash = field_hash("@namespace/top_level", "0.0.1");
+    let (url, received) = serve(vec![
+        route(
+            &format!("DELETE /v1/field/{hash}/yank"),
+            200,
+            r#"{"status":"success","data":null}"#,
+        ),
+        route(
+            &format!("PUT /v1/field/{hash}/unyank"),
+            403,
+            r#"{"status":"fail","data":"Only owners of the field can take a yank back."}"#,
+        ),
+    ]);
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    let package_version = PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: "0.0.1".to_owned(),
+    };
+    registry.yank(&package_version, true).unwrap();
+    let error = registry.yank(&package_version, false).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HttpRegistryError>(),
+        Some(HttpRegistryError::Rejected(reason)) if reason == "Only owners of the field can take a yank back."
+    ));
+
+    let received = received.lock().unwrap();
+    assert_eq!(received[0].header("authorization"), Some("Basic secret"));
+}
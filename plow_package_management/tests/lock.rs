@@ -2,9 +2,16 @@
 #![allow(unused_assignments)]
 use camino::Utf8PathBuf;
 use plow_package_management::{
-    lock::LOCK_FILE_NAME, registry::on_disk::OnDiskRegistry, workspace::OntologyWorkspace,
+    lock::{LockFile, LockFileError, LockOptions, PackageInLockFile, LOCK_FILE_NAME},
+    package::{OrganizationToResolveFor, PackageVersion, PackageVersionWithRegistryMetadata},
+    registry::{in_memory::InMemoryRegistry, on_disk::OnDiskRegistry},
+    resolve::Dependency,
+    source::PackageSource,
+    version::SemanticVersion,
+    workspace::OntologyWorkspace,
 };
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[allow(dead_code)]
 #[ignore = "Creates problems with Bazel: The test writes a file but there are privilege issues when running under Bazel. Could be fixed later."]
@@ -82,3 +89,260 @@ fn lock_read_write() {
             assert_eq!(a.package.version, b.package.version);
         });
 }
+
+#[test]
+fn lock_entries_are_reused_only_from_the_same_source() {
+    let public_index =
+        PackageSource::PublicIndex("https://github.com/field33/plow-registry-index.git".to_owned());
+    let private_index = PackageSource::PrivateIndex("https://api.plow.pm".to_owned());
+
+    let mut registry = InMemoryRegistry::default();
+    registry.insert(
+        PackageVersion::new("@cities/Berlin".to_owned(), "1.0.0".to_owned()),
+        PackageVersionWithRegistryMetadata {
+            package_name: "@cities/Berlin".to_owned(),
+            version: SemanticVersion::from_str("1.0.0").unwrap(),
+            ontology_iri: None,
+            dependencies: vec![],
+            cksum: None,
+            private: false,
+            source: Some(public_index.clone()),
+            keywords: vec![],
+            categories: vec![],
+            yanked: false,
+        },
+        "".to_owned(),
+    );
+
+    let locked = |version: &str, source: Option<PackageSource>| PackageInLockFile {
+        root: false,
+        name: "@cities/Berlin".to_owned(),
+        version: version.to_owned(),
+        source,
+        ontology_iri: None,
+        cksum: None,
+        dependencies: vec![],
+    };
+
+    assert!(locked("1.0.0", Some(public_index.clone())).source_matches(&registry));
+    // Lock files before sources were recorded.
+    assert!(locked("1.0.0", None).source_matches(&registry));
+    assert!(!locked("1.0.0", Some(private_index)).source_matches(&registry));
+    assert!(!locked("1.1.0", Some(public_index)).source_matches(&registry));
+}
+
+#[test]
+fn lock_entries_record_their_source() {
+    for source in [
+        "git+https://github.com/field33/plow-registry-index.git",
+        "private+https://api.plow.pm",
+        "path+src/@cities/Berlin.ttl",
+        "disk+/tmp/registry",
+    ] {
+        assert_eq!(PackageSource::from_str(source).unwrap().to_string(), source);
+    }
+    assert!(PackageSource::from_str("https://api.plow.pm").is_err());
+    assert!(PackageSource::from_str("ftp+somewhere").is_err());
+    assert!(PackageSource::from_str("git+").is_err());
+
+    let serialized = toml::to_string(&PackageInLockFile {
+        root: false,
+        name: "@cities/Berlin".to_owned(),
+        version: "1.0.0".to_owned(),
+        source: Some(PackageSource::PrivateIndex(
+            "https://api.plow.pm".to_owned(),
+        )),
+        ontology_iri: None,
+        cksum: None,
+        dependencies: vec![],
+    })
+    .unwrap();
+    assert!(serialized.contains(r#"source = "private+https://api.plow.pm""#));
+    let deserialized: PackageInLockFile = toml::from_str(&serialized).unwrap();
+    assert_eq!(
+        deserialized.source,
+        Some(PackageSource::PrivateIndex(
+            "https://api.plow.pm".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn lock_entries_which_disagree_with_the_registry_on_the_checksum_fail() {
+    let mut registry = InMemoryRegistry::default();
+    registry.insert(
+        PackageVersion::new("@cities/Berlin".to_owned(), "1.0.0".to_owned()),
+        PackageVersionWithRegistryMetadata {
+            package_name: "@cities/Berlin".to_owned(),
+            version: SemanticVersion::from_str("1.0.0").unwrap(),
+            ontology_iri: None,
+            dependencies: vec![],
+            cksum: Some("ab12".to_owned()),
+            private: false,
+            source: None,
+            keywords: vec![],
+            categories: vec![],
+            yanked: false,
+        },
+        "".to_owned(),
+    );
+
+    let locked = |cksum: Option<&str>| PackageInLockFile {
+        root: false,
+        name: "@cities/Berlin".to_owned(),
+        version: "1.0.0".to_owned(),
+        source: None,
+        ontology_iri: None,
+        cksum: cksum.map(ToOwned::to_owned),
+        dependencies: vec![],
+    };
+
+    assert!(locked(Some("ab12")).verify_cksum(&registry).is_ok());
+    assert!(locked(Some("AB12")).verify_cksum(&registry).is_ok());
+    assert!(locked(None).verify_cksum(&registry).is_ok());
+    assert!(matches!(
+        locked(Some("cd34")).verify_cksum(&registry),
+        Err(LockFileError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn locked_resolutions_fail_if_the_lock_file_would_change() {
+    let mut registry = InMemoryRegistry::default();
+    for version in ["1.0.0", "1.1.0"] {
+        registry.insert(
+            PackageVersion::new("@cities/Berlin".to_owned(), version.to_owned()),
+            PackageVersionWithRegistryMetadata {
+                package_name: "@cities/Berlin".to_owned(),
+                version: SemanticVersion::from_str(version).unwrap(),
+                ontology_iri: None,
+                dependencies: vec![],
+                cksum: None,
+                private: false,
+                source: None,
+                keywords: vec![],
+                categories: vec![],
+                yanked: false,
+            },
+            "".to_owned(),
+        );
+    }
+
+    let workspace = tempdir::TempDir::new("plow_locked").unwrap();
+    let workspace_root = Utf8PathBuf::from_path_buf(workspace.path().to_path_buf()).unwrap();
+    let lock = |locked: bool| {
+        LockFile::lock_with_registry(
+            OrganizationToResolveFor {
+                package_name: "@root/root".to_owned(),
+                package_version: SemanticVersion::default(),
+                dependencies: vec![Dependency::try_from("@cities/Berlin ^1.0.0").unwrap()],
+            },
+            &registry,
+            Some(workspace_root.clone()),
+            LockOptions {
+                respect_existing_lock_file: true,
+                locked,
+                ..LockOptions::default()
+            },
+        )
+    };
+    let locked = |name: &str, version: &str| PackageInLockFile {
+        root: false,
+        name: name.to_owned(),
+        version: version.to_owned(),
+        source: None,
+        ontology_iri: None,
+        cksum: None,
+        dependencies: vec![],
+    };
+
+    assert!(matches!(
+        lock(true).unwrap_err().downcast::<LockFileError>(),
+        Ok(LockFileError::NotFound)
+    ));
+
+    LockFile::write(
+        Some(workspace_root.clone()),
+        &[locked("@cities/Berlin", "1.0.0")],
+    )
+    .unwrap();
+    let resolved = lock(true).unwrap();
+    assert_eq!(
+        resolved.locked_dependencies.packages,
+        vec![PackageVersion::new(
+            "@cities/Berlin".to_owned(),
+            "1.0.0".to_owned()
+        )]
+    );
+    assert!(!LockFile::would_change(
+        Some(workspace_root.clone()),
+        &[locked("@cities/Berlin", "1.0.0")]
+    )
+    .unwrap());
+    assert!(LockFile::would_change(
+        Some(workspace_root.clone()),
+        &[locked("@cities/Berlin", "1.1.0")]
+    )
+    .unwrap());
+
+    LockFile::write(
+        Some(workspace_root.clone()),
+        &[locked("@cities/Paris", "1.0.0")],
+    )
+    .unwrap();
+    assert!(matches!(
+        lock(true).unwrap_err().downcast::<LockFileError>(),
+        Ok(LockFileError::WouldChange(packages)) if packages == vec!["@cities/Berlin 1.1.0".to_owned()]
+    ));
+    assert!(lock(false).is_ok());
+}
+
+#[test]
+fn lock_entries_describe_themselves_as_registry_metadata() {
+    let private_index = PackageSource::PrivateIndex("https://api.plow.pm".to_owned());
+    let lock_file = vec![
+        PackageInLockFile {
+            root: false,
+            name: "@cities/Berlin".to_owned(),
+            version: "1.0.0".to_owned(),
+            source: Some(private_index.clone()),
+            ontology_iri: None,
+            cksum: Some("ab12".to_owned()),
+            dependencies: vec!["@countries/Germany".to_owned()],
+        },
+        PackageInLockFile {
+            root: false,
+            name: "@countries/Germany".to_owned(),
+            version: "2.3.0".to_owned(),
+            source: None,
+            ontology_iri: Some("http://field33.com/ontologies/@countries/Germany/2/".to_owned()),
+            cksum: None,
+            dependencies: vec![],
+        },
+    ];
+
+    let metadata = lock_file[0].to_registry_metadata(&lock_file).unwrap();
+    assert!(metadata.private);
+    assert_eq!(metadata.source, Some(private_index));
+    assert_eq!(metadata.cksum, Some("ab12".to_owned()));
+    assert_eq!(metadata.dependencies.len(), 1);
+    assert_eq!(metadata.dependencies[0].full_name, "@countries/Germany");
+    assert!(metadata.dependencies[0]
+        .version_range
+        .contains(&SemanticVersion::from_str("2.3.0").unwrap()));
+    assert!(!metadata.dependencies[0]
+        .version_range
+        .contains(&SemanticVersion::from_str("2.3.1").unwrap()));
+
+    // The registry knows the ontology IRI of the package, the lock file the one of its major version.
+    let metadata = lock_file[1].to_registry_metadata(&lock_file[..1]).unwrap();
+    assert_eq!(
+        metadata.ontology_iri.as_deref(),
+        Some("http://field33.com/ontologies/@countries/Germany/")
+    );
+    assert_eq!(
+        metadata.major_version_ontology_iri(),
+        lock_file[1].ontology_iri
+    );
+    assert!(lock_file[0].to_registry_metadata(&lock_file[..1]).is_err());
+}
@@ -3,7 +3,7 @@ mod common;
 use crate::common::tests_filepath;
 use plow_package_management::package::PackageVersion;
 use plow_package_management::registry::on_disk::OnDiskRegistry;
-use plow_package_management::registry::Registry;
+use plow_package_management::registry::{PackageFilter, PackageListing, Registry};
 use tempdir::TempDir;
 
 #[test]
@@ -14,6 +14,29 @@ fn verify_integrity() {
     assert!(registry.unwrap().verify_integrity().is_ok());
 }
 
+#[test]
+fn verify_integrity_of_a_corrupted_artifact_fails() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("verify_integrity_of_a_corrupted_artifact_fails")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let ontology_package = std::fs::read_to_string(tests_filepath(
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
+    ))?;
+    let submitted = registry.submit_package(&ontology_package)?;
+    assert!(registry.verify_integrity().is_ok());
+
+    std::fs::write(
+        temp_dir
+            .path()
+            .join("artifacts")
+            .join(submitted.cksum.unwrap()),
+        ontology_package.replace("top_level", "tampered"),
+    )?;
+    assert!(registry.verify_integrity().is_err());
+
+    Ok(())
+}
+
 #[test]
 fn simple_hierarchy_registry() -> Result<(), anyhow::Error> {
     let registry_path = tests_filepath("../../data/example_registries/simple_hierarchy_with_ns");
@@ -38,7 +61,7 @@ fn submit_top_level() -> Result<(), anyhow::Error> {
     let registry = OnDiskRegistry::new(temp_dir.path())?;
 
     let ontology_package = std::fs::read_to_string(tests_filepath(
-        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/topLevel_0_0_1",
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
     ))
     .unwrap();
 
@@ -58,3 +81,229 @@ fn submit_top_level() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+fn names(listings: Vec<PackageListing>) -> Vec<String> {
+    listings
+        .into_iter()
+        .map(|listing| listing.package_name)
+        .collect()
+}
+
+#[test]
+fn list_and_search_packages() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("list_and_search_packages")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let top_level = std::fs::read_to_string(tests_filepath(
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
+    ))?;
+    let with_keywords = top_level.replace(
+        r#"registry:ontologyFormatVersion "v1" ."#,
+        r#"registry:ontologyFormatVersion "v1" ;
+           registry:keyword "maps" , "cities" ;
+           registry:category "Geography" ."#,
+    );
+    registry.submit_package(&with_keywords)?;
+    registry.submit_package(&top_level.replace("top_level", "other_level"))?;
+    registry.submit_package(
+        &with_keywords
+            .replace("top_level", "third_level")
+            .replace(r#""maps" , "#, ""),
+    )?;
+    assert_eq!(registry.package_names()?.len(), 3);
+
+    let top_level_version = registry.get_package_version_metadata(&PackageVersion {
+        package_name: "@namespace/top_level".to_string(),
+        version: "0.0.1".to_string(),
+    })?;
+    assert_eq!(top_level_version.keywords, vec!["cities", "maps"]);
+    assert_eq!(top_level_version.categories, vec!["Geography"]);
+
+    assert_eq!(
+        names(registry.search("MAPS")?),
+        vec!["@namespace/top_level"]
+    );
+    assert_eq!(
+        names(registry.search("level")?),
+        vec![
+            "@namespace/other_level",
+            "@namespace/third_level",
+            "@namespace/top_level"
+        ]
+    );
+
+    let in_geography = PackageFilter {
+        categories: vec!["geography".to_owned()],
+        keywords: vec!["cities".to_owned()],
+        ..PackageFilter::default()
+    };
+    assert_eq!(
+        names(
+            registry
+                .list_packages(&in_geography)?
+                .collect::<Result<_, _>>()?
+        ),
+        vec!["@namespace/third_level", "@namespace/top_level"]
+    );
+    assert_eq!(
+        names(
+            registry
+                .list_packages(&in_geography.page(1, 1))?
+                .collect::<Result<_, _>>()?
+        ),
+        vec!["@namespace/top_level"]
+    );
+    assert!(registry
+        .list_packages(&PackageFilter::default().page(1, 3))?
+        .next()
+        .is_none());
+
+    assert!(!registry.is_yanked(&PackageVersion {
+        package_name: "@namespace/top_level".to_string(),
+        version: "0.0.1".to_string(),
+    })?);
+
+    Ok(())
+}
+
+#[test]
+fn lookups_in_a_corrupt_index_fail() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("lookups_in_a_corrupt_index_fail")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let namespace_dir = temp_dir.path().join("index").join("@namespace");
+    std::fs::create_dir_all(&namespace_dir)?;
+    std::fs::write(namespace_dir.join("top_level.json"), "{ not json")?;
+
+    assert!(registry
+        .all_available_versions_of_a_package("@namespace/top_level".to_string())
+        .is_err());
+    // Index files are only read while listing.
+    assert!(registry
+        .list_packages(&PackageFilter::default())?
+        .any(|listing| listing.is_err()));
+
+    Ok(())
+}
+
+#[test]
+fn lookups_see_changes_of_index_files() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("lookups_see_changes_of_index_files")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let top_level = std::fs::read_to_string(tests_filepath(
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
+    ))?;
+    registry.submit_package(&top_level)?;
+    assert_eq!(
+        registry
+            .all_available_versions_of_a_package("@namespace/top_level".to_string())?
+            .len(),
+        1
+    );
+
+    // Submitting through the registry replaces what it has cached.
+    registry.submit_package(&top_level.replace("\"0.0.1\"", "\"0.0.2\""))?;
+    assert_eq!(
+        registry
+            .all_available_versions_of_a_package("@namespace/top_level".to_string())?
+            .len(),
+        2
+    );
+
+    // So does changing the index file behind its back.
+    let index_file = temp_dir
+        .path()
+        .join("index")
+        .join("@namespace")
+        .join("top_level.json");
+    let index = std::fs::read_to_string(&index_file)?;
+    std::fs::write(&index_file, index.replace("0.0.2", "0.0.30"))?;
+    let versions = registry
+        .all_available_versions_of_a_package("@namespace/top_level".to_string())?
+        .into_iter()
+        .map(|version| version.version.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(versions, vec!["0.0.1", "0.0.30"]);
+
+    Ok(())
+}
+
+#[test]
+fn yank_and_unyank() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("yank_and_unyank")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let top_level = std::fs::read_to_string(tests_filepath(
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
+    ))?;
+    registry.submit_package(&top_level)?;
+    let package_version = PackageVersion {
+        package_name: "@namespace/top_level".to_string(),
+        version: "0.0.1".to_string(),
+    };
+    let index_file = temp_dir
+        .path()
+        .join("index")
+        .join("@namespace")
+        .join("top_level.json");
+    assert!(!std::fs::read_to_string(&index_file)?.contains("yanked"));
+
+    registry.yank(&package_version, true)?;
+    assert!(registry.is_yanked(&package_version)?);
+    assert!(std::fs::read_to_string(&index_file)?.contains("\"yanked\": true"));
+    // Yanked versions can still be retrieved.
+    assert!(registry.retrieve_package(&package_version).is_ok());
+    // Packages of which all versions are yanked are not listed.
+    assert!(registry.search("top_level")?.is_empty());
+
+    registry.yank(&package_version, false)?;
+    assert!(!registry.is_yanked(&package_version)?);
+    assert_eq!(registry.search("top_level")?.len(), 1);
+
+    assert!(registry
+        .yank(
+            &PackageVersion {
+                package_name: "@namespace/top_level".to_string(),
+                version: "0.0.2".to_string(),
+            },
+            true
+        )
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn remove_package_version() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new("remove_package_version")?;
+    let registry = OnDiskRegistry::new(temp_dir.path())?;
+
+    let top_level = std::fs::read_to_string(tests_filepath(
+        "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9",
+    ))?;
+    let first = registry.submit_package(&top_level)?;
+    let second = registry.submit_package(&top_level.replace("\"0.0.1\"", "\"0.0.2\""))?;
+    let package_version = |version: &str| PackageVersion {
+        package_name: "@namespace/top_level".to_string(),
+        version: version.to_string(),
+    };
+    let artifact = |cksum: Option<String>| temp_dir.path().join("artifacts").join(cksum.unwrap());
+
+    registry.remove_package_version(&package_version("0.0.2"))?;
+    assert!(registry
+        .get_package_version_metadata(&package_version("0.0.2"))
+        .is_err());
+    assert!(!artifact(second.cksum).exists());
+    assert!(registry.retrieve_package(&package_version("0.0.1")).is_ok());
+
+    // The index file goes with the last version.
+    registry.remove_package_version(&package_version("0.0.1"))?;
+    assert!(!artifact(first.cksum).exists());
+    assert!(registry.package_names()?.is_empty());
+    assert!(registry
+        .remove_package_version(&package_version("0.0.1"))
+        .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+#![allow(clippy::restriction)]
+mod common;
+
+use crate::common::tests_filepath;
+use plow_package_management::{
+    package::PackageVersion,
+    registry::{
+        http::{field_hash, HttpRegistry, HttpRegistryError},
+        on_disk::OnDiskRegistry,
+        server::RegistryServer,
+        Registry,
+    },
+};
+use tempdir::TempDir;
+
+const TOP_LEVEL_ARTIFACT: &str = "../../data/example_registries/simple_hierarchy_with_ns/artifacts/80956c23bd5e590684dd2af39abedcf99eba74535b702371d52062a9389f46d9";
+
+/// Serves an empty registry in the directory on a local port, returns the url of the server.
+fn serve(directory: &TempDir) -> String {
+    let registry = OnDiskRegistry::new(directory.path()).unwrap();
+    let server = RegistryServer::bind(registry, "127.0.0.1:0")
+        .unwrap()
+        .with_token("secret");
+    let url = server.url();
+    std::thread::spawn(move || server.serve());
+    url
+}
+
+fn top_level() -> PackageVersion {
+    PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: "0.0.1".to_owned(),
+    }
+}
+
+#[test]
+fn submit_sync_and_retrieve_packages() {
+    let directory = TempDir::new("submit_sync_and_retrieve_packages").unwrap();
+    let url = serve(&directory);
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+
+    // Dry runs are checked but not kept.
+    HttpRegistry::new(&url)
+        .with_token("secret")
+        .dry_run(true)
+        .submit_package(&artifact)
+        .unwrap();
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.sync_private_index().unwrap();
+    assert!(registry.package_names().unwrap().is_empty());
+
+    registry.submit_package(&artifact).unwrap();
+    // The registry is persisted in the directory.
+    assert!(OnDiskRegistry::new(directory.path())
+        .unwrap()
+        .get_package_version_metadata(&top_level())
+        .is_ok());
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.sync_private_index().unwrap();
+    let versions = registry
+        .all_available_versions_of_a_package("@namespace/top_level".to_owned())
+        .unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(
+        registry.retrieve_package(&top_level()).unwrap(),
+        artifact.as_bytes()
+    );
+
+    let error = registry.submit_package(&artifact).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HttpRegistryError>(),
+        Some(HttpRegistryError::Rejected(reason)) if reason.contains("already exists")
+    ));
+}
+
+#[test]
+fn yanks_are_synced() {
+    let directory = TempDir::new("yanks_are_synced").unwrap();
+    let url = serve(&directory);
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.submit_package(&artifact).unwrap();
+    registry.yank(&top_level(), true).unwrap();
+
+    let other_registry = HttpRegistry::new(&url).with_token("secret");
+    other_registry.sync_private_index().unwrap();
+    assert!(other_registry.is_yanked(&top_level()).unwrap());
+
+    registry.yank(&top_level(), false).unwrap();
+    other_registry.sync_private_index().unwrap();
+    assert!(!other_registry.is_yanked(&top_level()).unwrap());
+
+    let missing = PackageVersion {
+        package_name: "@namespace/top_level".to_owned(),
+        version: "0.0.2".to_owned(),
+    };
+    assert!(registry.yank(&missing, true).is_err());
+}
+
+#[test]
+fn requests_without_the_token_are_unauthorized() {
+    let directory = TempDir::new("requests_without_the_token_are_unauthorized").unwrap();
+    let url = serve(&directory);
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+
+    assert!(matches!(
+        HttpRegistry::new(&url).sync_private_index(),
+        Err(HttpRegistryError::Unauthorized { .. })
+    ));
+    assert!(HttpRegistry::new(&url)
+        .with_token("wrong")
+        .submit_package(&artifact)
+        .is_err());
+    assert!(OnDiskRegistry::new(directory.path())
+        .unwrap()
+        .package_names()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn artifacts_are_only_served_through_signed_urls() {
+    let directory = TempDir::new("artifacts_are_only_served_through_signed_urls").unwrap();
+    let url = serve(&directory);
+    let artifact = std::fs::read_to_string(tests_filepath(TOP_LEVEL_ARTIFACT)).unwrap();
+    let registry = HttpRegistry::new(&url).with_token("secret");
+    registry.submit_package(&artifact).unwrap();
+    registry.sync_private_index().unwrap();
+    assert_eq!(
+        registry.retrieve_package(&top_level()).unwrap(),
+        artifact.as_bytes()
+    );
+
+    let hash = field_hash("@namespace/top_level", "0.0.1");
+    for artifact_url in [
+        format!("{url}/artifacts/{hash}"),
+        format!("{url}/artifacts/{hash}?signature=00ff"),
+        format!("{url}/artifacts/{hash}?signature=not-hex"),
+    ] {
+        let response = reqwest::blocking::get(&artifact_url).unwrap();
+        assert_eq!(response.status().as_u16(), 403);
+        // Errors have the same fields as the ones of the reference backend.
+        let body = response.json::<serde_json::Value>().unwrap();
+        assert_eq!(body["status"], "error");
+        assert_eq!(body["code"], 403);
+        assert_eq!(body["error"], "Forbidden");
+        assert!(body["timestamp"].is_string());
+    }
+}
+
+#[test]
+fn submissions_which_are_not_utf8_fail() {
+    let directory = TempDir::new("submissions_which_are_not_utf8_fail").unwrap();
+    let url = serve(&directory);
+
+    let form = reqwest::blocking::multipart::Form::new().part(
+        "field",
+        reqwest::blocking::multipart::Part::bytes(vec![0xFF, 0xFE, 0xFD]).file_name("field.ttl"),
+    );
+    let response = reqwest::blocking::Client::new()
+        .post(format!("{url}/v1/field/submit"))
+        .header("Authorization", "Basic secret")
+        .multipart(form)
+        .send()
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+    let body = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(body["status"], "fail");
+    assert!(body["data"]["failures"][0]
+        .as_str()
+        .unwrap()
+        .contains("UTF-8"));
+}
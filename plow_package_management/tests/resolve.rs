@@ -1,9 +1,13 @@
 #![allow(clippy::restriction, clippy::useless_vec)]
 
 use plow_package_management::{
-    package::OrganizationToResolveFor,
+    lock::PackageInLockFile,
+    package::{OrganizationToResolveFor, PackageToUpdate, PackageVersionWithRegistryMetadata},
     registry::Registry,
-    resolve::{Dependency, Resolver, VersionRequestResolver},
+    resolve::{
+        error::ResolverError, report::ConflictingRequirement, Dependency, Resolver,
+        VersionRequestResolver,
+    },
     version::semver,
     version::SemanticVersion,
 };
@@ -45,6 +49,10 @@ macro_rules! package {
             dependencies: $deps,
             cksum: None,
             private: false,
+            source: None,
+            keywords: vec![],
+            categories: vec![],
+            yanked: false,
         }
     };
 }
@@ -78,9 +86,28 @@ macro_rules! registry {
     };
 }
 
+// A package in a lock file.
+macro_rules! locked {
+    ($name: expr, $version: literal, [$($dep: expr),*]) => {
+        PackageInLockFile {
+            root: false,
+            name: $name.to_string(),
+            version: $version.to_string(),
+            source: None,
+            ontology_iri: None,
+            cksum: None,
+            dependencies: vec![$($dep.to_string()),*],
+        }
+    };
+}
+
 // Runs resolution.
+// Optionally with a lock file and a package to update.
 macro_rules! resolve_org {
     ($deps: expr, $registry: expr) => {
+        resolve_org!($deps, $registry, None, None)
+    };
+    ($deps: expr, $registry: expr, $locked: expr, $update: expr) => {
         Into::<VersionRequestResolver>::into(&$registry as &dyn Registry)
             .resolve_dependencies(
                 OrganizationToResolveFor {
@@ -88,7 +115,8 @@ macro_rules! resolve_org {
                     package_version: SemanticVersion::default(),
                     dependencies: $deps,
                 },
-                None,
+                $locked,
+                $update,
             )
             .expect("Unable to resolve dependencies")
             .packages
@@ -114,6 +142,7 @@ macro_rules! fail_to_resolve_org {
                         package_version: SemanticVersion::default(),
                         dependencies: $deps,
                     },
+                    None,
                     None
                 )
                 .is_err()
@@ -124,10 +153,10 @@ macro_rules! fail_to_resolve_org {
 // Get a solved package by name from the resolution results.
 macro_rules! solved {
     ($package_version_map: expr, $name: literal) => {
-        *$package_version_map.get($name).unwrap()
+        $package_version_map.get($name).unwrap().clone()
     };
     ($package_version_map: expr, $name: expr) => {
-        *$package_version_map.get($name).unwrap()
+        $package_version_map.get($name).unwrap().clone()
     };
 }
 
@@ -455,3 +484,446 @@ fn resolutions_with_version_request_pairs() {
     assert_eq!(solved!(solution, BERLIN), semver!("0.2.0"));
     assert_eq!(solved!(solution, HAMBURG), semver!("1.4.1"));
 }
+
+#[test]
+fn resolutions_with_pre_releases() {
+    let mut registry = plow_package_management::registry::in_memory::InMemoryRegistry::default();
+    registry!(
+        registry,
+        package!(name_and_version!(HAMBURG, "1.0.0"), deps![]),
+        package!(name_and_version!(HAMBURG, "1.1.0"), deps![]),
+        package!(name_and_version!(HAMBURG, "2.0.0-beta.1"), deps![]),
+        package!(name_and_version!(HAMBURG, "2.0.0-beta.2"), deps![]),
+        package!(name_and_version!(HAMBURG, "2.0.0-rc.1+001"), deps![])
+    );
+
+    // Pre-releases are not picked without an explicit opt in.
+    let solution = resolve_org!(deps![dep!(HAMBURG, ">=1.0.0")], registry);
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.1.0"));
+
+    // Requesting a pre-release of the same `major.minor.patch` opts in.
+    let solution = resolve_org!(deps![dep!(HAMBURG, "^2.0.0-beta.1")], registry);
+    assert_eq!(solved!(solution, HAMBURG), semver!("2.0.0-rc.1"));
+
+    let solution = resolve_org!(
+        deps![dep!(HAMBURG, ">=2.0.0-beta.1, <2.0.0-rc.1")],
+        registry
+    );
+    assert_eq!(solved!(solution, HAMBURG), semver!("2.0.0-beta.2"));
+
+    // Opting into a pre-release of another version does not make these eligible.
+    fail_to_resolve_org!(deps![dep!(HAMBURG, "^2.0.0")], registry);
+
+    // Only the requirement which names a pre-release opts into it, the other requirements on the package do not.
+    registry!(
+        registry,
+        package!(
+            name_and_version!(BERLIN, "1.0.0"),
+            deps![dep!(HAMBURG, ">=1.0.0")]
+        )
+    );
+    fail_to_resolve_org!(
+        deps![dep!(HAMBURG, "^2.0.0-beta.1"), dep!(BERLIN, "=1.0.0")],
+        registry
+    );
+    let solution = resolve_org!(
+        deps![dep!(HAMBURG, ">=1.0.0-beta.1"), dep!(BERLIN, "=1.0.0")],
+        registry
+    );
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.1.0"));
+}
+
+#[test]
+fn resolutions_with_selective_updates() {
+    let mut registry = plow_package_management::registry::in_memory::InMemoryRegistry::default();
+    registry!(
+        registry,
+        package!(
+            name_and_version!(BERLIN, "1.0.0"),
+            deps![dep!(FRANKFURT, "^1.0")]
+        ),
+        package!(
+            name_and_version!(BERLIN, "1.1.0"),
+            deps![dep!(FRANKFURT, "^1.0")]
+        ),
+        package!(name_and_version!(FRANKFURT, "1.0.0"), deps![]),
+        package!(name_and_version!(FRANKFURT, "1.1.0"), deps![]),
+        package!(name_and_version!(HAMBURG, "1.0.0"), deps![]),
+        package!(name_and_version!(HAMBURG, "1.1.0"), deps![]),
+        package!(name_and_version!(HAMBURG, "1.2.0"), deps![]),
+        package!(
+            name_and_version!(MAINZ, "1.0.0"),
+            deps![dep!(HAMBURG, "^1.0")]
+        )
+    );
+    let lock_file = vec![
+        locked!(BERLIN, "1.0.0", [FRANKFURT]),
+        locked!(FRANKFURT, "1.0.0", []),
+        locked!(HAMBURG, "1.0.0", []),
+    ];
+
+    // Everything stays pinned.
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, "^1.0")],
+        registry,
+        Some(&lock_file),
+        None
+    );
+    assert_eq!(solved!(solution, BERLIN), semver!("1.0.0"));
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.0.0"));
+
+    // The updated package and its dependencies are unlocked.
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, "^1.0")],
+        registry,
+        Some(&lock_file),
+        Some(&PackageToUpdate {
+            package_name: BERLIN.to_owned(),
+            precise_version: None,
+        })
+    );
+    assert_eq!(solved!(solution, BERLIN), semver!("1.1.0"));
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.1.0"));
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.0.0"));
+
+    // A precise update picks exactly the requested version.
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, "^1.0")],
+        registry,
+        Some(&lock_file),
+        Some(&PackageToUpdate {
+            package_name: HAMBURG.to_owned(),
+            precise_version: Some(semver!("1.1.0")),
+        })
+    );
+    assert_eq!(solved!(solution, BERLIN), semver!("1.0.0"));
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.1.0"));
+
+    // A locked version which does not satisfy the requirement anymore means that the lock file is out of date.
+    assert!(matches!(
+        Into::<VersionRequestResolver>::into(&registry as &dyn Registry).resolve_dependencies(
+            OrganizationToResolveFor {
+                package_name: "@root/root".to_owned(),
+                package_version: SemanticVersion::default(),
+                dependencies: deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, ">=1.1.0")],
+            },
+            Some(&lock_file),
+            None,
+        ),
+        Err(ResolverError::LockFileOutOfDate { package, .. }) if package == HAMBURG
+    ));
+
+    // Updating another package does not unpin it.
+    assert!(matches!(
+        Into::<VersionRequestResolver>::into(&registry as &dyn Registry).resolve_dependencies(
+            OrganizationToResolveFor {
+                package_name: "@root/root".to_owned(),
+                package_version: SemanticVersion::default(),
+                dependencies: deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, ">=1.1.0")],
+            },
+            Some(&lock_file),
+            Some(&PackageToUpdate {
+                package_name: BERLIN.to_owned(),
+                precise_version: None,
+            }),
+        ),
+        Err(ResolverError::LockFileOutOfDate { package, .. }) if package == HAMBURG
+    ));
+
+    // Updating the package itself resolves it again, the other packages stay pinned.
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0"), dep!(HAMBURG, ">=1.1.0")],
+        registry,
+        Some(&lock_file),
+        Some(&PackageToUpdate {
+            package_name: HAMBURG.to_owned(),
+            precise_version: None,
+        })
+    );
+    assert_eq!(solved!(solution, BERLIN), semver!("1.0.0"));
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.2.0"));
+
+    // Locked packages which nothing depends on anymore are not pinned.
+    let solution = resolve_org!(deps![dep!(MAINZ, "^1.0")], registry, Some(&lock_file), None);
+    assert_eq!(solved!(solution, MAINZ), semver!("1.0.0"));
+    assert_eq!(solved!(solution, HAMBURG), semver!("1.2.0"));
+
+    // Packages which are not in the lock file can not be updated.
+    assert!(
+        Into::<VersionRequestResolver>::into(&registry as &dyn Registry)
+            .resolve_dependencies(
+                OrganizationToResolveFor {
+                    package_name: "@root/root".to_owned(),
+                    package_version: SemanticVersion::default(),
+                    dependencies: deps![dep!(BERLIN, "^1.0")],
+                },
+                Some(&lock_file),
+                Some(&PackageToUpdate {
+                    package_name: MAINZ.to_owned(),
+                    precise_version: None,
+                }),
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn resolutions_skip_yanked_versions() {
+    let yanked = |mut package: PackageVersionWithRegistryMetadata| {
+        package.yanked = true;
+        package
+    };
+    let mut registry = plow_package_management::registry::in_memory::InMemoryRegistry::default();
+    registry!(
+        registry,
+        package!(
+            name_and_version!(BERLIN, "1.0.0"),
+            deps![dep!(FRANKFURT, "^1.0")]
+        ),
+        package!(name_and_version!(FRANKFURT, "1.0.0"), deps![]),
+        yanked(package!(name_and_version!(FRANKFURT, "1.1.0"), deps![]))
+    );
+
+    // The latest version is yanked.
+    let solution = resolve_org!(deps![dep!(BERLIN, "^1.0")], registry);
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+    fail_to_resolve_org!(deps![dep!(FRANKFURT, "=1.1.0")], registry);
+
+    // Unless it is locked already.
+    let lock_file = vec![
+        locked!(BERLIN, "1.0.0", [FRANKFURT]),
+        locked!(FRANKFURT, "1.1.0", []),
+    ];
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0")],
+        registry,
+        Some(&lock_file),
+        None
+    );
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.1.0"));
+
+    // Updating moves away from it.
+    let solution = resolve_org!(
+        deps![dep!(BERLIN, "^1.0")],
+        registry,
+        Some(&lock_file),
+        Some(&PackageToUpdate {
+            package_name: FRANKFURT.to_owned(),
+            precise_version: None,
+        })
+    );
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+}
+
+#[test]
+fn resolutions_which_fail_explain_the_conflict() {
+    let mut registry = plow_package_management::registry::in_memory::InMemoryRegistry::default();
+    registry!(
+        registry,
+        package!(
+            name_and_version!(BERLIN, "1.2.0"),
+            deps![dep!(MAINZ, "^2.0.0")]
+        ),
+        package!(
+            name_and_version!(FRANKFURT, "1.0.0"),
+            deps![dep!(MAINZ, "^1.0.0")]
+        ),
+        package!(name_and_version!(MAINZ, "1.0.0"), deps![]),
+        package!(name_and_version!(MAINZ, "2.0.0"), deps![])
+    );
+
+    let error = Into::<VersionRequestResolver>::into(&registry as &dyn Registry)
+        .resolve_dependencies(
+            OrganizationToResolveFor {
+                package_name: "@root/root".to_owned(),
+                package_version: SemanticVersion::default(),
+                dependencies: deps![dep!(BERLIN, "=1.2.0"), dep!(FRANKFURT, "=1.0.0")],
+            },
+            None,
+            None,
+        )
+        .unwrap_err();
+
+    let report = match error {
+        ResolverError::Conflict(report) => report,
+        other => panic!("Expected a conflict report, got {other}"),
+    };
+
+    assert!(report.requirements.contains(&ConflictingRequirement {
+        dependent: BERLIN.to_owned(),
+        dependent_versions: "1.2.0".to_owned(),
+        dependency: MAINZ.to_owned(),
+        requirement: "^2.0.0".to_owned(),
+    }));
+    assert!(report.requirements.contains(&ConflictingRequirement {
+        dependent: FRANKFURT.to_owned(),
+        dependent_versions: "1.0.0".to_owned(),
+        dependency: MAINZ.to_owned(),
+        requirement: "^1.0.0".to_owned(),
+    }));
+
+    let rendered = report.to_string();
+    assert!(rendered.contains("@cities/Berlin 1.2.0 requires @cities/Mainz ^2.0.0"));
+    assert!(rendered.contains("@cities/Frankfurt 1.0.0 requires @cities/Mainz ^1.0.0"));
+    assert!(rendered.contains("\n  "));
+}
+
+#[test]
+fn resolutions_with_multiple_major_versions() {
+    let mut registry = plow_package_management::registry::in_memory::InMemoryRegistry::default();
+    registry!(
+        registry,
+        package!(
+            name_and_version!(BERLIN, "1.2.0"),
+            deps![dep!(MAINZ, "^2.0.0")]
+        ),
+        package!(
+            name_and_version!(FRANKFURT, "1.0.0"),
+            deps![dep!(MAINZ, "^1.0.0")]
+        ),
+        package!(
+            name_and_version!(HAMBURG, "1.0.0"),
+            deps![dep!(MAINZ, "=1.0.0")]
+        ),
+        package!(name_and_version!(MAINZ, "1.0.0"), deps![]),
+        package!(name_and_version!(MAINZ, "1.1.0"), deps![]),
+        package!(name_and_version!(MAINZ, "2.0.0"), deps![])
+    );
+
+    let resolve = |dependencies, locked: Option<&[PackageInLockFile]>| {
+        Into::<VersionRequestResolver>::into(&registry as &dyn Registry)
+            .allow_multiple_major_versions(true)
+            .resolve_dependencies(
+                OrganizationToResolveFor {
+                    package_name: "@root/root".to_owned(),
+                    package_version: SemanticVersion::default(),
+                    dependencies,
+                },
+                locked,
+                None,
+            )
+            .map(|package_set| {
+                package_set
+                    .packages
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    // Incompatible major versions are resolved side by side.
+    let solution = resolve(
+        deps![dep!(BERLIN, "=1.2.0"), dep!(FRANKFURT, "=1.0.0")],
+        None,
+    )
+    .unwrap();
+    assert!(solution.contains(&format!("{MAINZ} 1.1.0")));
+    assert!(solution.contains(&format!("{MAINZ} 2.0.0")));
+    assert_eq!(solution.len(), 4);
+
+    // Still only one version within a major version.
+    let solution = resolve(
+        deps![dep!(FRANKFURT, "=1.0.0"), dep!(HAMBURG, "=1.0.0")],
+        None,
+    )
+    .unwrap();
+    assert!(solution.contains(&format!("{MAINZ} 1.0.0")));
+    assert!(!solution.contains(&format!("{MAINZ} 1.1.0")));
+
+    // Locked versions are kept per major version.
+    let lock_file = vec![
+        locked!(BERLIN, "1.2.0", [format!("{MAINZ} 2.0.0")]),
+        locked!(FRANKFURT, "1.0.0", [format!("{MAINZ} 1.0.0")]),
+        locked!(MAINZ, "1.0.0", []),
+        locked!(MAINZ, "2.0.0", []),
+    ];
+    let solution = resolve(
+        deps![dep!(BERLIN, "=1.2.0"), dep!(FRANKFURT, "=1.0.0")],
+        Some(&lock_file),
+    )
+    .unwrap();
+    assert!(solution.contains(&format!("{MAINZ} 1.0.0")));
+    assert!(solution.contains(&format!("{MAINZ} 2.0.0")));
+
+    // Without opting in it is a conflict.
+    fail_to_resolve_org!(
+        deps![dep!(BERLIN, "=1.2.0"), dep!(FRANKFURT, "=1.0.0")],
+        registry
+    );
+}
+
+#[test]
+fn resolutions_from_merged_registries() {
+    use plow_package_management::registry::{in_memory::InMemoryRegistry, merged::MergedRegistry};
+
+    let mut public = InMemoryRegistry::default();
+    registry!(
+        public,
+        package!(
+            name_and_version!(BERLIN, "1.0.0"),
+            deps![dep!(FRANKFURT, "^1.0.0")]
+        ),
+        package!(name_and_version!(FRANKFURT, "1.0.0"), deps![]),
+        package!(name_and_version!(MAINZ, "1.0.0"), deps![])
+    );
+    let mut internal = InMemoryRegistry::default();
+    registry!(
+        internal,
+        package!(
+            name_and_version!(HAMBURG, "1.0.0"),
+            deps![dep!(format!("internal:{MAINZ}"), "^1.0.0")]
+        ),
+        package!(name_and_version!(FRANKFURT, "1.1.0"), deps![]),
+        package!(name_and_version!(MAINZ, "1.0.0"), deps![]),
+        package!(name_and_version!(MAINZ, "1.2.0"), deps![])
+    );
+    let registry =
+        MergedRegistry::new(Box::new(public)).with_registry("internal", Box::new(internal));
+
+    // Unqualified dependencies are only looked up in the default registry, even if a named one has newer versions.
+    let solution = resolve_org!(deps![dep!(BERLIN, "=1.0.0")], registry);
+    assert_eq!(solved!(solution, FRANKFURT), semver!("1.0.0"));
+    let solution = resolve_org!(deps![dep!(MAINZ, "^1.0.0")], registry);
+    assert_eq!(solved!(solution, MAINZ), semver!("1.0.0"));
+    fail_to_resolve_org!(deps![dep!(HAMBURG, "=1.0.0")], registry);
+
+    // Qualified ones only in the registry they are qualified with.
+    let solution = resolve_org!(
+        deps![dep!(format!("internal:{HAMBURG}"), "=1.0.0")],
+        registry
+    );
+    assert_eq!(solved!(solution, MAINZ), semver!("1.2.0"));
+    assert_eq!(
+        registry.registry_of(&name_and_version!(MAINZ, "1.2.0")),
+        Some("internal".to_owned())
+    );
+    assert_eq!(
+        registry.registry_of(&name_and_version!(BERLIN, "1.0.0")),
+        None
+    );
+    fail_to_resolve_org!(
+        deps![dep!(format!("internal:{BERLIN}"), "=1.0.0")],
+        registry
+    );
+
+    // A registry which is not composed of named registries has none to look qualified dependencies up in.
+    let mut unnamed = InMemoryRegistry::default();
+    registry!(
+        unnamed,
+        package!(name_and_version!(MAINZ, "1.0.0"), deps![])
+    );
+    assert!(unnamed
+        .all_available_versions_of_a_package_in_registry("internal", MAINZ.to_owned())
+        .is_err());
+    fail_to_resolve_org!(deps![dep!(format!("internal:{MAINZ}"), "^1.0.0")], unnamed);
+
+    // The qualifier is kept when a dependency is written again.
+    let dependency: Dependency<SemanticVersion> = dep!(format!("internal:{MAINZ}"), "^1.0.0");
+    assert_eq!(dependency.full_name, MAINZ);
+    assert_eq!(dependency.registry.as_deref(), Some("internal"));
+    assert_eq!(dependency.to_string(), format!("internal:{MAINZ} ^1.0.0"));
+    assert!(Dependency::<SemanticVersion>::try_from("in.ternal:@cities/Mainz ^1.0.0").is_err());
+}
@@ -113,6 +113,55 @@ fn version_to_range_wildcard() {
     assert_eq!(range!("1.x.x"), Range::between(semver!("1"), semver!("2")));
 }
 
+#[test]
+fn version_to_range_pre_release() {
+    assert_eq!(
+        range!("=1.0.0-beta.1"),
+        Range::exact(semver!("1.0.0-beta.1"))
+    );
+    assert_eq!(
+        range!("^1.0.0-beta.1"),
+        Range::between(semver!("1.0.0-beta.1"), semver!("2.0.0"))
+    );
+    assert_eq!(
+        range!("^0.0.1-beta.1"),
+        Range::between(semver!("0.0.1-beta.1"), semver!("0.0.2"))
+    );
+    assert!(!range!(">1.0.0-beta.1").contains(&semver!("1.0.0-beta.1")));
+    assert!(range!(">1.0.0-beta.1").contains(&semver!("1.0.0-beta.1.0")));
+    assert!(range!("<=1.0.0-beta.1").contains(&semver!("1.0.0-beta.1")));
+    assert!(!range!("<=1.0.0-beta.1").contains(&semver!("1.0.0-beta.2")));
+}
+
+#[test]
+fn pre_release_and_build_metadata() {
+    let pre_release = semver!("1.2.0-rc.1+build.5");
+    assert!(pre_release.is_pre_release());
+    assert_eq!(pre_release.pre_release().as_str(), "rc.1");
+    assert_eq!(pre_release.build_metadata().as_str(), "build.5");
+    assert_eq!(pre_release.to_string(), "1.2.0-rc.1+build.5");
+
+    // Build metadata does not take part in precedence.
+    assert_eq!(pre_release, semver!("1.2.0-rc.1"));
+
+    // https://semver.org/#spec-item-11
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+    ];
+    for pair in ordered.windows(2) {
+        let lower = SemanticVersion::from_str(pair[0]).unwrap();
+        let higher = SemanticVersion::from_str(pair[1]).unwrap();
+        assert!(lower < higher);
+    }
+}
+
 #[test]
 fn test_semver_completeness() {
     let v1 = semver_completeness(&semver::VersionReq::parse("=1").unwrap().comparators[0]);